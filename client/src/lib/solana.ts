@@ -2,8 +2,8 @@ import { Connection, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from '@solana/
 import { Program, AnchorProvider, web3, BN } from '@project-serum/anchor';
 import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, Token } from '@solana/spl-token';
 
-// Program ID (replace with your deployed program ID)
-export const FREELANCE_ESCROW_PROGRAM_ID = new PublicKey("Your_Program_ID");
+// Program ID of the deployed freelance_escrow program
+export const FREELANCE_ESCROW_PROGRAM_ID = new PublicKey("44DrUbm5vGxnLinZP28PYz2zegzwVG6xLTQYaaoKBBx9");
 
 // USDC mint address on devnet
 export const USDC_MINT = new PublicKey("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
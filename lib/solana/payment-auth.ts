@@ -13,7 +13,7 @@ import {
 import { Program, AnchorProvider, web3, BN, Idl } from '@project-serum/anchor';
 
 // Program ID for the payment authorization program
-export const PAYMENT_AUTH_PROGRAM_ID = new PublicKey('SmartF1oPaymentAuth1111111111111111111111');
+export const PAYMENT_AUTH_PROGRAM_ID = new PublicKey('vM3ni7Fyg5D478g48dmVVVNuF1sFQ6RMYGMhg91iJmw');
 
 // USDC mint address (devnet)
 export const USDC_MINT = new PublicKey('4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU');
@@ -0,0 +1,314 @@
+//! `auth` subcommands for payment_auth.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use smartflo_client::authorization::{self, AuthorizationKeys};
+use smartflo_client::accounts::{decode, AuthConfig, KycAttestation, SanctionEntry};
+use smartflo_client::{pda, ContractId};
+use solana_sdk::signer::Signer;
+
+use crate::escrow;
+use crate::output;
+use crate::runtime::Runtime;
+
+#[derive(Subcommand)]
+pub enum AuthCommand {
+    /// Create a payment authorization; signed by the client
+    Create {
+        #[arg(long)]
+        id: ContractId,
+        /// Freelancer wallet
+        #[arg(long)]
+        freelancer: Pubkey,
+        /// Per-milestone limit in token base units
+        #[arg(long)]
+        max_per_milestone: u64,
+        /// Total authorized amount in token base units
+        #[arg(long)]
+        total: u64,
+    },
+    /// Change the per-milestone limit or authorize more funds; signed by the client
+    Update {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        max_per_milestone: Option<u64>,
+        /// Amount to add to the total authorized
+        #[arg(long)]
+        add: Option<u64>,
+    },
+    /// Let the contract's escrow pull milestone funding; signed by the client
+    LinkEscrow {
+        #[arg(long)]
+        id: ContractId,
+    },
+    /// Revoke the authorization; signed by the client
+    Revoke {
+        #[arg(long)]
+        id: ContractId,
+    },
+    /// Require a second key on payments above `threshold`; signed by the client and the co-signer
+    SetCoSigner {
+        #[arg(long)]
+        id: ContractId,
+        /// Keypair path of the co-signer
+        #[arg(long)]
+        co_signer_keypair: String,
+        /// Payments above this amount in token base units need the co-signer
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// Replace the co-signer or its threshold; signed by the client and the current co-signer
+    UpdateCoSigner {
+        #[arg(long)]
+        id: ContractId,
+        /// Keypair path of the current co-signer
+        #[arg(long)]
+        co_signer_keypair: String,
+        #[arg(long)]
+        new_co_signer: Pubkey,
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// Create the program config; signed by the upgrade authority
+    InitConfig {
+        #[arg(long)]
+        admin: Pubkey,
+        /// Comma-separated keys allowed to freeze authorizations
+        #[arg(long, value_delimiter = ',')]
+        freeze_admins: Vec<Pubkey>,
+        /// Key allowed to maintain the sanctions blocklist
+        #[arg(long)]
+        compliance_authority: Pubkey,
+        /// Seconds a queued config change waits before it can be executed
+        #[arg(long)]
+        timelock_delay: i64,
+    },
+    /// Print the AuthConfig account
+    Config,
+    /// Add a wallet to the sanctions blocklist; signed by the compliance authority
+    Sanction {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+    /// Remove a wallet from the sanctions blocklist; signed by the compliance authority
+    Unsanction {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+    /// Print whether a wallet is on the sanctions blocklist
+    Sanctioned {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+    /// Attest or renew a wallet's identity verification; signed by the compliance authority
+    KycIssue {
+        #[arg(long)]
+        wallet: Pubkey,
+        /// Verification level
+        #[arg(long)]
+        level: u8,
+        /// Unix timestamp the attestation expires at
+        #[arg(long)]
+        expires_at: i64,
+    },
+    /// Revoke a wallet's KYC attestation; signed by the compliance authority
+    KycRevoke {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+    /// Print a wallet's KycAttestation account
+    Kyc {
+        #[arg(long)]
+        wallet: Pubkey,
+    },
+    /// Freeze an authorization; signed by a freeze admin
+    Freeze {
+        #[arg(long)]
+        id: ContractId,
+        /// Client that owns the authorization
+        #[arg(long)]
+        client: Pubkey,
+    },
+    /// Print the PaymentAuthorization account
+    Show {
+        #[arg(long)]
+        id: ContractId,
+        /// Client that owns the authorization; defaults to the configured keypair
+        #[arg(long)]
+        client: Option<Pubkey>,
+    },
+}
+
+/// Keys of an existing authorization owned by `client`
+fn existing_keys(runtime: &Runtime, client: Pubkey, contract_id: ContractId) -> Result<AuthorizationKeys> {
+    let (_, auth) = runtime.fetch_authorization(&client, contract_id)?;
+    Ok(AuthorizationKeys::new(contract_id, client, auth.freelancer))
+}
+
+pub fn run(runtime: &Runtime, command: AuthCommand) -> Result<()> {
+    match command {
+        AuthCommand::Create {
+            id,
+            freelancer,
+            max_per_milestone,
+            total,
+        } => {
+            let signer = runtime.signer()?;
+            let mut keys = AuthorizationKeys::new(id, signer.pubkey(), freelancer);
+            keys.payer = Some(runtime.rent_payer(&signer)?);
+            runtime.send(
+                &[authorization::create_payment_authorization(&keys, max_per_milestone, total)],
+                &signer,
+            )
+        }
+        AuthCommand::Update {
+            id,
+            max_per_milestone,
+            add,
+        } => {
+            let signer = runtime.signer()?;
+            let keys = existing_keys(runtime, signer.pubkey(), id)?;
+            runtime.send(
+                &[authorization::update_authorization(&keys, max_per_milestone, add)],
+                &signer,
+            )
+        }
+        AuthCommand::LinkEscrow { id } => {
+            let signer = runtime.signer()?;
+            let keys = existing_keys(runtime, signer.pubkey(), id)?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let escrow_token_account = escrow::keys_for(runtime, id, &contract)?.escrow_token_account();
+            runtime.send(
+                &[authorization::link_escrow(&keys, escrow_token_account, &runtime.mint()?)],
+                &signer,
+            )
+        }
+        AuthCommand::Revoke { id } => {
+            let signer = runtime.signer()?;
+            let keys = existing_keys(runtime, signer.pubkey(), id)?;
+            runtime.send(&[authorization::revoke_authorization(&keys)], &signer)
+        }
+        AuthCommand::SetCoSigner {
+            id,
+            co_signer_keypair,
+            threshold,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = runtime.keypair(&co_signer_keypair)?;
+            let keys = existing_keys(runtime, signer.pubkey(), id)?;
+            runtime.send_with(
+                &[authorization::set_co_signer(&keys, co_signer.pubkey(), threshold)],
+                &signer,
+                &[&co_signer],
+            )
+        }
+        AuthCommand::UpdateCoSigner {
+            id,
+            co_signer_keypair,
+            new_co_signer,
+            threshold,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = runtime.keypair(&co_signer_keypair)?;
+            let keys = existing_keys(runtime, signer.pubkey(), id)?;
+            runtime.send_with(
+                &[authorization::update_co_signer(&keys, co_signer.pubkey(), new_co_signer, threshold)],
+                &signer,
+                &[&co_signer],
+            )
+        }
+        AuthCommand::InitConfig {
+            admin,
+            freeze_admins,
+            compliance_authority,
+            timelock_delay,
+        } => {
+            let signer = runtime.signer()?;
+            runtime.send(
+                &[authorization::initialize_config(
+                    signer.pubkey(),
+                    admin,
+                    freeze_admins,
+                    compliance_authority,
+                    timelock_delay,
+                )],
+                &signer,
+            )
+        }
+        AuthCommand::Config => {
+            let address = pda::auth_config().0;
+            let config: AuthConfig = runtime.fetch(&address)?;
+            runtime.output.print(&output::auth_config(&address, &config));
+            Ok(())
+        }
+        AuthCommand::Sanction { wallet } => {
+            let signer = runtime.signer()?;
+            let payer = runtime.rent_payer(&signer)?;
+            runtime.send(&[authorization::add_sanctioned_wallet(signer.pubkey(), payer, wallet)], &signer)
+        }
+        AuthCommand::Unsanction { wallet } => {
+            let signer = runtime.signer()?;
+            let entry: SanctionEntry = runtime.fetch(&pda::sanction_entry(&wallet).0)?;
+            runtime.send(
+                &[authorization::remove_sanctioned_wallet(signer.pubkey(), wallet, entry.rent_payer)],
+                &signer,
+            )
+        }
+        AuthCommand::Sanctioned { wallet } => {
+            let address = pda::sanction_entry(&wallet).0;
+            // The entry only exists while the wallet is blocklisted
+            let account = runtime
+                .rpc
+                .get_account_with_commitment(&address, runtime.rpc.commitment())?
+                .value;
+            let entry: Option<SanctionEntry> = account
+                .map(|account| decode(&account.data))
+                .transpose()
+                .map_err(|err| anyhow!("decoding account {address}: {err}"))?;
+            runtime.output.print(&output::sanction_entry(&wallet, &address, entry.as_ref()));
+            Ok(())
+        }
+        AuthCommand::KycIssue {
+            wallet,
+            level,
+            expires_at,
+        } => {
+            let signer = runtime.signer()?;
+            let payer = runtime.rent_payer(&signer)?;
+            runtime.send(
+                &[authorization::issue_kyc_attestation(signer.pubkey(), payer, wallet, level, expires_at)],
+                &signer,
+            )
+        }
+        AuthCommand::KycRevoke { wallet } => {
+            let signer = runtime.signer()?;
+            let attestation: KycAttestation = runtime.fetch(&pda::kyc_attestation(&wallet).0)?;
+            runtime.send(
+                &[authorization::revoke_kyc_attestation(signer.pubkey(), wallet, attestation.rent_payer)],
+                &signer,
+            )
+        }
+        AuthCommand::Kyc { wallet } => {
+            let address = pda::kyc_attestation(&wallet).0;
+            let attestation: KycAttestation = runtime.fetch(&address)?;
+            runtime.output.print(&output::kyc_attestation(&address, &attestation));
+            Ok(())
+        }
+        AuthCommand::Freeze { id, client } => {
+            let signer = runtime.signer()?;
+            let keys = existing_keys(runtime, client, id)?;
+            runtime.send(&[authorization::freeze_authorization(&keys, signer.pubkey())], &signer)
+        }
+        AuthCommand::Show { id, client } => {
+            let client = match client {
+                Some(client) => client,
+                None => runtime.signer()?.pubkey(),
+            };
+            let (address, auth) = runtime.fetch_authorization(&client, id)?;
+            runtime.output.print(&output::payment_authorization(&address, &auth));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Keypair, cluster and mint settings read from `~/.config/smartflo/config.toml`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// USDC on mainnet-beta
+const DEFAULT_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// RPC URL or one of `localnet`, `devnet`, `testnet`, `mainnet-beta`
+    pub cluster: String,
+    /// Path to the signing keypair
+    pub keypair: String,
+    /// Token mint used for escrow and authorization transfers
+    pub mint: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cluster: "devnet".to_string(),
+            keypair: "~/.config/solana/id.json".to_string(),
+            mint: DEFAULT_MINT.to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_default()
+            .join(".config")
+            .join("smartflo")
+            .join("config.toml")
+    }
+
+    /// Load the config file, falling back to defaults when it does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("reading config {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("parsing config {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("writing config {}", path.display()))
+    }
+
+    pub fn rpc_url(&self) -> String {
+        match self.cluster.as_str() {
+            "localnet" | "localhost" => "http://127.0.0.1:8899".to_string(),
+            "devnet" => "https://api.devnet.solana.com".to_string(),
+            "testnet" => "https://api.testnet.solana.com".to_string(),
+            "mainnet-beta" | "mainnet" => "https://api.mainnet-beta.solana.com".to_string(),
+            url => url.to_string(),
+        }
+    }
+
+    pub fn keypair_path(&self) -> PathBuf {
+        match self.keypair.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(&self.keypair),
+        }
+    }
+}
@@ -0,0 +1,512 @@
+//! `contract`, `milestone` and `program` subcommands for freelance_escrow.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use anchor_spl::associated_token::get_associated_token_address;
+use clap::Subcommand;
+use freelance_escrow::{Deliverable, EscrowConfig, FreelanceContract, Milestone, PayeeSplit, BPS_DENOMINATOR};
+use smartflo_client::escrow::{self, ContractKeys, ReleaseOptions};
+use smartflo_client::{pda, ContractId};
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::output;
+use crate::runtime::Runtime;
+
+#[derive(Subcommand)]
+pub enum ContractCommand {
+    /// Create a milestone contract; signed by the freelancer
+    Create {
+        #[arg(long)]
+        id: ContractId,
+        /// Client wallet
+        #[arg(long)]
+        client: Pubkey,
+        /// Total contract amount in token base units
+        #[arg(long)]
+        total: u64,
+        #[arg(long)]
+        milestones: u8,
+    },
+    /// Deposit funds into escrow; signed by the client
+    Fund {
+        #[arg(long)]
+        id: ContractId,
+        /// Amount in token base units
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Top up escrow for the active milestone from the client's linked payment authorization
+    FundFromAuth {
+        #[arg(long)]
+        id: ContractId,
+    },
+    /// Dispute the contract and refund the remaining escrow; signed by the client
+    Dispute {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        reason: String,
+        /// Keypair path of the contract's co-signer, for refunds above its threshold
+        #[arg(long)]
+        co_signer_keypair: Option<String>,
+    },
+    /// File evidence for an open dispute; signed by the client or the freelancer
+    SubmitEvidence {
+        #[arg(long)]
+        id: ContractId,
+        /// SHA-256 of the evidence as 64 hex digits
+        #[arg(long, value_parser = parse_sha256)]
+        hash: [u8; 32],
+        /// Where the evidence can be fetched
+        #[arg(long)]
+        uri: String,
+    },
+    /// Return escrow surplus to the client and true up the recorded balance; anyone can sign
+    Reconcile {
+        #[arg(long)]
+        id: ContractId,
+    },
+    /// Require a second key on approvals above `threshold`; signed by the client and the co-signer
+    SetCoSigner {
+        #[arg(long)]
+        id: ContractId,
+        /// Keypair path of the co-signer
+        #[arg(long)]
+        co_signer_keypair: String,
+        /// Approvals above this amount in token base units need the co-signer
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// Replace the co-signer or its threshold; signed by the client and the current co-signer
+    UpdateCoSigner {
+        #[arg(long)]
+        id: ContractId,
+        /// Keypair path of the current co-signer
+        #[arg(long)]
+        co_signer_keypair: String,
+        #[arg(long)]
+        new_co_signer: Pubkey,
+        #[arg(long)]
+        threshold: u64,
+    },
+    /// Print the FreelanceContract account
+    Show {
+        #[arg(long)]
+        id: ContractId,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MilestoneCommand {
+    /// Submit a milestone for approval; signed by the freelancer
+    Submit {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        index: u8,
+        /// URI of the delivered work
+        #[arg(long)]
+        proof: String,
+    },
+    /// Submit a milestone as content-hashed files, fixed once submitted; signed by the freelancer
+    SubmitDeliverables {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        index: u8,
+        /// One delivered file as `<uri>,<sha256 hex>,<mime type>`; repeat for each file
+        #[arg(long = "item", required = true, value_parser = parse_deliverable)]
+        items: Vec<Deliverable>,
+    },
+    /// Approve a milestone and release payment; signed by the client or an approver
+    Approve {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        index: u8,
+        /// Approve only this many basis points of the milestone
+        #[arg(long)]
+        bps: Option<u16>,
+        /// Keypair path of the contract's co-signer, for releases above its threshold
+        #[arg(long)]
+        co_signer_keypair: Option<String>,
+    },
+    /// Approve several milestones in full with one transfer; signed by the client
+    ApproveBatch {
+        #[arg(long)]
+        id: ContractId,
+        /// Comma-separated milestone indices
+        #[arg(long, value_delimiter = ',', required = true)]
+        indices: Vec<u8>,
+        /// Keypair path of the contract's co-signer, for batches above its threshold
+        #[arg(long)]
+        co_signer_keypair: Option<String>,
+    },
+    /// Sign an approval off-chain for a relayer to submit; signed by the client, who pays no fees
+    SignApproval {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        index: u8,
+        /// Approve only this many basis points of the milestone
+        #[arg(long)]
+        bps: Option<u16>,
+        /// Unix timestamp after which the approval can no longer be submitted
+        #[arg(long)]
+        expires_at: i64,
+    },
+    /// Submit a client's off-chain approval and pay its fees; signed by the relayer
+    RelayApproval {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        index: u8,
+        #[arg(long)]
+        bps: Option<u16>,
+        #[arg(long)]
+        nonce: u64,
+        #[arg(long)]
+        expires_at: i64,
+        /// Client signature printed by `milestone sign-approval`
+        #[arg(long)]
+        signature: Signature,
+    },
+    /// Print the Milestone account
+    Show {
+        #[arg(long)]
+        id: ContractId,
+        #[arg(long)]
+        index: u8,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProgramCommand {
+    /// Create the program config naming the pause guardian and config admin; signed by the upgrade authority
+    Init {
+        #[arg(long)]
+        guardian: Pubkey,
+        #[arg(long)]
+        admin: Pubkey,
+        /// Seconds a queued config change waits before it can be executed
+        #[arg(long)]
+        timelock_delay: i64,
+    },
+    /// Halt deposits, milestone submissions and approvals; signed by the guardian
+    Pause,
+    /// Lift a pause; signed by the guardian
+    Resume,
+    /// Print the EscrowConfig account
+    Show,
+}
+
+pub fn keys_for(runtime: &Runtime, contract_id: ContractId, contract: &FreelanceContract) -> Result<ContractKeys> {
+    // Migrated contracts have no mint until the client's next deposit
+    let mint = if contract.mint == Pubkey::default() { runtime.mint()? } else { contract.mint };
+    let mut keys = ContractKeys::new(contract_id, contract.freelancer, contract.client, mint);
+    // Contracts migrated from id-only addresses keep the id as their escrow seed
+    keys.legacy_seeds = contract.escrow_seed_len != 0 || contract.escrow_seed == contract_id.0;
+    if contract.escrow_seed_len != 0 {
+        keys.legacy_id = Some(String::from_utf8_lossy(contract.escrow_seed_bytes()).into_owned());
+    }
+    keys.payer = runtime.payer()?.map(|payer| payer.pubkey());
+    Ok(keys)
+}
+
+/// Payee token accounts and approver set accounts the contract's releases need
+fn release_options(runtime: &Runtime, keys: &ContractKeys, contract: &FreelanceContract) -> Result<ReleaseOptions> {
+    let mut options = ReleaseOptions {
+        approver_set: contract.has_approver_set,
+        co_signer_policy: contract.has_co_signer,
+        ..ReleaseOptions::default()
+    };
+    if contract.has_payee_split {
+        let split: PayeeSplit = runtime.fetch(&pda::payee_split(&keys.contract()).0)?;
+        options.payee_wallets = split.payees.iter().map(|payee| payee.wallet).collect();
+        options.payee_token_accounts = split
+            .payees
+            .iter()
+            .map(|payee| get_associated_token_address(&payee.wallet, &keys.mint))
+            .collect();
+    }
+    Ok(options)
+}
+
+/// Parse `<uri>,<sha256 hex>,<mime type>`; the URI may itself contain commas
+fn parse_deliverable(value: &str) -> std::result::Result<Deliverable, String> {
+    let mut parts = value.rsplitn(3, ',');
+    let (Some(mime_type), Some(hash), Some(uri)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("expected <uri>,<sha256 hex>,<mime type>".to_string());
+    };
+    Ok(Deliverable {
+        uri: uri.to_string(),
+        sha256: parse_sha256(hash)?,
+        mime_type: mime_type.to_string(),
+    })
+}
+
+fn parse_sha256(hash: &str) -> std::result::Result<[u8; 32], String> {
+    if hash.len() != 64 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(format!("invalid SHA-256 hash {hash:?}: expected 64 hex digits"));
+    }
+    let mut sha256 = [0u8; 32];
+    for (i, byte) in sha256.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hash[2 * i..2 * i + 2], 16).map_err(|err| err.to_string())?;
+    }
+    Ok(sha256)
+}
+
+/// Amount an approval of `approval_bps` releases, as the program computes it
+fn release_amount(milestone: &Milestone, approval_bps: u16) -> Result<u64> {
+    let remaining = milestone.payment_amount - milestone.amount_paid;
+    let amount = if approval_bps == BPS_DENOMINATOR {
+        remaining
+    } else {
+        (milestone.payment_amount as u128 * approval_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    };
+    if amount == 0 || amount > remaining {
+        bail!("approval of {approval_bps} bps does not fit the {remaining} still owed on the milestone");
+    }
+    Ok(amount)
+}
+
+pub fn run_contract(runtime: &Runtime, command: ContractCommand) -> Result<()> {
+    match command {
+        ContractCommand::Create {
+            id,
+            client,
+            total,
+            milestones,
+        } => {
+            let signer = runtime.signer()?;
+            let mut keys = ContractKeys::new(id, signer.pubkey(), client, runtime.mint()?);
+            keys.payer = Some(runtime.rent_payer(&signer)?);
+            runtime.send(&[escrow::create_contract(&keys, total, milestones)], &signer)
+        }
+        ContractCommand::Fund { id, amount } => {
+            let signer = runtime.signer()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send(&[escrow::deposit_funds(&keys, amount)], &signer)
+        }
+        ContractCommand::FundFromAuth { id } => {
+            let signer = runtime.signer()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send(&[escrow::fund_from_authorization(&keys)], &signer)
+        }
+        ContractCommand::Dispute {
+            id,
+            reason,
+            co_signer_keypair,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = co_signer_keypair.map(|path| runtime.keypair(&path)).transpose()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            let mut options = release_options(runtime, &keys, &contract)?;
+            options.co_signer = co_signer.as_ref().map(Signer::pubkey);
+            let co_signers: Vec<_> = co_signer.iter().collect();
+            runtime.send_with(&[escrow::dispute_contract(&keys, &reason, &options)], &signer, &co_signers)
+        }
+        ContractCommand::SubmitEvidence { id, hash, uri } => {
+            let signer = runtime.signer()?;
+            let (address, contract) = runtime.fetch_contract(id)?;
+            let Some(dispute) = runtime.fetch_dispute(&address, &contract)? else {
+                bail!("contract {id} is not disputed");
+            };
+            // The next evidence account is numbered by how many items the signer already filed
+            let evidence_index = if signer.pubkey() == contract.client {
+                dispute.client_evidence_count
+            } else if signer.pubkey() == contract.freelancer {
+                dispute.freelancer_evidence_count
+            } else {
+                bail!("{} is neither the client nor the freelancer of contract {id}", signer.pubkey());
+            };
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send(
+                &[escrow::submit_dispute_evidence(&keys, signer.pubkey(), evidence_index, hash, &uri)],
+                &signer,
+            )
+        }
+        ContractCommand::Reconcile { id } => {
+            let signer = runtime.signer()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send(&[escrow::reconcile_escrow(&keys, signer.pubkey())], &signer)
+        }
+        ContractCommand::SetCoSigner {
+            id,
+            co_signer_keypair,
+            threshold,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = runtime.keypair(&co_signer_keypair)?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send_with(
+                &[escrow::set_co_signer(&keys, co_signer.pubkey(), threshold)],
+                &signer,
+                &[&co_signer],
+            )
+        }
+        ContractCommand::UpdateCoSigner {
+            id,
+            co_signer_keypair,
+            new_co_signer,
+            threshold,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = runtime.keypair(&co_signer_keypair)?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send_with(
+                &[escrow::update_co_signer(&keys, co_signer.pubkey(), new_co_signer, threshold)],
+                &signer,
+                &[&co_signer],
+            )
+        }
+        ContractCommand::Show { id } => {
+            let (address, contract) = runtime.fetch_contract(id)?;
+            let dispute = runtime.fetch_dispute(&address, &contract)?;
+            runtime.output.print(&output::contract(&address, &contract, dispute.as_ref()));
+            Ok(())
+        }
+    }
+}
+
+pub fn run_milestone(runtime: &Runtime, command: MilestoneCommand) -> Result<()> {
+    match command {
+        MilestoneCommand::Submit { id, index, proof } => {
+            let signer = runtime.signer()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send(&[escrow::submit_milestone(&keys, index, &proof)], &signer)
+        }
+        MilestoneCommand::SubmitDeliverables { id, index, items } => {
+            let signer = runtime.signer()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            runtime.send(&[escrow::submit_milestone_deliverables(&keys, index, items)], &signer)
+        }
+        MilestoneCommand::Approve {
+            id,
+            index,
+            bps,
+            co_signer_keypair,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = co_signer_keypair.map(|path| runtime.keypair(&path)).transpose()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            let mut options = release_options(runtime, &keys, &contract)?;
+            options.co_signer = co_signer.as_ref().map(Signer::pubkey);
+            let instruction = match bps {
+                Some(bps) => escrow::approve_milestone_partial(&keys, signer.pubkey(), index, bps, &options),
+                None => escrow::approve_milestone(&keys, signer.pubkey(), index, &options),
+            };
+            let co_signers: Vec<_> = co_signer.iter().collect();
+            runtime.send_with(&[instruction], &signer, &co_signers)
+        }
+        MilestoneCommand::ApproveBatch {
+            id,
+            indices,
+            co_signer_keypair,
+        } => {
+            let signer = runtime.signer()?;
+            let co_signer = co_signer_keypair.map(|path| runtime.keypair(&path)).transpose()?;
+            let (_, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            let mut options = release_options(runtime, &keys, &contract)?;
+            options.co_signer = co_signer.as_ref().map(Signer::pubkey);
+            let co_signers: Vec<_> = co_signer.iter().collect();
+            runtime.send_with(&[escrow::approve_milestones(&keys, indices, &options)], &signer, &co_signers)
+        }
+        MilestoneCommand::SignApproval {
+            id,
+            index,
+            bps,
+            expires_at,
+        } => {
+            let signer = runtime.signer()?;
+            let (address, contract) = runtime.fetch_contract(id)?;
+            if contract.client != signer.pubkey() {
+                bail!("only the contract client {} can sign approvals", contract.client);
+            }
+            let milestone: Milestone = runtime.fetch(&pda::milestone(&address, index).0)?;
+            let bps = bps.unwrap_or(BPS_DENOMINATOR);
+            let amount = release_amount(&milestone, bps)?;
+            let nonce = contract.approval_nonce;
+            let message = freelance_escrow::signed_approval_message(&address, index, amount, nonce, expires_at);
+            let signature = signer.sign_message(&message);
+            runtime
+                .output
+                .print(&output::signed_approval(&address, index, bps, amount, nonce, expires_at, &signature));
+            Ok(())
+        }
+        MilestoneCommand::RelayApproval {
+            id,
+            index,
+            bps,
+            nonce,
+            expires_at,
+            signature,
+        } => {
+            let signer = runtime.signer()?;
+            let (address, contract) = runtime.fetch_contract(id)?;
+            let keys = keys_for(runtime, id, &contract)?;
+            let milestone: Milestone = runtime.fetch(&keys.milestone(index))?;
+            let bps = bps.unwrap_or(BPS_DENOMINATOR);
+            let amount = release_amount(&milestone, bps)?;
+            let message = freelance_escrow::signed_approval_message(&address, index, amount, nonce, expires_at);
+            let signature: [u8; 64] = signature.as_ref().try_into()?;
+            let options = release_options(runtime, &keys, &contract)?;
+            runtime.send(
+                &[
+                    escrow::ed25519_verify_instruction(&contract.client, &signature, &message),
+                    escrow::approve_milestone_signed(&keys, signer.pubkey(), index, bps, nonce, expires_at, &options),
+                ],
+                &signer,
+            )
+        }
+        MilestoneCommand::Show { id, index } => {
+            let (contract, _) = runtime.fetch_contract(id)?;
+            let address = pda::milestone(&contract, index).0;
+            let milestone: Milestone = runtime.fetch(&address)?;
+            let deliverables = runtime.fetch_deliverables(&address, &milestone)?;
+            runtime.output.print(&output::milestone(&address, &milestone, deliverables.as_ref()));
+            Ok(())
+        }
+    }
+}
+
+pub fn run_program(runtime: &Runtime, command: ProgramCommand) -> Result<()> {
+    match command {
+        ProgramCommand::Init {
+            guardian,
+            admin,
+            timelock_delay,
+        } => {
+            let signer = runtime.signer()?;
+            runtime.send(
+                &[escrow::initialize_config(signer.pubkey(), guardian, admin, timelock_delay)],
+                &signer,
+            )
+        }
+        ProgramCommand::Pause => {
+            let signer = runtime.signer()?;
+            runtime.send(&[escrow::set_paused(signer.pubkey(), true)], &signer)
+        }
+        ProgramCommand::Resume => {
+            let signer = runtime.signer()?;
+            runtime.send(&[escrow::set_paused(signer.pubkey(), false)], &signer)
+        }
+        ProgramCommand::Show => {
+            let address = pda::config().0;
+            let config: EscrowConfig = runtime.fetch(&address)?;
+            runtime.output.print(&output::escrow_config(&address, &config));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! `governance` subcommands for the timelocked config changes of both programs.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use clap::{Subcommand, ValueEnum};
+use smartflo_client::accounts::{EscrowConfig, PendingAuthConfigChange, PendingConfigChange};
+use smartflo_client::{authorization, escrow, pda};
+use solana_sdk::signer::Signer;
+
+use crate::output;
+use crate::runtime::Runtime;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TargetProgram {
+    Escrow,
+    PaymentAuth,
+}
+
+#[derive(Subcommand)]
+pub enum GovernanceCommand {
+    /// Queue a config change, executable after the timelock delay; signed by the config admin
+    Queue {
+        #[arg(long, value_enum)]
+        program: TargetProgram,
+        /// New pause guardian (escrow only)
+        #[arg(long, group = "change")]
+        guardian: Option<Pubkey>,
+        /// New config admin
+        #[arg(long, group = "change")]
+        admin: Option<Pubkey>,
+        /// New comma-separated freeze admins (payment-auth only)
+        #[arg(long, group = "change", value_delimiter = ',')]
+        freeze_admins: Option<Vec<Pubkey>>,
+        /// New sanctions blocklist authority (payment-auth only)
+        #[arg(long, group = "change")]
+        compliance_authority: Option<Pubkey>,
+        /// New timelock delay in seconds
+        #[arg(long, group = "change")]
+        timelock_delay: Option<i64>,
+        /// Amount above which contracts or authorizations need KYC attestations; 0 turns the check off
+        #[arg(long, group = "change")]
+        kyc_threshold: Option<u64>,
+        /// Minimum attestation level accepted above the KYC threshold
+        #[arg(long, requires = "kyc_threshold", default_value_t = 1)]
+        kyc_min_level: u8,
+    },
+    /// Apply a queued change whose delay has passed; anyone can sign
+    Execute {
+        #[arg(long, value_enum)]
+        program: TargetProgram,
+        #[arg(long)]
+        change: u64,
+    },
+    /// Drop a queued change; signed by the config admin, the escrow guardian or a payment_auth freeze admin
+    Cancel {
+        #[arg(long, value_enum)]
+        program: TargetProgram,
+        #[arg(long)]
+        change: u64,
+    },
+    /// Print a queued change
+    Show {
+        #[arg(long, value_enum)]
+        program: TargetProgram,
+        #[arg(long)]
+        change: u64,
+    },
+}
+
+pub fn run(runtime: &Runtime, command: GovernanceCommand) -> Result<()> {
+    match command {
+        GovernanceCommand::Queue {
+            program,
+            guardian,
+            admin,
+            freeze_admins,
+            compliance_authority,
+            timelock_delay,
+            kyc_threshold,
+            kyc_min_level,
+        } => {
+            let signer = runtime.signer()?;
+            let payer = runtime.rent_payer(&signer)?;
+            let instruction = match program {
+                TargetProgram::Escrow => {
+                    use freelance_escrow::ConfigChange;
+                    let change = match (guardian, admin, freeze_admins, compliance_authority, timelock_delay, kyc_threshold) {
+                        (Some(guardian), None, None, None, None, None) => ConfigChange::Guardian(guardian),
+                        (None, Some(admin), None, None, None, None) => ConfigChange::Admin(admin),
+                        (None, None, None, None, Some(delay), None) => ConfigChange::TimelockDelay(delay),
+                        (None, None, None, None, None, Some(threshold)) => ConfigChange::KycRequirement {
+                            threshold,
+                            min_level: kyc_min_level,
+                        },
+                        _ => bail!(
+                            "pass one of --guardian, --admin, --timelock-delay or --kyc-threshold for the escrow program"
+                        ),
+                    };
+                    let config: EscrowConfig = runtime.fetch(&pda::config().0)?;
+                    escrow::queue_config_change(signer.pubkey(), payer, config.change_count, change)
+                }
+                TargetProgram::PaymentAuth => {
+                    use payment_auth::ConfigChange;
+                    let change = match (guardian, admin, freeze_admins, compliance_authority, timelock_delay, kyc_threshold) {
+                        (None, Some(admin), None, None, None, None) => ConfigChange::Admin(admin),
+                        (None, None, Some(admins), None, None, None) => ConfigChange::FreezeAdmins(admins),
+                        (None, None, None, Some(authority), None, None) => ConfigChange::ComplianceAuthority(authority),
+                        (None, None, None, None, Some(delay), None) => ConfigChange::TimelockDelay(delay),
+                        (None, None, None, None, None, Some(threshold)) => ConfigChange::KycRequirement {
+                            threshold,
+                            min_level: kyc_min_level,
+                        },
+                        _ => bail!(
+                            "pass one of --admin, --freeze-admins, --compliance-authority, --timelock-delay or \
+                             --kyc-threshold for payment_auth"
+                        ),
+                    };
+                    let config: payment_auth::AuthConfig = runtime.fetch(&pda::auth_config().0)?;
+                    authorization::queue_config_change(signer.pubkey(), payer, config.change_count, change)
+                }
+            };
+            runtime.send(&[instruction], &signer)
+        }
+        GovernanceCommand::Execute { program, change } => {
+            let signer = runtime.signer()?;
+            let rent_payer = rent_payer(runtime, program, change)?;
+            let instruction = match program {
+                TargetProgram::Escrow => escrow::execute_config_change(signer.pubkey(), change, rent_payer),
+                TargetProgram::PaymentAuth => authorization::execute_config_change(signer.pubkey(), change, rent_payer),
+            };
+            runtime.send(&[instruction], &signer)
+        }
+        GovernanceCommand::Cancel { program, change } => {
+            let signer = runtime.signer()?;
+            let rent_payer = rent_payer(runtime, program, change)?;
+            let instruction = match program {
+                TargetProgram::Escrow => escrow::cancel_config_change(signer.pubkey(), change, rent_payer),
+                TargetProgram::PaymentAuth => authorization::cancel_config_change(signer.pubkey(), change, rent_payer),
+            };
+            runtime.send(&[instruction], &signer)
+        }
+        GovernanceCommand::Show { program, change } => {
+            let value = match program {
+                TargetProgram::Escrow => {
+                    let address = pda::config_change(change).0;
+                    let pending: PendingConfigChange = runtime.fetch(&address)?;
+                    output::escrow_config_change(&address, &pending)
+                }
+                TargetProgram::PaymentAuth => {
+                    let address = pda::auth_config_change(change).0;
+                    let pending: PendingAuthConfigChange = runtime.fetch(&address)?;
+                    output::auth_config_change(&address, &pending)
+                }
+            };
+            runtime.output.print(&value);
+            Ok(())
+        }
+    }
+}
+
+/// Account that paid the change's rent, refunded on execute or cancel
+fn rent_payer(runtime: &Runtime, program: TargetProgram, change: u64) -> Result<Pubkey> {
+    Ok(match program {
+        TargetProgram::Escrow => runtime.fetch::<PendingConfigChange>(&pda::config_change(change).0)?.rent_payer,
+        TargetProgram::PaymentAuth => {
+            runtime
+                .fetch::<PendingAuthConfigChange>(&pda::auth_config_change(change).0)?
+                .rent_payer
+        }
+    })
+}
@@ -0,0 +1,151 @@
+//! `smartflo` command-line tool for operating escrows and payment authorizations.
+
+mod authorization;
+mod config;
+mod escrow;
+mod governance;
+mod output;
+mod runtime;
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use serde_json::json;
+use smartflo_client::accounts::{decode_contract, decode_dispute, decode_milestone, decode_payment_authorization};
+
+use crate::config::Config;
+use crate::output::OutputFormat;
+use crate::runtime::Runtime;
+
+#[derive(Parser)]
+#[command(name = "smartflo", version, about = "Operate SmartFlo escrows and payment authorizations")]
+struct Cli {
+    /// Config file [default: ~/.config/smartflo/config.toml]
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// RPC URL or cluster moniker, overriding the config file
+    #[arg(long, short = 'u', global = true)]
+    url: Option<String>,
+    /// Signing keypair path, overriding the config file
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+    /// Keypair paying transaction fees and the rent of created accounts instead of the signer
+    #[arg(long, global = true)]
+    payer: Option<String>,
+    /// Token mint, overriding the config file
+    #[arg(long, global = true)]
+    mint: Option<String>,
+    /// Output format
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Display)]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show or update the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Create, fund, dispute and inspect escrow contracts
+    #[command(subcommand)]
+    Contract(escrow::ContractCommand),
+    /// Submit, approve and inspect milestones
+    #[command(subcommand)]
+    Milestone(escrow::MilestoneCommand),
+    /// Pause, resume and inspect the escrow program
+    #[command(subcommand)]
+    Program(escrow::ProgramCommand),
+    /// Create, update, revoke, freeze and inspect payment authorizations
+    #[command(subcommand)]
+    Auth(authorization::AuthCommand),
+    /// Queue, execute, cancel and inspect timelocked config changes of either program
+    #[command(subcommand)]
+    Governance(governance::GovernanceCommand),
+    /// Print any FreelanceContract, Milestone or PaymentAuthorization account
+    Show { address: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective configuration
+    Show,
+    /// Write the given settings to the config file
+    Set {
+        #[arg(long)]
+        cluster: Option<String>,
+        #[arg(long)]
+        keypair: Option<String>,
+        #[arg(long)]
+        mint: Option<String>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config_path = cli.config.clone().unwrap_or_else(Config::default_path);
+    let mut config = Config::load(&config_path)?;
+
+    if let Command::Config(ConfigCommand::Set {
+        cluster,
+        keypair,
+        mint,
+    }) = &cli.command
+    {
+        config.cluster = cluster.clone().unwrap_or(config.cluster);
+        config.keypair = keypair.clone().unwrap_or(config.keypair);
+        config.mint = mint.clone().unwrap_or(config.mint);
+        config.save(&config_path)?;
+        println!("Wrote {}", config_path.display());
+        return Ok(());
+    }
+
+    config.cluster = cli.url.unwrap_or(config.cluster);
+    config.keypair = cli.keypair.unwrap_or(config.keypair);
+    config.mint = cli.mint.unwrap_or(config.mint);
+    let runtime = Runtime::new(config, cli.output, cli.payer);
+
+    match cli.command {
+        Command::Config(_) => {
+            runtime.output.print(&json!({
+                "config_file": config_path.display().to_string(),
+                "rpc_url": runtime.config.rpc_url(),
+                "keypair": runtime.config.keypair_path().display().to_string(),
+                "mint": runtime.config.mint,
+            }));
+            Ok(())
+        }
+        Command::Contract(command) => escrow::run_contract(&runtime, command),
+        Command::Milestone(command) => escrow::run_milestone(&runtime, command),
+        Command::Program(command) => escrow::run_program(&runtime, command),
+        Command::Auth(command) => authorization::run(&runtime, command),
+        Command::Governance(command) => governance::run(&runtime, command),
+        Command::Show { address } => show(&runtime, &address),
+    }
+}
+
+/// Detect the account type from its owner and discriminator and print it
+fn show(runtime: &Runtime, address: &Pubkey) -> Result<()> {
+    let account = runtime.rpc.get_account(address)?;
+    let value = if account.owner == freelance_escrow::ID {
+        if let Ok(contract) = decode_contract(&account.data) {
+            let dispute = runtime.fetch_dispute(address, &contract)?;
+            output::contract(address, &contract, dispute.as_ref())
+        } else if let Ok(dispute) = decode_dispute(&account.data) {
+            output::dispute(address, &dispute)
+        } else if let Ok(milestone) = decode_milestone(&account.data) {
+            let deliverables = runtime.fetch_deliverables(address, &milestone)?;
+            output::milestone(address, &milestone, deliverables.as_ref())
+        } else {
+            bail!("{address} is a freelance_escrow account of an unsupported type");
+        }
+    } else if account.owner == payment_auth::ID {
+        output::payment_authorization(address, &decode_payment_authorization(&account.data)?)
+    } else {
+        bail!("{address} is not owned by freelance_escrow or payment_auth");
+    };
+    runtime.output.print(&value);
+    Ok(())
+}
@@ -0,0 +1,304 @@
+//! Rendering of accounts and command results as text or JSON.
+
+use anchor_lang::prelude::Pubkey;
+use clap::ValueEnum;
+use freelance_escrow::{ContractType, Deliverables, Dispute, EscrowConfig, FreelanceContract, Milestone};
+use payment_auth::{AuthConfig, KycAttestation, PaymentAuthorization, SanctionEntry};
+use smartflo_client::accounts::{PendingAuthConfigChange, PendingConfigChange};
+use serde_json::{json, Value};
+use solana_sdk::signature::Signature;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Display,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn print(self, value: &Value) {
+        match self {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value).unwrap()),
+            OutputFormat::Display => print_fields(value, 0),
+        }
+    }
+}
+
+fn print_fields(value: &Value, indent: usize) {
+    let Value::Object(fields) = value else {
+        println!("{:indent$}{}", "", plain(value));
+        return;
+    };
+    let width = fields.keys().map(|key| key.len()).max().unwrap_or(0) + 1;
+    for (key, field) in fields {
+        match field {
+            Value::Object(_) => {
+                println!("{:indent$}{}:", "", title(key));
+                print_fields(field, indent + 2);
+            }
+            _ => println!("{:indent$}{:width$} {}", "", format!("{}:", title(key)), plain(field)),
+        }
+    }
+}
+
+fn title(key: &str) -> String {
+    key.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(plain).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+pub fn contract_type(contract_type: ContractType) -> &'static str {
+    match contract_type {
+        ContractType::Milestone => "milestone",
+        ContractType::Streaming => "streaming",
+        ContractType::Hourly => "hourly",
+    }
+}
+
+/// `dispute` is the contract's `Dispute` account, if it has been disputed
+pub fn contract(address: &Pubkey, contract: &FreelanceContract, dispute: Option<&Dispute>) -> Value {
+    json!({
+        "account": "FreelanceContract",
+        "address": address.to_string(),
+        "contract_id": contract.contract_id_str(),
+        "contract_type": contract_type(contract.contract_type),
+        "freelancer": contract.freelancer.to_string(),
+        "client": contract.client.to_string(),
+        "total_amount": contract.total_amount,
+        "milestone_count": contract.milestone_count,
+        "completed_milestones": contract.completed_milestones,
+        "amount_released": contract.amount_released,
+        "escrow_balance": contract.escrow_balance,
+        "bonus_paid": contract.bonus_paid,
+        "stream_start": contract.stream_start,
+        "stream_end": contract.stream_end,
+        "hourly_rate": contract.hourly_rate,
+        "weekly_hour_cap": contract.weekly_hour_cap,
+        "has_payee_split": contract.has_payee_split,
+        "has_approver_set": contract.has_approver_set,
+        "has_co_signer": contract.has_co_signer,
+        "approval_nonce": contract.approval_nonce,
+        "is_active": contract.is_active,
+        "is_completed": contract.is_completed,
+        "is_disputed": contract.is_disputed,
+        "dispute_reason": dispute.map(|dispute| dispute.reason.as_str()),
+        "created_at": contract.created_at,
+        "completed_at": contract.completed_at,
+    })
+}
+
+/// `deliverables` is the milestone's `Deliverables` account, if it was submitted with one
+pub fn milestone(address: &Pubkey, milestone: &Milestone, deliverables: Option<&Deliverables>) -> Value {
+    json!({
+        "account": "Milestone",
+        "address": address.to_string(),
+        "contract": milestone.contract.to_string(),
+        "index": milestone.index,
+        "submitted_at": milestone.submitted_at,
+        "approved_at": milestone.approved_at,
+        "proof_uri": milestone.proof_uri,
+        "is_approved": milestone.is_approved,
+        "payment_amount": milestone.payment_amount,
+        "amount_paid": milestone.amount_paid,
+        "has_deliverables": milestone.has_deliverables,
+        "deliverables": deliverables.map(|deliverables| {
+            deliverables
+                .items
+                .iter()
+                .map(|item| {
+                    json!({
+                        "uri": item.uri,
+                        "sha256": item.sha256.iter().map(|byte| format!("{byte:02x}")).collect::<String>(),
+                        "mime_type": item.mime_type,
+                    })
+                })
+                .collect::<Vec<_>>()
+        }),
+    })
+}
+
+pub fn dispute(address: &Pubkey, dispute: &Dispute) -> Value {
+    json!({
+        "account": "Dispute",
+        "address": address.to_string(),
+        "contract": dispute.contract.to_string(),
+        "reason": dispute.reason,
+        "opened_at": dispute.opened_at,
+        "client_evidence_count": dispute.client_evidence_count,
+        "freelancer_evidence_count": dispute.freelancer_evidence_count,
+    })
+}
+
+pub fn escrow_config(address: &Pubkey, config: &EscrowConfig) -> Value {
+    json!({
+        "account": "EscrowConfig",
+        "address": address.to_string(),
+        "guardian": config.guardian.to_string(),
+        "is_paused": config.is_paused,
+        "paused_at": config.paused_at,
+        "admin": config.admin.to_string(),
+        "timelock_delay": config.timelock_delay,
+        "change_count": config.change_count,
+        "kyc_threshold": config.kyc_threshold,
+        "kyc_min_level": config.kyc_min_level,
+    })
+}
+
+pub fn auth_config(address: &Pubkey, config: &AuthConfig) -> Value {
+    json!({
+        "account": "AuthConfig",
+        "address": address.to_string(),
+        "admin": config.admin.to_string(),
+        "freeze_admins": config.freeze_admins.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+        "compliance_authority": config.compliance_authority.to_string(),
+        "timelock_delay": config.timelock_delay,
+        "change_count": config.change_count,
+        "kyc_threshold": config.kyc_threshold,
+        "kyc_min_level": config.kyc_min_level,
+    })
+}
+
+pub fn escrow_config_change(address: &Pubkey, pending: &PendingConfigChange) -> Value {
+    use freelance_escrow::ConfigChange;
+    let change = match &pending.change {
+        ConfigChange::Guardian(guardian) => json!({ "guardian": guardian.to_string() }),
+        ConfigChange::Admin(admin) => json!({ "admin": admin.to_string() }),
+        ConfigChange::TimelockDelay(delay) => json!({ "timelock_delay": delay }),
+        ConfigChange::KycRequirement { threshold, min_level } => {
+            json!({ "kyc_threshold": threshold, "kyc_min_level": min_level })
+        }
+    };
+    pending_change(
+        address,
+        pending.change_id,
+        change,
+        &pending.proposer,
+        &pending.rent_payer,
+        pending.queued_at,
+        pending.eta,
+    )
+}
+
+pub fn auth_config_change(address: &Pubkey, pending: &PendingAuthConfigChange) -> Value {
+    use payment_auth::ConfigChange;
+    let change = match &pending.change {
+        ConfigChange::Admin(admin) => json!({ "admin": admin.to_string() }),
+        ConfigChange::FreezeAdmins(admins) => {
+            json!({ "freeze_admins": admins.iter().map(Pubkey::to_string).collect::<Vec<_>>() })
+        }
+        ConfigChange::ComplianceAuthority(authority) => json!({ "compliance_authority": authority.to_string() }),
+        ConfigChange::TimelockDelay(delay) => json!({ "timelock_delay": delay }),
+        ConfigChange::KycRequirement { threshold, min_level } => {
+            json!({ "kyc_threshold": threshold, "kyc_min_level": min_level })
+        }
+    };
+    pending_change(
+        address,
+        pending.change_id,
+        change,
+        &pending.proposer,
+        &pending.rent_payer,
+        pending.queued_at,
+        pending.eta,
+    )
+}
+
+fn pending_change(
+    address: &Pubkey,
+    change_id: u64,
+    change: Value,
+    proposer: &Pubkey,
+    rent_payer: &Pubkey,
+    queued_at: i64,
+    eta: i64,
+) -> Value {
+    json!({
+        "account": "PendingConfigChange",
+        "address": address.to_string(),
+        "change_id": change_id,
+        "change": change,
+        "proposer": proposer.to_string(),
+        "rent_payer": rent_payer.to_string(),
+        "queued_at": queued_at,
+        "eta": eta,
+    })
+}
+
+/// Off-chain client approval for a relayer to submit with `milestone relay-approval`
+pub fn signed_approval(
+    contract: &Pubkey,
+    milestone_index: u8,
+    approval_bps: u16,
+    amount: u64,
+    nonce: u64,
+    expires_at: i64,
+    signature: &Signature,
+) -> Value {
+    json!({
+        "contract": contract.to_string(),
+        "milestone_index": milestone_index,
+        "approval_bps": approval_bps,
+        "amount": amount,
+        "nonce": nonce,
+        "expires_at": expires_at,
+        "signature": signature.to_string(),
+    })
+}
+
+/// Blocklist status of `wallet`; `entry` is its `SanctionEntry` account when it has one
+pub fn sanction_entry(wallet: &Pubkey, address: &Pubkey, entry: Option<&SanctionEntry>) -> Value {
+    json!({
+        "account": "SanctionEntry",
+        "address": address.to_string(),
+        "wallet": wallet.to_string(),
+        "is_sanctioned": entry.is_some(),
+        "added_by": entry.map(|entry| entry.added_by.to_string()),
+        "added_at": entry.map(|entry| entry.added_at),
+        "rent_payer": entry.map(|entry| entry.rent_payer.to_string()),
+    })
+}
+
+pub fn kyc_attestation(address: &Pubkey, attestation: &KycAttestation) -> Value {
+    json!({
+        "account": "KycAttestation",
+        "address": address.to_string(),
+        "wallet": attestation.wallet.to_string(),
+        "level": attestation.level,
+        "expires_at": attestation.expires_at,
+        "issued_by": attestation.issued_by.to_string(),
+        "issued_at": attestation.issued_at,
+        "rent_payer": attestation.rent_payer.to_string(),
+    })
+}
+
+pub fn payment_authorization(address: &Pubkey, auth: &PaymentAuthorization) -> Value {
+    json!({
+        "account": "PaymentAuthorization",
+        "address": address.to_string(),
+        "contract_id": auth.contract_id_str(),
+        "client": auth.client.to_string(),
+        "freelancer": auth.freelancer.to_string(),
+        "max_per_milestone": auth.max_per_milestone,
+        "total_authorized": auth.total_authorized,
+        "total_spent": auth.total_spent,
+        "escrow_token_account": auth.escrow_token_account.to_string(),
+        "co_signer": (auth.co_signer != Pubkey::default()).then(|| auth.co_signer.to_string()),
+        "co_sign_threshold": auth.co_sign_threshold,
+        "is_active": auth.is_active,
+        "authorized_at": auth.authorized_at,
+    })
+}
@@ -0,0 +1,199 @@
+//! RPC connection, signer and output settings shared by every command.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::json;
+use smartflo_client::accounts::{
+    decode, Deliverables, Dispute, FreelanceContract, Milestone, PaymentAuthorization, CONTRACT_ID_OFFSET,
+};
+use smartflo_client::errors::{decode_instruction_error, ProgramError};
+use smartflo_client::{pda, ContractId};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+use crate::config::Config;
+use crate::output::OutputFormat;
+
+pub struct Runtime {
+    pub rpc: RpcClient,
+    pub config: Config,
+    pub output: OutputFormat,
+    /// Keypair paying fees and rent in place of the signer
+    pub payer_path: Option<String>,
+}
+
+impl Runtime {
+    pub fn new(config: Config, output: OutputFormat, payer_path: Option<String>) -> Self {
+        let rpc = RpcClient::new_with_commitment(config.rpc_url(), CommitmentConfig::confirmed());
+        Self {
+            rpc,
+            config,
+            output,
+            payer_path,
+        }
+    }
+
+    pub fn signer(&self) -> Result<Keypair> {
+        let path = self.config.keypair_path();
+        read_keypair_file(&path).map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))
+    }
+
+    pub fn mint(&self) -> Result<Pubkey> {
+        Pubkey::from_str(&self.config.mint).with_context(|| format!("invalid mint {}", self.config.mint))
+    }
+
+    pub fn fetch<T: anchor_lang::AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .rpc
+            .get_account(address)
+            .with_context(|| format!("fetching account {address}"))?;
+        decode(&account.data).map_err(|err| anyhow!("decoding account {address}: {err}"))
+    }
+
+    /// Look a contract up by id. Contract addresses include both participants, so an id is not
+    /// unique on chain; when several contracts share it, take the one the signer is party to
+    pub fn fetch_contract(&self, contract_id: ContractId) -> Result<(Pubkey, FreelanceContract)> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, FreelanceContract::DISCRIMINATOR.to_vec())),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(CONTRACT_ID_OFFSET, contract_id.as_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&freelance_escrow::ID, config)
+            .with_context(|| format!("looking up contract {contract_id}"))?;
+        let mut contracts = accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let contract = decode(&account.data).map_err(|err| anyhow!("decoding account {address}: {err}"))?;
+                Ok((address, contract))
+            })
+            .collect::<Result<Vec<(Pubkey, FreelanceContract)>>>()?;
+        if contracts.len() > 1 {
+            if let Ok(signer) = self.signer() {
+                let signer = signer.pubkey();
+                let mine: Vec<_> = contracts
+                    .iter()
+                    .filter(|(_, contract)| contract.freelancer == signer || contract.client == signer)
+                    .cloned()
+                    .collect();
+                if mine.len() == 1 {
+                    contracts = mine;
+                }
+            }
+        }
+        match contracts.len() {
+            0 => bail!("no contract with id {contract_id}"),
+            1 => Ok(contracts.remove(0)),
+            _ => {
+                let addresses: Vec<_> = contracts.iter().map(|(address, _)| address.to_string()).collect();
+                bail!("contract id {contract_id} is ambiguous: {}", addresses.join(", "))
+            }
+        }
+    }
+
+    /// The contract's `Dispute` account; only disputed contracts have one
+    pub fn fetch_dispute(&self, contract_address: &Pubkey, contract: &FreelanceContract) -> Result<Option<Dispute>> {
+        if !contract.is_disputed {
+            return Ok(None);
+        }
+        Ok(Some(self.fetch(&pda::dispute(contract_address).0)?))
+    }
+
+    /// The milestone's `Deliverables` account; only milestones submitted with deliverables have one
+    pub fn fetch_deliverables(
+        &self,
+        milestone_address: &Pubkey,
+        milestone: &Milestone,
+    ) -> Result<Option<Deliverables>> {
+        if !milestone.has_deliverables {
+            return Ok(None);
+        }
+        Ok(Some(self.fetch(&pda::deliverables(milestone_address).0)?))
+    }
+
+    pub fn fetch_authorization(&self, client: &Pubkey, contract_id: ContractId) -> Result<(Pubkey, PaymentAuthorization)> {
+        let address = pda::payment_authorization(client, &contract_id).0;
+        Ok((address, self.fetch(&address)?))
+    }
+
+    /// Second signing keypair, such as a co-signer, read from `path`
+    pub fn keypair(&self, path: &str) -> Result<Keypair> {
+        read_keypair_file(path).map_err(|err| anyhow!("reading keypair {path}: {err}"))
+    }
+
+    /// The `--payer` keypair, if one was given
+    pub fn payer(&self) -> Result<Option<Keypair>> {
+        self.payer_path.as_deref().map(|path| self.keypair(path)).transpose()
+    }
+
+    /// Account paying the rent of what the signer creates: the `--payer` keypair, else the signer
+    pub fn rent_payer(&self, signer: &Keypair) -> Result<Pubkey> {
+        Ok(self.payer()?.map_or(signer.pubkey(), |payer| payer.pubkey()))
+    }
+
+    /// Sign with `signer`, send and print the signature; the `--payer` keypair, if any, pays the fee
+    pub fn send(&self, instructions: &[Instruction], signer: &Keypair) -> Result<()> {
+        self.send_with(instructions, signer, &[])
+    }
+
+    /// Like [`Runtime::send`], with `co_signers` signing alongside the fee payer
+    pub fn send_with(&self, instructions: &[Instruction], signer: &Keypair, co_signers: &[&Keypair]) -> Result<()> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let payer = self.payer()?.filter(|payer| payer.pubkey() != signer.pubkey());
+        let fee_payer = payer.as_ref().unwrap_or(signer);
+        let signers: Vec<&Keypair> = std::iter::once(signer)
+            .chain(payer.as_ref())
+            .chain(co_signers.iter().copied())
+            .collect();
+        let transaction =
+            Transaction::new_signed_with_payer(instructions, Some(&fee_payer.pubkey()), &signers, blockhash);
+        let signature = match self.rpc.send_and_confirm_transaction(&transaction) {
+            Ok(signature) => signature,
+            Err(err) => {
+                if let Some(TransactionError::InstructionError(index, error)) = err.get_transaction_error() {
+                    let decoded = instructions
+                        .get(index as usize)
+                        .and_then(|instruction| decode_instruction_error(&instruction.program_id, &error));
+                    if let Some(decoded) = decoded {
+                        bail!("transaction failed: {}", describe(decoded));
+                    }
+                }
+                return Err(err.into());
+            }
+        };
+        self.print_signature(&signature);
+        Ok(())
+    }
+
+    fn print_signature(&self, signature: &Signature) {
+        match self.output {
+            OutputFormat::Json => self.output.print(&json!({ "signature": signature.to_string() })),
+            OutputFormat::Display => println!("Signature: {signature}"),
+        }
+    }
+}
+
+fn describe(error: ProgramError) -> String {
+    match error {
+        ProgramError::Escrow(code) => format!("{} ({code})", code.name()),
+        ProgramError::PaymentAuth(code) => format!("{} ({code})", code.name()),
+        ProgramError::Anchor(code) => format!("Anchor error {code}"),
+    }
+}
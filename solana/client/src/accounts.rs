@@ -0,0 +1,37 @@
+//! Deserializers for accounts owned by both programs.
+
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use freelance_escrow::{
+    ApproverSet, CoSignerPolicy, Deliverable, Deliverables, Dispute, DisputeEvidence, EscrowConfig, FreelanceContract,
+    Milestone, MilestoneApproval, PayeeSplit, PendingConfigChange, Timesheet,
+};
+pub use payment_auth::{
+    AuthConfig, KycAttestation, PaymentAuthorization, PendingConfigChange as PendingAuthConfigChange,
+    SanctionEntry,
+};
+
+/// Offset of `contract_id` in `FreelanceContract` data, after the discriminator and version;
+/// for `memcmp` filters that look contracts up by id
+pub const CONTRACT_ID_OFFSET: usize = 9;
+
+/// Decode raw account data, checking the account discriminator
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_contract(data: &[u8]) -> Result<FreelanceContract> {
+    decode(data)
+}
+
+pub fn decode_milestone(data: &[u8]) -> Result<Milestone> {
+    decode(data)
+}
+
+pub fn decode_dispute(data: &[u8]) -> Result<Dispute> {
+    decode(data)
+}
+
+pub fn decode_payment_authorization(data: &[u8]) -> Result<PaymentAuthorization> {
+    decode(data)
+}
@@ -0,0 +1,335 @@
+//! Instruction builders for the payment_auth program.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token;
+use payment_auth::{accounts, instruction, ConfigChange};
+
+use crate::{pda, ContractId};
+
+/// Client, freelancer and contract id identifying one payment authorization.
+#[derive(Clone, Debug)]
+pub struct AuthorizationKeys {
+    pub contract_id: ContractId,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    /// Id a legacy authorization was created under when it was not 32 bytes long; `contract_id`
+    /// is then [`ContractId::from_legacy`] of it
+    pub legacy_id: Option<String>,
+    /// Pays the authorization's rent; `None` leaves it to the client
+    pub payer: Option<Pubkey>,
+}
+
+impl AuthorizationKeys {
+    pub fn new(contract_id: ContractId, client: Pubkey, freelancer: Pubkey) -> Self {
+        Self {
+            contract_id,
+            client,
+            freelancer,
+            legacy_id: None,
+            payer: None,
+        }
+    }
+
+    pub fn payment_authorization(&self) -> Pubkey {
+        match &self.legacy_id {
+            Some(legacy_id) => pda::legacy_payment_authorization(&self.client, legacy_id.as_bytes()).0,
+            None => pda::payment_authorization(&self.client, &self.contract_id).0,
+        }
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: payment_auth::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `create_payment_authorization`, signed by the client
+pub fn create_payment_authorization(
+    keys: &AuthorizationKeys,
+    max_per_milestone: u64,
+    total_authorized: u64,
+) -> Instruction {
+    build(
+        accounts::CreateAuth {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+            payer: keys.payer.unwrap_or(keys.client),
+            freelancer: keys.freelancer,
+            config: pda::auth_config().0,
+            client_kyc: pda::kyc_attestation(&keys.client).0,
+            freelancer_kyc: pda::kyc_attestation(&keys.freelancer).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreatePaymentAuthorization {
+            contract_id: keys.contract_id.0,
+            max_per_milestone,
+            total_authorized,
+        },
+    )
+}
+
+/// `process_milestone_payment` between the participants' associated token accounts for `mint`;
+/// passes both participants' sanctions blocklist entries. Payments above the co-sign threshold
+/// also need the registered `co_signer` to sign
+pub fn process_milestone_payment(
+    keys: &AuthorizationKeys,
+    mint: &Pubkey,
+    milestone_id: &str,
+    amount: u64,
+    co_signer: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ProcessPayment {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+            freelancer: keys.freelancer,
+            client_token_account: get_associated_token_address(&keys.client, mint),
+            freelancer_token_account: get_associated_token_address(&keys.freelancer, mint),
+            client_sanction: pda::sanction_entry(&keys.client).0,
+            recipient_sanction: pda::sanction_entry(&keys.freelancer).0,
+            co_signer,
+            token_program: token::ID,
+        },
+        instruction::ProcessMilestonePayment {
+            milestone_id: milestone_id.to_string(),
+            amount,
+        },
+    )
+}
+
+/// `link_escrow`, signed by the client; delegates the client's associated token account for `mint`
+/// to the contract's escrow, see [`ContractKeys::escrow_token_account`](crate::escrow::ContractKeys::escrow_token_account)
+pub fn link_escrow(keys: &AuthorizationKeys, escrow_token_account: Pubkey, mint: &Pubkey) -> Instruction {
+    build(
+        accounts::LinkEscrow {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+            client_token_account: get_associated_token_address(&keys.client, mint),
+            escrow_token_account,
+            token_program: token::ID,
+        },
+        instruction::LinkEscrow {},
+    )
+}
+
+/// `revoke_authorization`, signed by the client
+pub fn revoke_authorization(keys: &AuthorizationKeys) -> Instruction {
+    build(
+        accounts::RevokeAuth {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+        },
+        instruction::RevokeAuthorization {},
+    )
+}
+
+/// `update_authorization`, signed by the client
+pub fn update_authorization(
+    keys: &AuthorizationKeys,
+    new_max_per_milestone: Option<u64>,
+    additional_authorized: Option<u64>,
+) -> Instruction {
+    build(
+        accounts::UpdateAuth {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+            config: pda::auth_config().0,
+            client_kyc: pda::kyc_attestation(&keys.client).0,
+            freelancer_kyc: pda::kyc_attestation(&keys.freelancer).0,
+        },
+        instruction::UpdateAuthorization {
+            new_max_per_milestone,
+            additional_authorized,
+        },
+    )
+}
+
+/// `set_co_signer`, signed by both the client and the co-signer
+pub fn set_co_signer(keys: &AuthorizationKeys, co_signer: Pubkey, threshold: u64) -> Instruction {
+    build(
+        accounts::SetCoSigner {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+            co_signer,
+        },
+        instruction::SetCoSigner { threshold },
+    )
+}
+
+/// `update_co_signer`, signed by both the client and the current co-signer
+pub fn update_co_signer(
+    keys: &AuthorizationKeys,
+    co_signer: Pubkey,
+    new_co_signer: Pubkey,
+    threshold: u64,
+) -> Instruction {
+    build(
+        accounts::UpdateCoSigner {
+            payment_authorization: keys.payment_authorization(),
+            client: keys.client,
+            co_signer,
+        },
+        instruction::UpdateCoSigner {
+            new_co_signer,
+            threshold,
+        },
+    )
+}
+
+/// `freeze_authorization`, signed by one of the config's freeze admins
+pub fn freeze_authorization(keys: &AuthorizationKeys, admin: Pubkey) -> Instruction {
+    build(
+        accounts::FreezeAuth {
+            payment_authorization: keys.payment_authorization(),
+            config: pda::auth_config().0,
+            admin,
+        },
+        instruction::FreezeAuthorization {},
+    )
+}
+
+/// `migrate_authorization`, signed by whoever pays for the larger account
+pub fn migrate_authorization(keys: &AuthorizationKeys, payer: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateAuth {
+            payment_authorization: keys.payment_authorization(),
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAuthorization {},
+    )
+}
+
+/// `initialize_config`, signed by the program's upgrade authority
+pub fn initialize_config(
+    authority: Pubkey,
+    admin: Pubkey,
+    freeze_admins: Vec<Pubkey>,
+    compliance_authority: Pubkey,
+    timelock_delay: i64,
+) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            config: pda::auth_config().0,
+            authority,
+            program_data: pda::program_data(&payment_auth::ID).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            admin,
+            freeze_admins,
+            compliance_authority,
+            timelock_delay,
+        },
+    )
+}
+
+/// `queue_config_change`, signed by the config admin and `payer`; `change_id` is the config's current `change_count`
+pub fn queue_config_change(admin: Pubkey, payer: Pubkey, change_id: u64, change: ConfigChange) -> Instruction {
+    build(
+        accounts::QueueConfigChange {
+            config: pda::auth_config().0,
+            pending_change: pda::auth_config_change(change_id).0,
+            admin,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::QueueConfigChange { change },
+    )
+}
+
+/// `execute_config_change`, signed by anyone once the change's eta has passed
+pub fn execute_config_change(caller: Pubkey, change_id: u64, rent_payer: Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteConfigChange {
+            config: pda::auth_config().0,
+            pending_change: pda::auth_config_change(change_id).0,
+            rent_payer,
+            caller,
+        },
+        instruction::ExecuteConfigChange {},
+    )
+}
+
+/// `cancel_config_change`, signed by the config admin or a freeze admin
+pub fn cancel_config_change(canceller: Pubkey, change_id: u64, rent_payer: Pubkey) -> Instruction {
+    build(
+        accounts::CancelConfigChange {
+            config: pda::auth_config().0,
+            pending_change: pda::auth_config_change(change_id).0,
+            rent_payer,
+            canceller,
+        },
+        instruction::CancelConfigChange {},
+    )
+}
+
+/// `add_sanctioned_wallet`, signed by the compliance authority and `payer`
+pub fn add_sanctioned_wallet(compliance_authority: Pubkey, payer: Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::AddSanctionedWallet {
+            config: pda::auth_config().0,
+            sanction_entry: pda::sanction_entry(&wallet).0,
+            compliance_authority,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::AddSanctionedWallet { wallet },
+    )
+}
+
+/// `remove_sanctioned_wallet`, signed by the compliance authority; the rent goes back to the entry's `rent_payer`
+pub fn remove_sanctioned_wallet(compliance_authority: Pubkey, wallet: Pubkey, rent_payer: Pubkey) -> Instruction {
+    build(
+        accounts::RemoveSanctionedWallet {
+            config: pda::auth_config().0,
+            sanction_entry: pda::sanction_entry(&wallet).0,
+            compliance_authority,
+            rent_payer,
+        },
+        instruction::RemoveSanctionedWallet {},
+    )
+}
+
+/// `issue_kyc_attestation`, signed by the compliance authority and `payer`; renews an existing attestation
+pub fn issue_kyc_attestation(
+    compliance_authority: Pubkey,
+    payer: Pubkey,
+    wallet: Pubkey,
+    level: u8,
+    expires_at: i64,
+) -> Instruction {
+    build(
+        accounts::IssueKycAttestation {
+            config: pda::auth_config().0,
+            kyc_attestation: pda::kyc_attestation(&wallet).0,
+            compliance_authority,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::IssueKycAttestation {
+            wallet,
+            level,
+            expires_at,
+        },
+    )
+}
+
+/// `revoke_kyc_attestation`, signed by the compliance authority; the rent goes back to the attestation's `rent_payer`
+pub fn revoke_kyc_attestation(compliance_authority: Pubkey, wallet: Pubkey, rent_payer: Pubkey) -> Instruction {
+    build(
+        accounts::RevokeKycAttestation {
+            config: pda::auth_config().0,
+            kyc_attestation: pda::kyc_attestation(&wallet).0,
+            compliance_authority,
+            rent_payer,
+        },
+        instruction::RevokeKycAttestation {},
+    )
+}
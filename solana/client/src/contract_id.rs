@@ -0,0 +1,83 @@
+//! Fixed-size contract identifiers shared by both programs.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A contract id as stored on chain: the backend's UUID as 32 lowercase hex digits.
+///
+/// The same bytes seed the `contract` address, together with both participants, and the
+/// `payment_auth` address. Parse from a UUID with or without hyphens, in either case.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContractId(pub [u8; 32]);
+
+impl ContractId {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    /// Id a legacy contract has after `migrate_contract`: the legacy id itself when it is
+    /// 32 bytes long, otherwise a hash of it
+    pub fn from_legacy(legacy_id: &str) -> Self {
+        Self(freelance_escrow::FreelanceContract::migrated_contract_id(legacy_id))
+    }
+}
+
+impl From<[u8; 32]> for ContractId {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for ContractId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromStr for ContractId {
+    type Err = ParseContractIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut bytes = [0; 32];
+        let mut len = 0;
+        for byte in value.bytes().filter(|byte| *byte != b'-') {
+            if !byte.is_ascii_hexdigit() || len == bytes.len() {
+                return Err(ParseContractIdError(value.to_string()));
+            }
+            bytes[len] = byte.to_ascii_lowercase();
+            len += 1;
+        }
+        if len != bytes.len() {
+            return Err(ParseContractIdError(value.to_string()));
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Display for ContractId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(&self.0))
+    }
+}
+
+impl fmt::Debug for ContractId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ContractId({self})")
+    }
+}
+
+/// A string that is not a UUID / 32 hex digits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseContractIdError(String);
+
+impl fmt::Display for ParseContractIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid contract id {:?}: expected a UUID or 32 hex digits", self.0)
+    }
+}
+
+impl std::error::Error for ParseContractIdError {}
@@ -0,0 +1,133 @@
+//! Mapping of custom instruction error codes back to each program's `ErrorCode`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::InstructionError;
+
+pub use freelance_escrow::ErrorCode as EscrowErrorCode;
+pub use payment_auth::ErrorCode as PaymentAuthErrorCode;
+
+/// A decoded custom error returned by one of the programs
+#[derive(Debug, Clone, Copy)]
+pub enum ProgramError {
+    Escrow(EscrowErrorCode),
+    PaymentAuth(PaymentAuthErrorCode),
+    /// Anchor framework error (account constraints, deserialization, ...)
+    Anchor(u32),
+}
+
+const ESCROW_ERRORS: &[EscrowErrorCode] = &[
+    EscrowErrorCode::ContractNotActive,
+    EscrowErrorCode::InvalidMilestone,
+    EscrowErrorCode::MilestoneAlreadyApproved,
+    EscrowErrorCode::InvalidContractState,
+    EscrowErrorCode::InvalidApprovalBps,
+    EscrowErrorCode::ApprovalExceedsRemaining,
+    EscrowErrorCode::InsufficientEscrowBalance,
+    EscrowErrorCode::InvalidBonusAmount,
+    EscrowErrorCode::InvalidStreamSchedule,
+    EscrowErrorCode::NotStreamingContract,
+    EscrowErrorCode::NothingToWithdraw,
+    EscrowErrorCode::InvalidHourlyTerms,
+    EscrowErrorCode::NotHourlyContract,
+    EscrowErrorCode::InvalidTimesheetHours,
+    EscrowErrorCode::ExceedsWeeklyCap,
+    EscrowErrorCode::InvalidTimesheetPeriod,
+    EscrowErrorCode::TimesheetNotPending,
+    EscrowErrorCode::ExceedsContractBudget,
+    EscrowErrorCode::InvalidPayeeSplit,
+    EscrowErrorCode::PayeeSplitRequired,
+    EscrowErrorCode::PayeeAccountsMismatch,
+    EscrowErrorCode::InvalidApproverSet,
+    EscrowErrorCode::NotAnApprover,
+    EscrowErrorCode::AlreadySigned,
+    EscrowErrorCode::ApprovalAlreadyExecuted,
+    EscrowErrorCode::ApproverSetRequired,
+    EscrowErrorCode::ApprovalMismatch,
+    EscrowErrorCode::ApprovalThresholdNotMet,
+    EscrowErrorCode::UnauthorizedClient,
+    EscrowErrorCode::AuthorizationMismatch,
+    EscrowErrorCode::EscrowAlreadyFunded,
+    EscrowErrorCode::AccountAlreadyMigrated,
+    EscrowErrorCode::UnknownAccountLayout,
+    EscrowErrorCode::InvalidContractId,
+    EscrowErrorCode::ProgramPaused,
+    EscrowErrorCode::NotGuardian,
+    EscrowErrorCode::NotUpgradeAuthority,
+    EscrowErrorCode::NotConfigAdmin,
+    EscrowErrorCode::InvalidTimelockDelay,
+    EscrowErrorCode::TimelockNotElapsed,
+    EscrowErrorCode::SanctionedParty,
+    EscrowErrorCode::KycRequired,
+    EscrowErrorCode::InvalidCoSigner,
+    EscrowErrorCode::UnauthorizedCoSigner,
+    EscrowErrorCode::CoSignerRequired,
+    EscrowErrorCode::InvalidApprovalSignature,
+    EscrowErrorCode::ApprovalSignatureExpired,
+    EscrowErrorCode::InvalidApprovalNonce,
+    EscrowErrorCode::SignedApprovalUnsupported,
+    EscrowErrorCode::InvalidMilestoneBatch,
+    EscrowErrorCode::BatchApprovalUnsupported,
+    EscrowErrorCode::InvalidDeliverables,
+    EscrowErrorCode::NotADisputeParty,
+    EscrowErrorCode::EvidenceWindowClosed,
+    EscrowErrorCode::EvidenceLimitReached,
+    EscrowErrorCode::InvalidEvidence,
+    EscrowErrorCode::NotTheProposer,
+    EscrowErrorCode::MintMismatch,
+];
+
+const PAYMENT_AUTH_ERRORS: &[PaymentAuthErrorCode] = &[
+    PaymentAuthErrorCode::InvalidAmount,
+    PaymentAuthErrorCode::ExceedsPerMilestone,
+    PaymentAuthErrorCode::ExceedsTotal,
+    PaymentAuthErrorCode::AuthorizationInactive,
+    PaymentAuthErrorCode::UnauthorizedClient,
+    PaymentAuthErrorCode::UnauthorizedFreelancer,
+    PaymentAuthErrorCode::EscrowNotLinked,
+    PaymentAuthErrorCode::AccountAlreadyMigrated,
+    PaymentAuthErrorCode::UnknownAccountLayout,
+    PaymentAuthErrorCode::InvalidContractId,
+    PaymentAuthErrorCode::UnauthorizedAdmin,
+    PaymentAuthErrorCode::NotUpgradeAuthority,
+    PaymentAuthErrorCode::TooManyFreezeAdmins,
+    PaymentAuthErrorCode::InvalidTimelockDelay,
+    PaymentAuthErrorCode::TimelockNotElapsed,
+    PaymentAuthErrorCode::NotComplianceAuthority,
+    PaymentAuthErrorCode::SanctionedParty,
+    PaymentAuthErrorCode::KycRequired,
+    PaymentAuthErrorCode::InvalidKycAttestation,
+    PaymentAuthErrorCode::InvalidCoSigner,
+    PaymentAuthErrorCode::CoSignerAlreadySet,
+    PaymentAuthErrorCode::UnauthorizedCoSigner,
+    PaymentAuthErrorCode::CoSignerRequired,
+];
+
+/// Decode a custom error code returned by `program_id`
+pub fn decode_error(program_id: &Pubkey, code: u32) -> Option<ProgramError> {
+    if code < anchor_lang::error::ERROR_CODE_OFFSET {
+        return Some(ProgramError::Anchor(code));
+    }
+    if *program_id == freelance_escrow::ID {
+        ESCROW_ERRORS
+            .iter()
+            .copied()
+            .find(|error| u32::from(*error) == code)
+            .map(ProgramError::Escrow)
+    } else if *program_id == payment_auth::ID {
+        PAYMENT_AUTH_ERRORS
+            .iter()
+            .copied()
+            .find(|error| u32::from(*error) == code)
+            .map(ProgramError::PaymentAuth)
+    } else {
+        None
+    }
+}
+
+/// Decode the custom error inside a failed instruction of `program_id`
+pub fn decode_instruction_error(program_id: &Pubkey, error: &InstructionError) -> Option<ProgramError> {
+    match error {
+        InstructionError::Custom(code) => decode_error(program_id, *code),
+        _ => None,
+    }
+}
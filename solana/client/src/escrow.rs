@@ -0,0 +1,887 @@
+//! Instruction builders for the freelance_escrow program.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{ed25519_program, sysvar};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use freelance_escrow::{accounts, instruction, ConfigChange, Deliverable, Payee};
+
+use crate::{pda, ContractId};
+
+/// Participants and token accounts of one contract.
+///
+/// Token accounts default to the participants' associated token accounts for `mint`;
+/// overwrite the fields to use other accounts. Set `legacy_seeds` for contracts created
+/// before addresses included the participants, whose accounts are derived from the id alone.
+/// A legacy id that was not 32 bytes long goes in `legacy_id`; `contract_id` is then
+/// [`ContractId::from_legacy`] of it.
+#[derive(Clone, Debug)]
+pub struct ContractKeys {
+    pub contract_id: ContractId,
+    pub freelancer: Pubkey,
+    pub client: Pubkey,
+    pub mint: Pubkey,
+    pub client_token_account: Pubkey,
+    pub freelancer_token_account: Pubkey,
+    pub legacy_seeds: bool,
+    /// Id a legacy contract was created under, when it differs from `contract_id`
+    pub legacy_id: Option<String>,
+    /// Pays the rent of accounts the participants create; `None` leaves it to the signing participant
+    pub payer: Option<Pubkey>,
+}
+
+impl ContractKeys {
+    pub fn new(contract_id: ContractId, freelancer: Pubkey, client: Pubkey, mint: Pubkey) -> Self {
+        Self {
+            contract_id,
+            freelancer,
+            client,
+            mint,
+            client_token_account: get_associated_token_address(&client, &mint),
+            freelancer_token_account: get_associated_token_address(&freelancer, &mint),
+            legacy_seeds: false,
+            legacy_id: None,
+            payer: None,
+        }
+    }
+
+    /// Rent payer for an instruction signed by `party`
+    pub fn payer_or(&self, party: Pubkey) -> Pubkey {
+        self.payer.unwrap_or(party)
+    }
+
+    /// Seed of a legacy contract's addresses
+    pub fn legacy_seed(&self) -> &[u8] {
+        self.legacy_id.as_deref().map_or(self.contract_id.as_ref(), str::as_bytes)
+    }
+
+    /// The client's payment authorization for the contract, at its legacy address for a short legacy id
+    pub fn payment_authorization(&self) -> Pubkey {
+        match &self.legacy_id {
+            Some(legacy_id) => pda::legacy_payment_authorization(&self.client, legacy_id.as_bytes()).0,
+            None => pda::payment_authorization(&self.client, &self.contract_id).0,
+        }
+    }
+
+    pub fn contract(&self) -> Pubkey {
+        if self.legacy_seeds {
+            pda::legacy_contract(self.legacy_seed()).0
+        } else {
+            pda::contract(&self.freelancer, &self.client, &self.contract_id).0
+        }
+    }
+
+    pub fn escrow_authority(&self) -> Pubkey {
+        if self.legacy_seeds {
+            pda::legacy_escrow_authority(self.legacy_seed()).0
+        } else {
+            pda::escrow_authority(&self.contract()).0
+        }
+    }
+
+    pub fn escrow_token_account(&self) -> Pubkey {
+        pda::escrow_token_account(&self.escrow_authority(), &self.mint)
+    }
+
+    pub fn milestone(&self, milestone_index: u8) -> Pubkey {
+        pda::milestone(&self.contract(), milestone_index).0
+    }
+
+    pub fn dispute(&self) -> Pubkey {
+        pda::dispute(&self.contract()).0
+    }
+}
+
+/// Extra accounts for instructions that move funds out of escrow.
+#[derive(Clone, Debug, Default)]
+pub struct ReleaseOptions {
+    /// Payee token accounts in split order, for contracts with a payee split
+    pub payee_token_accounts: Vec<Pubkey>,
+    /// Payee wallets in split order, whose sanctions blocklist entries every release passes
+    pub payee_wallets: Vec<Pubkey>,
+    /// Pass the approver set and milestone approval, for contracts with an approver set
+    pub approver_set: bool,
+    /// Pass the co-signer policy, for contracts with a co-signer
+    pub co_signer_policy: bool,
+    /// Co-signer signing along, for releases and refunds above the policy threshold
+    pub co_signer: Option<Pubkey>,
+}
+
+impl ReleaseOptions {
+    fn payee_split(&self, contract: &Pubkey) -> Option<Pubkey> {
+        (!self.payee_token_accounts.is_empty()).then(|| pda::payee_split(contract).0)
+    }
+
+    // Payee token accounts followed by each payee's blocklist entry
+    fn remaining_accounts(&self) -> impl Iterator<Item = AccountMeta> + '_ {
+        self.payee_token_accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false))
+            .chain(
+                self.payee_wallets
+                    .iter()
+                    .map(|wallet| AccountMeta::new_readonly(pda::sanction_entry(wallet).0, false)),
+            )
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: freelance_escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn with_remaining(mut ix: Instruction, remaining: impl Iterator<Item = AccountMeta>) -> Instruction {
+    ix.accounts.extend(remaining);
+    ix
+}
+
+fn create_accounts(keys: &ContractKeys) -> accounts::CreateContract {
+    accounts::CreateContract {
+        contract: keys.contract(),
+        freelancer: keys.freelancer,
+        payer: keys.payer_or(keys.freelancer),
+        client: keys.client,
+        mint: keys.mint,
+        config: pda::config().0,
+        freelancer_kyc: pda::kyc_attestation(&keys.freelancer).0,
+        client_kyc: pda::kyc_attestation(&keys.client).0,
+        system_program: system_program::ID,
+    }
+}
+
+/// `create_contract`, signed by the freelancer
+pub fn create_contract(keys: &ContractKeys, total_amount: u64, milestone_count: u8) -> Instruction {
+    build(
+        create_accounts(keys),
+        instruction::CreateContract {
+            contract_id: keys.contract_id.0,
+            total_amount,
+            milestone_count,
+        },
+    )
+}
+
+/// `create_streaming_contract`, signed by the freelancer
+pub fn create_streaming_contract(
+    keys: &ContractKeys,
+    total_amount: u64,
+    stream_start: i64,
+    stream_end: i64,
+) -> Instruction {
+    build(
+        create_accounts(keys),
+        instruction::CreateStreamingContract {
+            contract_id: keys.contract_id.0,
+            total_amount,
+            stream_start,
+            stream_end,
+        },
+    )
+}
+
+/// `create_hourly_contract`, signed by the freelancer
+pub fn create_hourly_contract(
+    keys: &ContractKeys,
+    total_amount: u64,
+    hourly_rate: u64,
+    weekly_hour_cap: u16,
+) -> Instruction {
+    build(
+        create_accounts(keys),
+        instruction::CreateHourlyContract {
+            contract_id: keys.contract_id.0,
+            total_amount,
+            hourly_rate,
+            weekly_hour_cap,
+        },
+    )
+}
+
+/// `set_payee_split`, signed by the freelancer
+pub fn set_payee_split(keys: &ContractKeys, payees: Vec<Payee>) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::SetPayeeSplit {
+            contract,
+            payee_split: pda::payee_split(&contract).0,
+            freelancer: keys.freelancer,
+            payer: keys.payer_or(keys.freelancer),
+            system_program: system_program::ID,
+        },
+        instruction::SetPayeeSplit { payees },
+    )
+}
+
+/// `update_payee_split`, signed by both the freelancer and the client
+pub fn update_payee_split(keys: &ContractKeys, payees: Vec<Payee>) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::UpdatePayeeSplit {
+            contract,
+            payee_split: pda::payee_split(&contract).0,
+            freelancer: keys.freelancer,
+            client: keys.client,
+        },
+        instruction::UpdatePayeeSplit { payees },
+    )
+}
+
+/// `set_co_signer`, signed by both the client and the co-signer
+pub fn set_co_signer(keys: &ContractKeys, co_signer: Pubkey, threshold: u64) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::SetCoSigner {
+            contract,
+            co_signer_policy: pda::co_signer_policy(&contract).0,
+            client: keys.client,
+            payer: keys.payer_or(keys.client),
+            co_signer,
+            system_program: system_program::ID,
+        },
+        instruction::SetCoSigner { threshold },
+    )
+}
+
+/// `update_co_signer`, signed by both the client and the current co-signer
+pub fn update_co_signer(
+    keys: &ContractKeys,
+    co_signer: Pubkey,
+    new_co_signer: Pubkey,
+    threshold: u64,
+) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::UpdateCoSigner {
+            contract,
+            co_signer_policy: pda::co_signer_policy(&contract).0,
+            client: keys.client,
+            co_signer,
+        },
+        instruction::UpdateCoSigner {
+            new_co_signer,
+            threshold,
+        },
+    )
+}
+
+/// `set_approver_set`, signed by the client
+pub fn set_approver_set(keys: &ContractKeys, members: Vec<Pubkey>, threshold: u8) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::SetApproverSet {
+            contract,
+            approver_set: pda::approver_set(&contract).0,
+            client: keys.client,
+            payer: keys.payer_or(keys.client),
+            system_program: system_program::ID,
+        },
+        instruction::SetApproverSet { members, threshold },
+    )
+}
+
+/// `deposit_funds`, signed by the client
+pub fn deposit_funds(keys: &ContractKeys, amount: u64) -> Instruction {
+    build(
+        accounts::DepositFunds {
+            config: pda::config().0,
+            contract: keys.contract(),
+            client: keys.client,
+            payer: keys.payer_or(keys.client),
+            client_token_account: keys.client_token_account,
+            escrow_account: keys.escrow_authority(),
+            escrow_token_account: keys.escrow_token_account(),
+            usdc_mint: keys.mint,
+            client_sanction: pda::sanction_entry(&keys.client).0,
+            freelancer_sanction: pda::sanction_entry(&keys.freelancer).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DepositFunds { amount },
+    )
+}
+
+/// `fund_from_authorization`; permissionless, any payer can submit it
+pub fn fund_from_authorization(keys: &ContractKeys) -> Instruction {
+    build(
+        accounts::FundFromAuthorization {
+            config: pda::config().0,
+            contract: keys.contract(),
+            payment_authorization: keys.payment_authorization(),
+            escrow_account: keys.escrow_authority(),
+            escrow_token_account: keys.escrow_token_account(),
+            client_token_account: keys.client_token_account,
+            client_sanction: pda::sanction_entry(&keys.client).0,
+            freelancer_sanction: pda::sanction_entry(&keys.freelancer).0,
+            payment_auth_program: payment_auth::ID,
+            token_program: token::ID,
+        },
+        instruction::FundFromAuthorization {},
+    )
+}
+
+/// `submit_milestone`, signed by the freelancer
+pub fn submit_milestone(keys: &ContractKeys, milestone_index: u8, proof_uri: &str) -> Instruction {
+    build(
+        accounts::SubmitMilestone {
+            config: pda::config().0,
+            contract: keys.contract(),
+            milestone: keys.milestone(milestone_index),
+            freelancer: keys.freelancer,
+            payer: keys.payer_or(keys.freelancer),
+            system_program: system_program::ID,
+        },
+        instruction::SubmitMilestone {
+            milestone_index,
+            proof_uri: proof_uri.to_string(),
+        },
+    )
+}
+
+/// `submit_milestone_deliverables`, signed by the freelancer; the deliverables cannot change afterwards
+pub fn submit_milestone_deliverables(keys: &ContractKeys, milestone_index: u8, items: Vec<Deliverable>) -> Instruction {
+    let milestone = keys.milestone(milestone_index);
+    build(
+        accounts::SubmitMilestoneDeliverables {
+            config: pda::config().0,
+            contract: keys.contract(),
+            milestone,
+            deliverables: pda::deliverables(&milestone).0,
+            freelancer: keys.freelancer,
+            payer: keys.payer_or(keys.freelancer),
+            system_program: system_program::ID,
+        },
+        instruction::SubmitMilestoneDeliverables { milestone_index, items },
+    )
+}
+
+fn approve_accounts(
+    keys: &ContractKeys,
+    approver: Pubkey,
+    milestone_index: u8,
+    options: &ReleaseOptions,
+) -> accounts::ApproveMilestone {
+    let contract = keys.contract();
+    let milestone = keys.milestone(milestone_index);
+    accounts::ApproveMilestone {
+        config: pda::config().0,
+        contract,
+        milestone,
+        client: approver,
+        escrow_account: keys.escrow_authority(),
+        escrow_token_account: keys.escrow_token_account(),
+        freelancer_token_account: keys.freelancer_token_account,
+        payee_split: options.payee_split(&contract),
+        approver_set: options.approver_set.then(|| pda::approver_set(&contract).0),
+        milestone_approval: options
+            .approver_set
+            .then(|| pda::milestone_approval(&milestone).0),
+        co_signer_policy: options.co_signer_policy.then(|| pda::co_signer_policy(&contract).0),
+        co_signer: options.co_signer,
+        instructions: None,
+        client_sanction: pda::sanction_entry(&keys.client).0,
+        recipient_sanction: pda::sanction_entry(&keys.freelancer).0,
+        token_program: token::ID,
+    }
+}
+
+/// `approve_milestone`, signed by the client or, with an approver set, any approver
+pub fn approve_milestone(
+    keys: &ContractKeys,
+    approver: Pubkey,
+    milestone_index: u8,
+    options: &ReleaseOptions,
+) -> Instruction {
+    with_remaining(
+        build(
+            approve_accounts(keys, approver, milestone_index, options),
+            instruction::ApproveMilestone { milestone_index },
+        ),
+        options.remaining_accounts(),
+    )
+}
+
+/// `approve_milestone_partial`, signed by the client or, with an approver set, any approver
+pub fn approve_milestone_partial(
+    keys: &ContractKeys,
+    approver: Pubkey,
+    milestone_index: u8,
+    approval_bps: u16,
+    options: &ReleaseOptions,
+) -> Instruction {
+    with_remaining(
+        build(
+            approve_accounts(keys, approver, milestone_index, options),
+            instruction::ApproveMilestonePartial {
+                milestone_index,
+                approval_bps,
+            },
+        ),
+        options.remaining_accounts(),
+    )
+}
+
+/// `approve_milestones`, signed by the client; pays every listed milestone in full in one transfer.
+/// Not available to contracts with an approver set
+pub fn approve_milestones(keys: &ContractKeys, milestone_indices: Vec<u8>, options: &ReleaseOptions) -> Instruction {
+    let contract = keys.contract();
+    let milestones: Vec<_> = milestone_indices
+        .iter()
+        .map(|index| AccountMeta::new(keys.milestone(*index), false))
+        .collect();
+    with_remaining(
+        build(
+            accounts::ApproveMilestones {
+                config: pda::config().0,
+                contract,
+                client: keys.client,
+                escrow_account: keys.escrow_authority(),
+                escrow_token_account: keys.escrow_token_account(),
+                freelancer_token_account: keys.freelancer_token_account,
+                payee_split: options.payee_split(&contract),
+                co_signer_policy: options.co_signer_policy.then(|| pda::co_signer_policy(&contract).0),
+                co_signer: options.co_signer,
+                client_sanction: pda::sanction_entry(&keys.client).0,
+                recipient_sanction: pda::sanction_entry(&keys.freelancer).0,
+                token_program: token::ID,
+            },
+            instruction::ApproveMilestones { milestone_indices },
+        ),
+        milestones.into_iter().chain(options.remaining_accounts()),
+    )
+}
+
+/// Bytes the client signs off-chain for [`approve_milestone_signed`]; `amount` is the release the
+/// approval pays out and `nonce` the contract's current `approval_nonce`
+pub fn signed_approval_message(
+    keys: &ContractKeys,
+    milestone_index: u8,
+    amount: u64,
+    nonce: u64,
+    expires_at: i64,
+) -> Vec<u8> {
+    freelance_escrow::signed_approval_message(&keys.contract(), milestone_index, amount, nonce, expires_at)
+}
+
+/// Ed25519 program instruction verifying one `signature` by `signer` over `message`, with the key,
+/// signature and message all stored in the instruction itself
+pub fn ed25519_verify_instruction(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+    const DATA_START: u16 = 16;
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// `approve_milestone_signed`, signed and paid for by `relayer`; send it right after
+/// [`ed25519_verify_instruction`] over the client's [`signed_approval_message`]
+pub fn approve_milestone_signed(
+    keys: &ContractKeys,
+    relayer: Pubkey,
+    milestone_index: u8,
+    approval_bps: u16,
+    nonce: u64,
+    expires_at: i64,
+    options: &ReleaseOptions,
+) -> Instruction {
+    let mut accounts = approve_accounts(keys, relayer, milestone_index, options);
+    accounts.instructions = Some(sysvar::instructions::ID);
+    with_remaining(
+        build(
+            accounts,
+            instruction::ApproveMilestoneSigned {
+                milestone_index,
+                approval_bps,
+                nonce,
+                expires_at,
+            },
+        ),
+        options.remaining_accounts(),
+    )
+}
+
+/// `propose_milestone_approval`, signed by an approver
+pub fn propose_milestone_approval(
+    keys: &ContractKeys,
+    approver: Pubkey,
+    milestone_index: u8,
+    approval_bps: u16,
+) -> Instruction {
+    let contract = keys.contract();
+    let milestone = keys.milestone(milestone_index);
+    build(
+        accounts::ProposeMilestoneApproval {
+            contract,
+            milestone,
+            approver_set: pda::approver_set(&contract).0,
+            milestone_approval: pda::milestone_approval(&milestone).0,
+            approver,
+            payer: keys.payer_or(approver),
+            system_program: system_program::ID,
+        },
+        instruction::ProposeMilestoneApproval {
+            milestone_index,
+            approval_bps,
+        },
+    )
+}
+
+fn sign_accounts(
+    keys: &ContractKeys,
+    approver: Pubkey,
+    milestone_index: u8,
+) -> accounts::SignMilestoneApproval {
+    let contract = keys.contract();
+    let milestone = keys.milestone(milestone_index);
+    accounts::SignMilestoneApproval {
+        contract,
+        milestone,
+        approver_set: pda::approver_set(&contract).0,
+        milestone_approval: pda::milestone_approval(&milestone).0,
+        approver,
+    }
+}
+
+/// `revise_milestone_approval`, signed by the round's proposer or, once it has executed, any approver
+pub fn revise_milestone_approval(
+    keys: &ContractKeys,
+    approver: Pubkey,
+    milestone_index: u8,
+    approval_bps: u16,
+) -> Instruction {
+    build(
+        sign_accounts(keys, approver, milestone_index),
+        instruction::ReviseMilestoneApproval {
+            milestone_index,
+            approval_bps,
+        },
+    )
+}
+
+/// `sign_milestone_approval`, signed by an approver
+pub fn sign_milestone_approval(keys: &ContractKeys, approver: Pubkey, milestone_index: u8) -> Instruction {
+    build(
+        sign_accounts(keys, approver, milestone_index),
+        instruction::SignMilestoneApproval { milestone_index },
+    )
+}
+
+/// `pay_bonus`, signed by the client
+pub fn pay_bonus(keys: &ContractKeys, amount: u64, from_escrow: bool, options: &ReleaseOptions) -> Instruction {
+    let contract = keys.contract();
+    with_remaining(
+        build(
+            accounts::PayBonus {
+                config: pda::config().0,
+                contract,
+                client: keys.client,
+                client_token_account: keys.client_token_account,
+                escrow_account: keys.escrow_authority(),
+                escrow_token_account: keys.escrow_token_account(),
+                freelancer_token_account: keys.freelancer_token_account,
+                payee_split: options.payee_split(&contract),
+                client_sanction: pda::sanction_entry(&keys.client).0,
+                recipient_sanction: pda::sanction_entry(&keys.freelancer).0,
+                co_signer_policy: options.co_signer_policy.then(|| pda::co_signer_policy(&contract).0),
+                co_signer: options.co_signer,
+                token_program: token::ID,
+            },
+            instruction::PayBonus { amount, from_escrow },
+        ),
+        options.remaining_accounts(),
+    )
+}
+
+/// `withdraw_vested`, signed by the freelancer
+pub fn withdraw_vested(keys: &ContractKeys, options: &ReleaseOptions) -> Instruction {
+    let contract = keys.contract();
+    with_remaining(
+        build(
+            accounts::WithdrawVested {
+                contract,
+                freelancer: keys.freelancer,
+                escrow_account: keys.escrow_authority(),
+                escrow_token_account: keys.escrow_token_account(),
+                freelancer_token_account: keys.freelancer_token_account,
+                payee_split: options.payee_split(&contract),
+                client_sanction: pda::sanction_entry(&keys.client).0,
+                recipient_sanction: pda::sanction_entry(&keys.freelancer).0,
+                token_program: token::ID,
+            },
+            instruction::WithdrawVested {},
+        ),
+        options.remaining_accounts(),
+    )
+}
+
+/// `cancel_stream`, signed by the client
+pub fn cancel_stream(keys: &ContractKeys, options: &ReleaseOptions) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::CancelStream {
+            contract,
+            client: keys.client,
+            escrow_account: keys.escrow_authority(),
+            escrow_token_account: keys.escrow_token_account(),
+            client_token_account: keys.client_token_account,
+            client_sanction: pda::sanction_entry(&keys.client).0,
+            co_signer_policy: options.co_signer_policy.then(|| pda::co_signer_policy(&contract).0),
+            co_signer: options.co_signer,
+            token_program: token::ID,
+        },
+        instruction::CancelStream {},
+    )
+}
+
+/// `submit_timesheet`, signed by the freelancer
+pub fn submit_timesheet(
+    keys: &ContractKeys,
+    week_index: u32,
+    hours: u16,
+    description_hash: [u8; 32],
+) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::SubmitTimesheet {
+            contract,
+            timesheet: pda::timesheet(&contract, week_index).0,
+            freelancer: keys.freelancer,
+            payer: keys.payer_or(keys.freelancer),
+            system_program: system_program::ID,
+        },
+        instruction::SubmitTimesheet {
+            week_index,
+            hours,
+            description_hash,
+        },
+    )
+}
+
+/// `approve_timesheet`, signed by the client
+pub fn approve_timesheet(keys: &ContractKeys, week_index: u32, options: &ReleaseOptions) -> Instruction {
+    let contract = keys.contract();
+    with_remaining(
+        build(
+            accounts::ApproveTimesheet {
+                config: pda::config().0,
+                contract,
+                timesheet: pda::timesheet(&contract, week_index).0,
+                client: keys.client,
+                escrow_account: keys.escrow_authority(),
+                escrow_token_account: keys.escrow_token_account(),
+                freelancer_token_account: keys.freelancer_token_account,
+                payee_split: options.payee_split(&contract),
+                client_sanction: pda::sanction_entry(&keys.client).0,
+                recipient_sanction: pda::sanction_entry(&keys.freelancer).0,
+                co_signer_policy: options.co_signer_policy.then(|| pda::co_signer_policy(&contract).0),
+                co_signer: options.co_signer,
+                token_program: token::ID,
+            },
+            instruction::ApproveTimesheet {},
+        ),
+        options.remaining_accounts(),
+    )
+}
+
+/// `dispute_timesheet`, signed by the client
+pub fn dispute_timesheet(keys: &ContractKeys, week_index: u32) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::DisputeTimesheet {
+            contract,
+            timesheet: pda::timesheet(&contract, week_index).0,
+            client: keys.client,
+        },
+        instruction::DisputeTimesheet {},
+    )
+}
+
+/// `dispute_contract`, signed by the client; only the co-signer fields of `options` are used
+pub fn dispute_contract(keys: &ContractKeys, reason: &str, options: &ReleaseOptions) -> Instruction {
+    let contract = keys.contract();
+    build(
+        accounts::DisputeContract {
+            contract,
+            dispute: keys.dispute(),
+            client: keys.client,
+            payer: keys.payer_or(keys.client),
+            escrow_account: keys.escrow_authority(),
+            escrow_token_account: keys.escrow_token_account(),
+            client_token_account: keys.client_token_account,
+            client_sanction: pda::sanction_entry(&keys.client).0,
+            co_signer_policy: options.co_signer_policy.then(|| pda::co_signer_policy(&contract).0),
+            co_signer: options.co_signer,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DisputeContract {
+            reason: reason.to_string(),
+        },
+    )
+}
+
+/// `submit_dispute_evidence`, signed by the client or the freelancer; `evidence_index` is the
+/// number of items `submitter` has already filed
+pub fn submit_dispute_evidence(
+    keys: &ContractKeys,
+    submitter: Pubkey,
+    evidence_index: u8,
+    hash: [u8; 32],
+    uri: &str,
+) -> Instruction {
+    let dispute = keys.dispute();
+    build(
+        accounts::SubmitDisputeEvidence {
+            contract: keys.contract(),
+            dispute,
+            evidence: pda::dispute_evidence(&dispute, &submitter, evidence_index).0,
+            submitter,
+            payer: keys.payer_or(submitter),
+            system_program: system_program::ID,
+        },
+        instruction::SubmitDisputeEvidence {
+            evidence_index,
+            hash,
+            uri: uri.to_string(),
+        },
+    )
+}
+
+/// `reconcile_escrow`, signed by anyone
+pub fn reconcile_escrow(keys: &ContractKeys, caller: Pubkey) -> Instruction {
+    build(
+        accounts::ReconcileEscrow {
+            contract: keys.contract(),
+            escrow_account: keys.escrow_authority(),
+            escrow_token_account: keys.escrow_token_account(),
+            client_token_account: keys.client_token_account,
+            client_sanction: pda::sanction_entry(&keys.client).0,
+            caller,
+            token_program: token::ID,
+        },
+        instruction::ReconcileEscrow {},
+    )
+}
+
+/// `migrate_contract`, signed by whoever pays for a `Dispute` account if one is created;
+/// rent freed by the compact layout goes back to the freelancer. A client payer also records
+/// `keys.mint` as the escrow mint; otherwise the client's next deposit does
+pub fn migrate_contract(keys: &ContractKeys, payer: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateContract {
+            contract: keys.contract(),
+            dispute: keys.dispute(),
+            freelancer: keys.freelancer,
+            mint: (payer == keys.client).then_some(keys.mint),
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateContract {},
+    )
+}
+
+/// `migrate_milestone`, signed by whoever pays for the larger account; the contract must be migrated first
+pub fn migrate_milestone(keys: &ContractKeys, payer: Pubkey, milestone_index: u8) -> Instruction {
+    build(
+        accounts::MigrateMilestone {
+            account: keys.milestone(milestone_index),
+            contract: keys.contract(),
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateMilestone {},
+    )
+}
+
+/// `initialize_config`, signed by the program's upgrade authority
+pub fn initialize_config(authority: Pubkey, guardian: Pubkey, admin: Pubkey, timelock_delay: i64) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            config: pda::config().0,
+            authority,
+            program_data: pda::program_data(&freelance_escrow::ID).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            guardian,
+            admin,
+            timelock_delay,
+        },
+    )
+}
+
+/// `set_paused`, signed by the guardian
+pub fn set_paused(guardian: Pubkey, paused: bool) -> Instruction {
+    build(
+        accounts::SetPaused {
+            config: pda::config().0,
+            guardian,
+        },
+        instruction::SetPaused { paused },
+    )
+}
+
+/// `queue_config_change`, signed by the config admin and `payer`; `change_id` is the config's current `change_count`
+pub fn queue_config_change(admin: Pubkey, payer: Pubkey, change_id: u64, change: ConfigChange) -> Instruction {
+    build(
+        accounts::QueueConfigChange {
+            config: pda::config().0,
+            pending_change: pda::config_change(change_id).0,
+            admin,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::QueueConfigChange { change },
+    )
+}
+
+/// `execute_config_change`, signed by anyone once the change's eta has passed
+pub fn execute_config_change(caller: Pubkey, change_id: u64, rent_payer: Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteConfigChange {
+            config: pda::config().0,
+            pending_change: pda::config_change(change_id).0,
+            rent_payer,
+            caller,
+        },
+        instruction::ExecuteConfigChange {},
+    )
+}
+
+/// `cancel_config_change`, signed by the config admin or the guardian
+pub fn cancel_config_change(canceller: Pubkey, change_id: u64, rent_payer: Pubkey) -> Instruction {
+    build(
+        accounts::CancelConfigChange {
+            config: pda::config().0,
+            pending_change: pda::config_change(change_id).0,
+            rent_payer,
+            canceller,
+        },
+        instruction::CancelConfigChange {},
+    )
+}
@@ -0,0 +1,201 @@
+//! Decoders for events emitted by freelance_escrow and payment_auth.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+pub use freelance_escrow::{
+    ApproverSetCreated, BonusPaid, CoSignerUpdated, ContractDisputed, EscrowReconciled, EvidenceSubmitted,
+    FundsDeposited, MilestoneApprovalSigned, MilestoneApproved, MilestoneSubmitted, PayeeSplitUpdated,
+    StreamCancelled, TimesheetApproved, TimesheetDisputed, TimesheetSubmitted, VestedWithdrawn,
+};
+pub use payment_auth::{
+    AuthorizationCoSignerUpdated, AuthorizationCreated, AuthorizationFrozen, AuthorizationRevoked,
+    AuthorizationUpdated, EscrowFunded, EscrowLinked, MilestonePaymentProcessed,
+};
+
+use crate::ContractId;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! program_events {
+    ($(#[$doc:meta])* $name:ident { $($event:ident),* $(,)? }) => {
+        $(#[$doc])*
+        pub enum $name {
+            $($event($event),)*
+        }
+
+        impl $name {
+            /// Decode discriminator-prefixed event data as written by `emit!`
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let (discriminator, mut payload) = data.split_at(8);
+                $(
+                    if discriminator == $event::DISCRIMINATOR {
+                        return $event::deserialize(&mut payload).ok().map($name::$event);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$event(_) => stringify!($event),)*
+                }
+            }
+
+            /// Contract the event belongs to; every event of both programs carries one
+            pub fn contract_id(&self) -> ContractId {
+                match self {
+                    $($name::$event(event) => ContractId(event.contract_id),)*
+                }
+            }
+
+            pub fn timestamp(&self) -> i64 {
+                match self {
+                    $($name::$event(event) => event.timestamp,)*
+                }
+            }
+        }
+    };
+}
+
+program_events!(
+    /// Any event emitted by freelance_escrow
+    EscrowEvent {
+        PayeeSplitUpdated,
+        CoSignerUpdated,
+        ApproverSetCreated,
+        MilestoneApprovalSigned,
+        FundsDeposited,
+        MilestoneSubmitted,
+        MilestoneApproved,
+        BonusPaid,
+        VestedWithdrawn,
+        StreamCancelled,
+        TimesheetSubmitted,
+        TimesheetApproved,
+        TimesheetDisputed,
+        ContractDisputed,
+        EvidenceSubmitted,
+        EscrowReconciled,
+    }
+);
+
+program_events!(
+    /// Any event emitted by payment_auth
+    PaymentAuthEvent {
+        AuthorizationCreated,
+        MilestonePaymentProcessed,
+        EscrowLinked,
+        EscrowFunded,
+        AuthorizationUpdated,
+        AuthorizationRevoked,
+        AuthorizationFrozen,
+        AuthorizationCoSignerUpdated,
+    }
+);
+
+macro_rules! config_events {
+    ($(#[$doc:meta])* $name:ident { $($variant:ident($event:path)),* $(,)? }) => {
+        $(#[$doc])*
+        pub enum $name {
+            $($variant($event),)*
+        }
+
+        impl $name {
+            /// Decode discriminator-prefixed event data as written by `emit!`
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let (discriminator, mut payload) = data.split_at(8);
+                $(
+                    if discriminator == <$event>::DISCRIMINATOR {
+                        return <$event>::deserialize(&mut payload).ok().map($name::$variant);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant(_) => stringify!($variant),)*
+                }
+            }
+
+            pub fn timestamp(&self) -> i64 {
+                match self {
+                    $($name::$variant(event) => event.timestamp,)*
+                }
+            }
+        }
+    };
+}
+
+config_events!(
+    /// Program-wide pause and governance event emitted by freelance_escrow; these carry no contract id
+    EscrowConfigEvent {
+        PauseChanged(freelance_escrow::PauseChanged),
+        ConfigChangeQueued(freelance_escrow::ConfigChangeQueued),
+        ConfigChangeExecuted(freelance_escrow::ConfigChangeExecuted),
+        ConfigChangeCancelled(freelance_escrow::ConfigChangeCancelled),
+    }
+);
+
+config_events!(
+    /// Governance and compliance event emitted by payment_auth; these carry no contract id
+    PaymentAuthConfigEvent {
+        ConfigChangeQueued(payment_auth::ConfigChangeQueued),
+        ConfigChangeExecuted(payment_auth::ConfigChangeExecuted),
+        ConfigChangeCancelled(payment_auth::ConfigChangeCancelled),
+        SanctionAdded(payment_auth::SanctionAdded),
+        SanctionRemoved(payment_auth::SanctionRemoved),
+        KycAttested(payment_auth::KycAttested),
+        KycRevoked(payment_auth::KycRevoked),
+    }
+);
+
+/// Event emitted by either program
+pub enum Event {
+    Escrow(EscrowEvent),
+    PaymentAuth(PaymentAuthEvent),
+}
+
+/// Decode every escrow event found in a transaction's log messages
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<EscrowEvent> {
+    program_data(logs).filter_map(|data| EscrowEvent::decode(&data)).collect()
+}
+
+/// Decode every payment_auth event found in a transaction's log messages
+pub fn parse_payment_auth_logs<S: AsRef<str>>(logs: &[S]) -> Vec<PaymentAuthEvent> {
+    program_data(logs).filter_map(|data| PaymentAuthEvent::decode(&data)).collect()
+}
+
+/// Decode every escrow pause and governance event found in a transaction's log messages
+pub fn parse_escrow_config_logs<S: AsRef<str>>(logs: &[S]) -> Vec<EscrowConfigEvent> {
+    program_data(logs).filter_map(|data| EscrowConfigEvent::decode(&data)).collect()
+}
+
+/// Decode every payment_auth governance and compliance event found in a transaction's log messages
+pub fn parse_payment_auth_config_logs<S: AsRef<str>>(logs: &[S]) -> Vec<PaymentAuthConfigEvent> {
+    program_data(logs).filter_map(|data| PaymentAuthConfigEvent::decode(&data)).collect()
+}
+
+/// Decode the events of both programs in the order they were emitted
+pub fn parse_all_logs<S: AsRef<str>>(logs: &[S]) -> Vec<Event> {
+    program_data(logs)
+        .filter_map(|data| {
+            EscrowEvent::decode(&data)
+                .map(Event::Escrow)
+                .or_else(|| PaymentAuthEvent::decode(&data).map(Event::PaymentAuth))
+        })
+        .collect()
+}
+
+fn program_data<S: AsRef<str>>(logs: &[S]) -> impl Iterator<Item = Vec<u8>> + '_ {
+    logs.iter()
+        .filter_map(|line| line.as_ref().strip_prefix(PROGRAM_DATA))
+        .filter_map(|encoded| STANDARD.decode(encoded).ok())
+}
@@ -0,0 +1,20 @@
+//! Rust client SDK for the SmartFlo `freelance_escrow` and `payment_auth` programs.
+//!
+//! - [`ContractId`] is the fixed-size contract id both programs key their accounts by
+//! - [`pda`] derives every program address (`contract`, `escrow`, `milestone`, `payment_auth`, ...)
+//! - [`escrow`] and [`authorization`] build typed instructions
+//! - [`accounts`] deserializes program accounts
+//! - [`events`] decodes events from transaction logs
+//! - [`errors`] maps custom error codes back to each program's `ErrorCode`
+
+pub mod accounts;
+pub mod authorization;
+pub mod contract_id;
+pub mod errors;
+pub mod escrow;
+pub mod events;
+pub mod pda;
+
+pub use contract_id::{ContractId, ParseContractIdError};
+pub use freelance_escrow::ID as ESCROW_PROGRAM_ID;
+pub use payment_auth::ID as PAYMENT_AUTH_PROGRAM_ID;
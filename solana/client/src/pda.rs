@@ -0,0 +1,141 @@
+//! Program-derived addresses used by both programs.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address;
+
+use crate::ContractId;
+
+/// `EscrowConfig` account holding the pause flag, guardian and governance settings
+pub fn config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &freelance_escrow::ID)
+}
+
+/// freelance_escrow `PendingConfigChange` account for a queued change
+pub fn config_change(change_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config_change", &change_id.to_le_bytes()], &freelance_escrow::ID)
+}
+
+/// payment_auth `AuthConfig` account holding the freeze admins and governance settings
+pub fn auth_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &payment_auth::ID)
+}
+
+/// payment_auth `PendingConfigChange` account for a queued change
+pub fn auth_config_change(change_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config_change", &change_id.to_le_bytes()], &payment_auth::ID)
+}
+
+/// payment_auth `SanctionEntry` account; it exists only while `wallet` is on the sanctions blocklist
+pub fn sanction_entry(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sanctioned", wallet.as_ref()], &payment_auth::ID)
+}
+
+/// payment_auth `KycAttestation` account of `wallet`
+pub fn kyc_attestation(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"kyc", wallet.as_ref()], &payment_auth::ID)
+}
+
+/// Program data account of an upgradeable program, naming its upgrade authority
+pub fn program_data(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+/// `FreelanceContract` account for a contract id between `freelancer` and `client`
+pub fn contract(freelancer: &Pubkey, client: &Pubkey, contract_id: &ContractId) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"contract", freelancer.as_ref(), client.as_ref(), contract_id.as_ref()],
+        &freelance_escrow::ID,
+    )
+}
+
+/// `FreelanceContract` account of a contract created before addresses included the participants,
+/// derived from the id it was created under
+pub fn legacy_contract(legacy_id: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"contract", legacy_id], &freelance_escrow::ID)
+}
+
+/// Escrow PDA that owns the contract's escrow token account
+pub fn escrow_authority(contract: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", contract.as_ref()], &freelance_escrow::ID)
+}
+
+/// Escrow PDA of a contract created before addresses included the participants
+pub fn legacy_escrow_authority(legacy_id: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", legacy_id], &freelance_escrow::ID)
+}
+
+/// Associated token account of `escrow_authority` holding the contract's escrowed funds
+pub fn escrow_token_account(escrow_authority: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(escrow_authority, mint)
+}
+
+/// `Dispute` account holding the reason a contract was disputed
+pub fn dispute(contract: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dispute", contract.as_ref()], &freelance_escrow::ID)
+}
+
+/// `Milestone` account for one milestone of a contract
+pub fn milestone(contract: &Pubkey, milestone_index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"milestone", contract.as_ref(), &[milestone_index]],
+        &freelance_escrow::ID,
+    )
+}
+
+/// `Timesheet` account for one week of an hourly contract
+pub fn timesheet(contract: &Pubkey, week_index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"timesheet", contract.as_ref(), &week_index.to_le_bytes()],
+        &freelance_escrow::ID,
+    )
+}
+
+/// `PayeeSplit` account of a contract
+pub fn payee_split(contract: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"payee_split", contract.as_ref()], &freelance_escrow::ID)
+}
+
+/// `CoSignerPolicy` account of a contract
+pub fn co_signer_policy(contract: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"co_signer", contract.as_ref()], &freelance_escrow::ID)
+}
+
+/// `Deliverables` account listing the files a milestone was submitted with
+pub fn deliverables(milestone: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"deliverables", milestone.as_ref()], &freelance_escrow::ID)
+}
+
+/// `DisputeEvidence` account for the `index`th item `submitter` filed in a dispute
+pub fn dispute_evidence(dispute: &Pubkey, submitter: &Pubkey, index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"evidence", dispute.as_ref(), submitter.as_ref(), &[index]],
+        &freelance_escrow::ID,
+    )
+}
+
+/// `ApproverSet` account of a contract
+pub fn approver_set(contract: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"approver_set", contract.as_ref()], &freelance_escrow::ID)
+}
+
+/// `MilestoneApproval` account collecting approver signatures for a milestone
+pub fn milestone_approval(milestone: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"milestone_approval", milestone.as_ref()],
+        &freelance_escrow::ID,
+    )
+}
+
+/// `PaymentAuthorization` account for a client and contract id
+pub fn payment_authorization(client: &Pubkey, contract_id: &ContractId) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"payment_auth", client.as_ref(), contract_id.as_ref()],
+        &payment_auth::ID,
+    )
+}
+
+/// `PaymentAuthorization` account created under a legacy id that was not 32 bytes long
+pub fn legacy_payment_authorization(client: &Pubkey, legacy_id: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"payment_auth", client.as_ref(), legacy_id], &payment_auth::ID)
+}
@@ -0,0 +1,17 @@
+use smartflo_client::ContractId;
+
+#[test]
+fn uuids_parse_to_lowercase_hex_ids() {
+    let hyphenated: ContractId = "9E4F2A6B-1C3D-4E8F-A5B7-C9D0E1F2A3B4".parse().unwrap();
+    let compact: ContractId = "9e4f2a6b1c3d4e8fa5b7c9d0e1f2a3b4".parse().unwrap();
+    assert_eq!(hyphenated, compact);
+    assert_eq!(compact.as_str(), "9e4f2a6b1c3d4e8fa5b7c9d0e1f2a3b4");
+    assert_eq!(compact.to_string(), compact.as_str());
+}
+
+#[test]
+fn non_uuid_ids_are_rejected() {
+    for id in ["", "sdk-decode", "9e4f2a6b1c3d4e8fa5b7c9d0e1f2a3b", "9e4f2a6b1c3d4e8fa5b7c9d0e1f2a3b4c", "9e4f2a6b1c3d4e8fa5b7c9d0e1f2a3bz"] {
+        assert!(id.parse::<ContractId>().is_err(), "{id} parsed");
+    }
+}
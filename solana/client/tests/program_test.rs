@@ -0,0 +1,263 @@
+use anchor_lang::solana_program::account_info::AccountInfo;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::system_instruction;
+use smartflo_client::accounts::{decode_contract, decode_milestone, decode_payment_authorization};
+use smartflo_client::authorization::{self, AuthorizationKeys};
+use smartflo_client::errors::{decode_instruction_error, EscrowErrorCode, ProgramError};
+use smartflo_client::escrow::{self, ContractKeys};
+use smartflo_client::events::{parse_logs, EscrowEvent};
+use smartflo_client::{pda, ContractId};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+const DECODE_ID: &str = "9e4f2a6b-1c3d-4e8f-a5b7-c9d0e1f2a3b4";
+
+fn escrow_processor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    freelance_escrow::entry(program_id, accounts, data)
+}
+
+fn payment_auth_processor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    payment_auth::entry(program_id, accounts, data)
+}
+
+async fn start() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "freelance_escrow",
+        freelance_escrow::ID,
+        processor!(escrow_processor),
+    );
+    program_test.add_program("payment_auth", payment_auth::ID, processor!(payment_auth_processor));
+    let mut context = program_test.start_with_context().await;
+
+    // Natively loaded programs have no program data account; stand one in naming the payer
+    // as upgrade authority so it can initialize the escrow config
+    let payer = context.payer.pubkey();
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(payer.as_ref());
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), &bpf_loader_upgradeable::ID);
+    account.set_data_from_slice(&data);
+    context.set_account(&pda::program_data(&freelance_escrow::ID).0, &account);
+    send(&mut context, &[escrow::initialize_config(payer, payer, payer, 0)], &[]).await.unwrap();
+    context
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Vec<String>, TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result?;
+    Ok(result.metadata.map(|metadata| metadata.log_messages).unwrap_or_default())
+}
+
+async fn fund_wallet(context: &mut ProgramTestContext, wallet: &Pubkey) {
+    let transfer = system_instruction::transfer(&context.payer.pubkey(), wallet, 1_000_000_000);
+    send(context, &[transfer], &[]).await.unwrap();
+}
+
+async fn create_mint(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(
+            &spl_token::ID,
+            &mint.pubkey(),
+            &context.payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    ];
+    send(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+async fn create_token_account(context: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let payer = context.payer.pubkey();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &payer,
+            &account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_account(&spl_token::ID, &account.pubkey(), mint, owner).unwrap(),
+    ];
+    if amount > 0 {
+        instructions.push(
+            spl_token::instruction::mint_to(&spl_token::ID, mint, &account.pubkey(), &payer, &[], amount).unwrap(),
+        );
+    }
+    send(context, &instructions, &[&account]).await.unwrap();
+    account.pubkey()
+}
+
+async fn contract_keys(context: &mut ProgramTestContext, contract_id: &str) -> (ContractKeys, Keypair, Keypair) {
+    let freelancer = Keypair::new();
+    let client = Keypair::new();
+    fund_wallet(context, &freelancer.pubkey()).await;
+    fund_wallet(context, &client.pubkey()).await;
+
+    let mint = create_mint(context).await;
+    let mut keys = ContractKeys::new(contract_id.parse().unwrap(), freelancer.pubkey(), client.pubkey(), mint);
+    keys.client_token_account = create_token_account(context, &mint, &client.pubkey(), 10_000).await;
+    keys.freelancer_token_account = create_token_account(context, &mint, &freelancer.pubkey(), 0).await;
+    (keys, freelancer, client)
+}
+
+#[tokio::test]
+async fn derived_contract_address_holds_decodable_contract() {
+    let mut context = start().await;
+    let (keys, freelancer, _client) = contract_keys(&mut context, DECODE_ID).await;
+
+    send(&mut context, &[escrow::create_contract(&keys, 3_000, 3)], &[&freelancer])
+        .await
+        .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(pda::contract(&keys.freelancer, &keys.client, &keys.contract_id).0)
+        .await
+        .unwrap()
+        .expect("contract account at derived address");
+    let contract = decode_contract(&account.data).unwrap();
+    assert_eq!(contract.contract_id_str(), DECODE_ID.replace('-', ""));
+    assert_eq!(contract.freelancer, keys.freelancer);
+    assert_eq!(contract.client, keys.client);
+    assert_eq!(contract.escrow_seed, keys.contract().to_bytes());
+    assert_eq!(contract.total_amount, 3_000);
+    assert_eq!(contract.milestone_count, 3);
+}
+
+#[tokio::test]
+async fn events_are_decoded_from_transaction_logs() {
+    let mut context = start().await;
+    let (keys, freelancer, client) = contract_keys(&mut context, "5b0e3c9a-7d41-4f2e-8c6b-1a9d2e4f7b30").await;
+
+    send(&mut context, &[escrow::create_contract(&keys, 3_000, 3)], &[&freelancer])
+        .await
+        .unwrap();
+    let logs = send(&mut context, &[escrow::deposit_funds(&keys, 3_000)], &[&client])
+        .await
+        .unwrap();
+    match parse_logs(&logs).as_slice() {
+        [EscrowEvent::FundsDeposited(event)] => {
+            assert_eq!(event.contract_id, *keys.contract_id.as_bytes());
+            assert_eq!(event.amount, 3_000);
+        }
+        _ => panic!("expected a single FundsDeposited event"),
+    }
+
+    let logs = send(
+        &mut context,
+        &[escrow::submit_milestone(&keys, 0, "ipfs://proof")],
+        &[&freelancer],
+    )
+    .await
+    .unwrap();
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name(), "MilestoneSubmitted");
+
+    let account = context
+        .banks_client
+        .get_account(keys.milestone(0))
+        .await
+        .unwrap()
+        .unwrap();
+    let milestone = decode_milestone(&account.data).unwrap();
+    assert_eq!(milestone.proof_uri, "ipfs://proof");
+    assert_eq!(milestone.payment_amount, 1_000);
+}
+
+#[tokio::test]
+async fn custom_errors_map_to_program_error_codes() {
+    let mut context = start().await;
+    let (keys, freelancer, _client) = contract_keys(&mut context, "c2a7f4e1-3b8d-4a6c-9f05-e1d2c3b4a596").await;
+
+    send(&mut context, &[escrow::create_contract(&keys, 3_000, 3)], &[&freelancer])
+        .await
+        .unwrap();
+    let error = send(
+        &mut context,
+        &[escrow::submit_milestone(&keys, 0, "ipfs://proof")],
+        &[&freelancer],
+    )
+    .await
+    .unwrap_err();
+
+    let instruction_error = match error {
+        TransactionError::InstructionError(_, instruction_error) => instruction_error,
+        other => panic!("expected an instruction error, got {other:?}"),
+    };
+    assert!(matches!(
+        decode_instruction_error(&freelance_escrow::ID, &instruction_error),
+        Some(ProgramError::Escrow(EscrowErrorCode::ContractNotActive))
+    ));
+}
+
+#[tokio::test]
+async fn payment_authorization_round_trips() {
+    let mut context = start().await;
+    let client = Keypair::new();
+    let freelancer = Keypair::new();
+    fund_wallet(&mut context, &client.pubkey()).await;
+
+    let contract_id: ContractId = "0d4b8e2f6a1c49d3b7e5f2a8c6d4e1b9".parse().unwrap();
+    let keys = AuthorizationKeys::new(contract_id, client.pubkey(), freelancer.pubkey());
+    send(
+        &mut context,
+        &[authorization::create_payment_authorization(&keys, 500, 2_000)],
+        &[&client],
+    )
+    .await
+    .unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(keys.payment_authorization())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, payment_auth::ID);
+    let auth = decode_payment_authorization(&account.data).unwrap();
+    assert_eq!(ContractId(auth.contract_id), contract_id);
+    assert_eq!(auth.freelancer, freelancer.pubkey());
+    assert_eq!(auth.max_per_milestone, 500);
+    assert_eq!(auth.total_authorized, 2_000);
+    assert!(auth.is_active);
+}
@@ -0,0 +1,119 @@
+//! `smartflo-indexer`: materializes escrow and payment authorization events into SQL tables.
+//!
+//! Transactions come from a JSON Lines file (or stdin) or from polling a validator over RPC.
+//! Progress is checkpointed by slot in the same database, so an interrupted run resumes where
+//! it stopped; replaying already indexed transactions is a no-op.
+
+mod source;
+mod store;
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use smartflo_client::{ESCROW_PROGRAM_ID, PAYMENT_AUTH_PROGRAM_ID};
+
+use crate::source::{read_jsonl, RpcSource, TransactionLogs};
+use crate::store::Store;
+
+#[derive(Parser)]
+#[command(name = "smartflo-indexer", version, about = "Index SmartFlo program events into a local database")]
+struct Cli {
+    /// SQLite database file, created if missing
+    #[arg(long, short = 'd', default_value = "smartflo-index.db")]
+    database: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index `{"slot", "signature", "logs", "err"}` objects, one per line
+    File {
+        /// JSON Lines file, or `-` for stdin
+        path: String,
+    },
+    /// Poll a validator for transactions of both programs
+    Rpc {
+        /// RPC URL
+        #[arg(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Seconds between polls
+        #[arg(long, default_value_t = 5)]
+        poll_interval: u64,
+        /// Index what is available and exit instead of polling
+        #[arg(long)]
+        once: bool,
+    },
+    /// Print the last indexed slot
+    Checkpoint,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut store = Store::open(&cli.database)?;
+
+    match cli.command {
+        Command::File { path } => {
+            let from_slot = resume_slot(&store)?;
+            let transactions: Box<dyn Iterator<Item = Result<TransactionLogs>>> = if path == "-" {
+                Box::new(read_jsonl(io::stdin().lock()))
+            } else {
+                let file = File::open(&path).with_context(|| format!("opening {path}"))?;
+                Box::new(read_jsonl(BufReader::new(file)))
+            };
+            let mut count = 0;
+            let mut events = 0;
+            for transaction in transactions {
+                let transaction = transaction?;
+                if transaction.slot < from_slot {
+                    continue;
+                }
+                events += store.apply(&transaction)?;
+                count += 1;
+            }
+            report(&store, count, events)
+        }
+        Command::Rpc {
+            url,
+            poll_interval,
+            once,
+        } => {
+            let source = RpcSource::new(&url, vec![ESCROW_PROGRAM_ID, PAYMENT_AUTH_PROGRAM_ID]);
+            loop {
+                let transactions = source.fetch_since(resume_slot(&store)?)?;
+                let mut events = 0;
+                for transaction in &transactions {
+                    events += store.apply(transaction)?;
+                }
+                report(&store, transactions.len(), events)?;
+                if once {
+                    return Ok(());
+                }
+                thread::sleep(Duration::from_secs(poll_interval));
+            }
+        }
+        Command::Checkpoint => {
+            match store.checkpoint()? {
+                Some(checkpoint) => println!("slot {} ({})", checkpoint.slot, checkpoint.signature),
+                None => println!("nothing indexed yet"),
+            }
+            Ok(())
+        }
+    }
+}
+
+/// The checkpoint slot itself is replayed, since it may have been only partly indexed
+fn resume_slot(store: &Store) -> Result<u64> {
+    Ok(store.checkpoint()?.map_or(0, |checkpoint| checkpoint.slot))
+}
+
+fn report(store: &Store, transactions: usize, events: usize) -> Result<()> {
+    let slot = store.checkpoint()?.map_or(0, |checkpoint| checkpoint.slot);
+    println!("Indexed {transactions} transactions, {events} new events, checkpoint slot {slot}");
+    Ok(())
+}
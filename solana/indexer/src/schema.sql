@@ -0,0 +1,94 @@
+-- Tables written by smartflo-indexer. Plain SQL that runs unchanged on SQLite and Postgres.
+
+-- Highest slot whose transactions have all been indexed
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    slot BIGINT NOT NULL,
+    signature TEXT NOT NULL
+);
+
+-- Every decoded event; the primary key makes re-indexing a transaction a no-op
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    program TEXT NOT NULL,
+    name TEXT NOT NULL,
+    contract_id TEXT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS contracts (
+    contract_id TEXT PRIMARY KEY,
+    total_deposited BIGINT NOT NULL DEFAULT 0,
+    total_released BIGINT NOT NULL DEFAULT 0,
+    milestones_submitted INTEGER NOT NULL DEFAULT 0,
+    milestones_approved INTEGER NOT NULL DEFAULT 0,
+    is_disputed BOOLEAN NOT NULL DEFAULT FALSE,
+    dispute_reason TEXT,
+    disputed_at BIGINT,
+    last_slot BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS deposits (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    contract_id TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS milestones (
+    contract_id TEXT NOT NULL,
+    milestone_index INTEGER NOT NULL,
+    submitted_at BIGINT,
+    amount_paid BIGINT NOT NULL DEFAULT 0,
+    is_approved BOOLEAN NOT NULL DEFAULT FALSE,
+    approved_at BIGINT,
+    last_slot BIGINT NOT NULL,
+    PRIMARY KEY (contract_id, milestone_index)
+);
+
+-- One row per full or partial milestone approval
+CREATE TABLE IF NOT EXISTS milestone_releases (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    contract_id TEXT NOT NULL,
+    milestone_index INTEGER NOT NULL,
+    amount BIGINT NOT NULL,
+    approval_bps INTEGER NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS payment_authorizations (
+    client TEXT NOT NULL,
+    contract_id TEXT NOT NULL,
+    freelancer TEXT NOT NULL,
+    max_per_milestone BIGINT NOT NULL,
+    total_authorized BIGINT NOT NULL,
+    total_spent BIGINT NOT NULL DEFAULT 0,
+    escrow_token_account TEXT,
+    status TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    last_slot BIGINT NOT NULL,
+    PRIMARY KEY (client, contract_id)
+);
+
+-- Direct milestone payments and escrow funding pulled through an authorization
+CREATE TABLE IF NOT EXISTS authorization_payments (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    client TEXT NOT NULL,
+    contract_id TEXT NOT NULL,
+    milestone_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
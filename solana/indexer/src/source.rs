@@ -0,0 +1,133 @@
+//! Transaction log sources: JSON Lines files or streams, and RPC polling of a validator.
+
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionEncoding;
+
+/// Signatures fetched per `getSignaturesForAddress` page
+const PAGE_LIMIT: usize = 1_000;
+
+/// Log messages of one confirmed transaction
+#[derive(Debug, Deserialize)]
+pub struct TransactionLogs {
+    pub slot: u64,
+    pub signature: String,
+    pub logs: Vec<String>,
+    /// Transaction error, if it failed; failed transactions are skipped
+    #[serde(default)]
+    pub err: Option<serde_json::Value>,
+}
+
+impl TransactionLogs {
+    pub fn succeeded(&self) -> bool {
+        self.err.as_ref().is_none_or(serde_json::Value::is_null)
+    }
+}
+
+/// Read one `{"slot", "signature", "logs", "err"}` object per line, skipping blank lines
+pub fn read_jsonl(reader: impl BufRead) -> impl Iterator<Item = Result<TransactionLogs>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(number, line)| {
+            let line = line.context("reading transaction logs")?;
+            serde_json::from_str(&line).with_context(|| format!("parsing line {}", number + 1))
+        })
+}
+
+/// Polls a validator for transactions touching the given programs
+pub struct RpcSource {
+    rpc: RpcClient,
+    programs: Vec<Pubkey>,
+}
+
+impl RpcSource {
+    pub fn new(url: &str, programs: Vec<Pubkey>) -> Self {
+        let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+        Self { rpc, programs }
+    }
+
+    /// Every transaction at or after `from_slot`, oldest first
+    pub fn fetch_since(&self, from_slot: u64) -> Result<Vec<TransactionLogs>> {
+        let mut seen = HashSet::new();
+        let mut statuses = Vec::new();
+        for program in &self.programs {
+            let mut before = None;
+            loop {
+                let config = GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(PAGE_LIMIT),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                };
+                let page = self
+                    .rpc
+                    .get_signatures_for_address_with_config(program, config)
+                    .with_context(|| format!("fetching signatures for {program}"))?;
+                let exhausted = page.len() < PAGE_LIMIT;
+                before = page.last().map(|status| Signature::from_str(&status.signature)).transpose()?;
+
+                // Pages are newest first; stop once the page reaches back past the checkpoint
+                let mut reached_checkpoint = false;
+                for status in page {
+                    if status.slot < from_slot {
+                        reached_checkpoint = true;
+                        continue;
+                    }
+                    // Failed transactions changed no state, whatever their logs say
+                    if status.err.is_none() && seen.insert(status.signature.clone()) {
+                        statuses.push(status);
+                    }
+                }
+                if exhausted || reached_checkpoint {
+                    break;
+                }
+            }
+        }
+
+        // Newest-first pages reversed give chain order within a slot
+        statuses.reverse();
+        statuses.sort_by_key(|status| status.slot);
+
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        statuses
+            .into_iter()
+            .map(|status| {
+                let signature = Signature::from_str(&status.signature)?;
+                let transaction = self
+                    .rpc
+                    .get_transaction_with_config(&signature, config)
+                    .with_context(|| format!("fetching transaction {signature}"))?;
+                let logs = transaction
+                    .transaction
+                    .meta
+                    .and_then(|meta| match meta.log_messages {
+                        OptionSerializer::Some(logs) => Some(logs),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                Ok(TransactionLogs {
+                    slot: status.slot,
+                    signature: status.signature,
+                    logs,
+                    err: None,
+                })
+            })
+            .collect()
+    }
+}
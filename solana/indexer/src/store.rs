@@ -0,0 +1,445 @@
+//! SQLite store for decoded events and the rows materialized from them.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use smartflo_client::events::{parse_all_logs, EscrowEvent, Event, PaymentAuthEvent};
+use smartflo_client::ContractId;
+
+use crate::source::TransactionLogs;
+
+const SCHEMA: &str = include_str!("schema.sql");
+
+/// Last fully indexed position
+pub struct Checkpoint {
+    pub slot: u64,
+    pub signature: String,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open or create the database and make sure every table exists
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path).with_context(|| format!("opening database {}", path.display()))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).context("creating tables")?;
+        Ok(Self { conn })
+    }
+
+    pub fn checkpoint(&self) -> Result<Option<Checkpoint>> {
+        self.conn
+            .query_row("SELECT slot, signature FROM checkpoint WHERE id = 1", [], |row| {
+                Ok(Checkpoint {
+                    slot: row.get::<_, i64>(0)? as u64,
+                    signature: row.get(1)?,
+                })
+            })
+            .optional()
+            .context("reading checkpoint")
+    }
+
+    /// Index one transaction atomically and advance the checkpoint; returns the number of
+    /// new events. Events already stored are skipped, so replaying a slot is safe.
+    pub fn apply(&mut self, transaction: &TransactionLogs) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let slot = to_i64(transaction.slot)?;
+        let mut indexed = 0;
+
+        if transaction.succeeded() {
+            for (index, event) in parse_all_logs(&transaction.logs).into_iter().enumerate() {
+                let row = EventRow {
+                    signature: &transaction.signature,
+                    event_index: index as i64,
+                    slot,
+                };
+                if row.record(&tx, &event)? {
+                    match event {
+                        Event::Escrow(event) => apply_escrow_event(&tx, &row, event)?,
+                        Event::PaymentAuth(event) => apply_payment_auth_event(&tx, &row, event)?,
+                    }
+                    indexed += 1;
+                }
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO checkpoint (id, slot, signature) VALUES (1, ?1, ?2)
+             ON CONFLICT (id) DO UPDATE SET slot = excluded.slot, signature = excluded.signature
+             WHERE excluded.slot >= checkpoint.slot",
+            params![slot, transaction.signature],
+        )?;
+        tx.commit()?;
+        Ok(indexed)
+    }
+}
+
+/// Position of one event within the indexed history
+struct EventRow<'a> {
+    signature: &'a str,
+    event_index: i64,
+    slot: i64,
+}
+
+impl EventRow<'_> {
+    /// Insert into the event ledger; false when the event was indexed before
+    fn record(&self, tx: &Transaction, event: &Event) -> Result<bool> {
+        let (program, name, contract_id, timestamp) = match event {
+            Event::Escrow(event) => ("freelance_escrow", event.name(), event.contract_id().to_string(), event.timestamp()),
+            Event::PaymentAuth(event) => ("payment_auth", event.name(), event.contract_id().to_string(), event.timestamp()),
+        };
+        let inserted = tx.execute(
+            "INSERT INTO events (signature, event_index, slot, program, name, contract_id, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (signature, event_index) DO NOTHING",
+            params![self.signature, self.event_index, self.slot, program, name, contract_id, timestamp],
+        )?;
+        Ok(inserted == 1)
+    }
+}
+
+fn apply_escrow_event(tx: &Transaction, row: &EventRow, event: EscrowEvent) -> Result<()> {
+    match event {
+        EscrowEvent::FundsDeposited(event) => {
+            let amount = to_i64(event.amount)?;
+            tx.execute(
+                "INSERT INTO deposits (signature, event_index, slot, contract_id, amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![row.signature, row.event_index, row.slot, text(event.contract_id), amount, event.timestamp],
+            )?;
+            upsert_contract(tx, &text(event.contract_id), row.slot)?;
+            tx.execute(
+                "UPDATE contracts SET total_deposited = total_deposited + ?2 WHERE contract_id = ?1",
+                params![text(event.contract_id), amount],
+            )?;
+        }
+        EscrowEvent::MilestoneSubmitted(event) => {
+            tx.execute(
+                "INSERT INTO milestones (contract_id, milestone_index, submitted_at, last_slot)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (contract_id, milestone_index)
+                 DO UPDATE SET submitted_at = excluded.submitted_at, last_slot = excluded.last_slot",
+                params![text(event.contract_id), event.milestone_index, event.timestamp, row.slot],
+            )?;
+            upsert_contract(tx, &text(event.contract_id), row.slot)?;
+            tx.execute(
+                "UPDATE contracts SET milestones_submitted = milestones_submitted + 1 WHERE contract_id = ?1",
+                params![text(event.contract_id)],
+            )?;
+        }
+        EscrowEvent::MilestoneApproved(event) => {
+            let amount = to_i64(event.amount)?;
+            tx.execute(
+                "INSERT INTO milestone_releases
+                 (signature, event_index, slot, contract_id, milestone_index, amount, approval_bps, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    row.signature,
+                    row.event_index,
+                    row.slot,
+                    text(event.contract_id),
+                    event.milestone_index,
+                    amount,
+                    event.approval_bps,
+                    event.timestamp
+                ],
+            )?;
+            // Releases within one slot may arrive out of order, so totals only ever grow
+            tx.execute(
+                "INSERT INTO milestones (contract_id, milestone_index, amount_paid, is_approved, approved_at, last_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT (contract_id, milestone_index) DO UPDATE SET
+                     amount_paid = CASE WHEN excluded.amount_paid > milestones.amount_paid
+                                        THEN excluded.amount_paid ELSE milestones.amount_paid END,
+                     is_approved = milestones.is_approved OR excluded.is_approved,
+                     approved_at = COALESCE(milestones.approved_at, excluded.approved_at),
+                     last_slot = excluded.last_slot",
+                params![
+                    text(event.contract_id),
+                    event.milestone_index,
+                    to_i64(event.total_paid)?,
+                    event.is_fully_approved,
+                    event.is_fully_approved.then_some(event.timestamp),
+                    row.slot
+                ],
+            )?;
+            upsert_contract(tx, &text(event.contract_id), row.slot)?;
+            tx.execute(
+                "UPDATE contracts SET total_released = total_released + ?2,
+                     milestones_approved = milestones_approved + ?3
+                 WHERE contract_id = ?1",
+                params![text(event.contract_id), amount, event.is_fully_approved as i64],
+            )?;
+        }
+        EscrowEvent::ContractDisputed(event) => {
+            upsert_contract(tx, &text(event.contract_id), row.slot)?;
+            tx.execute(
+                "UPDATE contracts SET is_disputed = TRUE, dispute_reason = ?2, disputed_at = ?3
+                 WHERE contract_id = ?1",
+                params![text(event.contract_id), event.reason, event.timestamp],
+            )?;
+        }
+        // Other escrow events are kept in the event ledger only
+        _ => {}
+    }
+    Ok(())
+}
+
+fn apply_payment_auth_event(tx: &Transaction, row: &EventRow, event: PaymentAuthEvent) -> Result<()> {
+    match event {
+        PaymentAuthEvent::AuthorizationCreated(event) => {
+            tx.execute(
+                "INSERT INTO payment_authorizations
+                 (client, contract_id, freelancer, max_per_milestone, total_authorized, status, created_at, last_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'active', ?6, ?7)
+                 ON CONFLICT (client, contract_id) DO NOTHING",
+                params![
+                    event.client.to_string(),
+                    text(event.contract_id),
+                    event.freelancer.to_string(),
+                    to_i64(event.max_per_milestone)?,
+                    to_i64(event.total_authorized)?,
+                    event.timestamp,
+                    row.slot
+                ],
+            )?;
+        }
+        PaymentAuthEvent::MilestonePaymentProcessed(event) => {
+            insert_authorization_payment(tx, row, &event.client.to_string(), &text(event.contract_id), &event.milestone_id, "direct", event.amount, event.timestamp)?;
+            update_total_spent(tx, row, &event.client.to_string(), &text(event.contract_id), event.total_spent)?;
+        }
+        PaymentAuthEvent::EscrowFunded(event) => {
+            insert_authorization_payment(tx, row, &event.client.to_string(), &text(event.contract_id), &event.milestone_id, "escrow", event.amount, event.timestamp)?;
+            update_total_spent(tx, row, &event.client.to_string(), &text(event.contract_id), event.total_spent)?;
+        }
+        PaymentAuthEvent::EscrowLinked(event) => {
+            tx.execute(
+                "UPDATE payment_authorizations SET escrow_token_account = ?3, last_slot = ?4
+                 WHERE client = ?1 AND contract_id = ?2",
+                params![
+                    event.client.to_string(),
+                    text(event.contract_id),
+                    event.escrow_token_account.to_string(),
+                    row.slot
+                ],
+            )?;
+        }
+        PaymentAuthEvent::AuthorizationUpdated(event) => {
+            tx.execute(
+                "UPDATE payment_authorizations SET max_per_milestone = ?3, total_authorized = ?4, last_slot = ?5
+                 WHERE client = ?1 AND contract_id = ?2",
+                params![
+                    event.client.to_string(),
+                    text(event.contract_id),
+                    to_i64(event.max_per_milestone)?,
+                    to_i64(event.total_authorized)?,
+                    row.slot
+                ],
+            )?;
+        }
+        PaymentAuthEvent::AuthorizationRevoked(event) => {
+            set_status(tx, row, &event.client.to_string(), &text(event.contract_id), "revoked")?;
+        }
+        PaymentAuthEvent::AuthorizationFrozen(event) => {
+            set_status(tx, row, &event.client.to_string(), &text(event.contract_id), "frozen")?;
+        }
+        // Co-signer changes stay in the raw event log
+        PaymentAuthEvent::AuthorizationCoSignerUpdated(_) => {}
+    }
+    Ok(())
+}
+
+fn upsert_contract(tx: &Transaction, contract_id: &str, slot: i64) -> Result<()> {
+    tx.execute(
+        "INSERT INTO contracts (contract_id, last_slot) VALUES (?1, ?2)
+         ON CONFLICT (contract_id) DO UPDATE SET last_slot = excluded.last_slot",
+        params![contract_id, slot],
+    )?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_authorization_payment(
+    tx: &Transaction,
+    row: &EventRow,
+    client: &str,
+    contract_id: &str,
+    milestone_id: &str,
+    kind: &str,
+    amount: u64,
+    timestamp: i64,
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO authorization_payments
+         (signature, event_index, slot, client, contract_id, milestone_id, kind, amount, timestamp)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            row.signature,
+            row.event_index,
+            row.slot,
+            client,
+            contract_id,
+            milestone_id,
+            kind,
+            to_i64(amount)?,
+            timestamp
+        ],
+    )?;
+    Ok(())
+}
+
+fn update_total_spent(tx: &Transaction, row: &EventRow, client: &str, contract_id: &str, total_spent: u64) -> Result<()> {
+    tx.execute(
+        "UPDATE payment_authorizations SET
+             total_spent = CASE WHEN ?3 > total_spent THEN ?3 ELSE total_spent END,
+             last_slot = ?4
+         WHERE client = ?1 AND contract_id = ?2",
+        params![client, contract_id, to_i64(total_spent)?, row.slot],
+    )?;
+    Ok(())
+}
+
+fn set_status(tx: &Transaction, row: &EventRow, client: &str, contract_id: &str, status: &str) -> Result<()> {
+    tx.execute(
+        "UPDATE payment_authorizations SET status = ?3, last_slot = ?4 WHERE client = ?1 AND contract_id = ?2",
+        params![client, contract_id, status, row.slot],
+    )?;
+    Ok(())
+}
+
+/// Contract ids are fixed 32-byte arrays on chain but TEXT in SQL
+fn text(contract_id: [u8; 32]) -> String {
+    ContractId(contract_id).to_string()
+}
+
+/// Token amounts are u64 on chain but BIGINT in SQL
+fn to_i64(value: u64) -> Result<i64> {
+    i64::try_from(value).with_context(|| format!("{value} does not fit in a BIGINT column"))
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use smartflo_client::events::{FundsDeposited, MilestoneApproved, MilestoneSubmitted};
+
+    use super::*;
+
+    const CONTRACT_ID: [u8; 32] = *b"9e4f2a6b1c3d4e8fa5b7c9d0e1f2a3b4";
+
+    fn log(event: impl anchor_lang::Event) -> String {
+        format!("Program data: {}", STANDARD.encode(event.data()))
+    }
+
+    fn transaction(slot: u64, signature: &str, logs: Vec<String>) -> TransactionLogs {
+        TransactionLogs {
+            slot,
+            signature: signature.to_string(),
+            logs,
+            err: None,
+        }
+    }
+
+    fn history() -> Vec<TransactionLogs> {
+        vec![
+            transaction(
+                10,
+                "deposit",
+                vec![log(FundsDeposited { contract_id: CONTRACT_ID, amount: 1_000, timestamp: 100 })],
+            ),
+            transaction(
+                11,
+                "submit",
+                vec![log(MilestoneSubmitted { contract_id: CONTRACT_ID, milestone_index: 0, timestamp: 110 })],
+            ),
+            transaction(
+                12,
+                "approve",
+                vec![log(MilestoneApproved {
+                    contract_id: CONTRACT_ID,
+                    milestone_index: 0,
+                    amount: 500,
+                    approval_bps: 10_000,
+                    total_paid: 500,
+                    is_fully_approved: true,
+                    timestamp: 120,
+                })],
+            ),
+        ]
+    }
+
+    fn in_memory() -> Store {
+        Store::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn contract_totals(store: &Store) -> (i64, i64, i64, i64) {
+        store
+            .conn
+            .query_row(
+                "SELECT total_deposited, total_released, milestones_submitted, milestones_approved
+                 FROM contracts WHERE contract_id = ?1",
+                params![text(CONTRACT_ID)],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap()
+    }
+
+    fn event_count(store: &Store) -> i64 {
+        store.conn.query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn replaying_the_same_logs_is_idempotent() {
+        let mut store = in_memory();
+        let indexed: usize = history().iter().map(|transaction| store.apply(transaction).unwrap()).sum();
+        assert_eq!(indexed, 3);
+        assert_eq!(contract_totals(&store), (1_000, 500, 1, 1));
+
+        let replayed: usize = history().iter().map(|transaction| store.apply(transaction).unwrap()).sum();
+        assert_eq!(replayed, 0);
+        assert_eq!(event_count(&store), 3);
+        assert_eq!(contract_totals(&store), (1_000, 500, 1, 1));
+    }
+
+    #[test]
+    fn restart_resumes_from_the_checkpoint() {
+        let mut store = in_memory();
+        assert!(store.checkpoint().unwrap().is_none());
+        let history = history();
+        for transaction in &history[..2] {
+            store.apply(transaction).unwrap();
+        }
+
+        // Reopening runs the schema again over the existing tables and keeps the checkpoint
+        let mut store = Store::with_connection(store.conn).unwrap();
+        let checkpoint = store.checkpoint().unwrap().unwrap();
+        assert_eq!((checkpoint.slot, checkpoint.signature.as_str()), (11, "submit"));
+        assert_eq!(crate::resume_slot(&store).unwrap(), 11);
+
+        // The source hands back everything from the checkpoint slot on, including the last one indexed
+        let indexed: usize = history
+            .iter()
+            .filter(|transaction| transaction.slot >= checkpoint.slot)
+            .map(|transaction| store.apply(transaction).unwrap())
+            .sum();
+        assert_eq!(indexed, 1);
+        assert_eq!(store.checkpoint().unwrap().unwrap().slot, 12);
+        assert_eq!(contract_totals(&store), (1_000, 500, 1, 1));
+    }
+
+    #[test]
+    fn failed_transactions_only_advance_the_checkpoint() {
+        let mut store = in_memory();
+        let mut failed = history().remove(0);
+        failed.err = Some(serde_json::json!({ "InstructionError": [0, { "Custom": 6006 }] }));
+        assert_eq!(store.apply(&failed).unwrap(), 0);
+        assert_eq!(event_count(&store), 0);
+        assert_eq!(store.checkpoint().unwrap().unwrap().slot, 10);
+    }
+}
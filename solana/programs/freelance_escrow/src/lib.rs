@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use anchor_lang::solana_program::{bpf_loader_upgradeable, ed25519_program};
+use anchor_lang::{system_program, Discriminator};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use payment_auth::program::PaymentAuth;
+use payment_auth::{KycAttestation, PaymentAuthorization};
 
-declare_id!("Your_Program_ID");
+declare_id!("44DrUbm5vGxnLinZP28PYz2zegzwVG6xLTQYaaoKBBx9");
 
 #[program]
 pub mod freelance_escrow {
@@ -9,26 +14,169 @@ pub mod freelance_escrow {
 
     // Initialize a new freelance contract
     pub fn create_contract(
-        ctx: Context<CreateContract>,
-        contract_id: String,
+        mut ctx: Context<CreateContract>,
+        contract_id: [u8; 32],
         total_amount: u64,
         milestone_count: u8,
     ) -> Result<()> {
+        initialize_contract(&mut ctx, contract_id, total_amount, milestone_count, ContractType::Milestone)
+    }
+
+    // Initialize a retainer contract whose escrow vests linearly between start and end
+    pub fn create_streaming_contract(
+        mut ctx: Context<CreateContract>,
+        contract_id: [u8; 32],
+        total_amount: u64,
+        stream_start: i64,
+        stream_end: i64,
+    ) -> Result<()> {
+        require!(stream_end > stream_start, ErrorCode::InvalidStreamSchedule);
+        initialize_contract(&mut ctx, contract_id, total_amount, 0, ContractType::Streaming)?;
+
+        let contract = &mut ctx.accounts.contract;
+        contract.stream_start = stream_start;
+        contract.stream_end = stream_end;
+        Ok(())
+    }
+
+    // Initialize an hourly contract paid per approved weekly timesheet
+    pub fn create_hourly_contract(
+        mut ctx: Context<CreateContract>,
+        contract_id: [u8; 32],
+        total_amount: u64,
+        hourly_rate: u64,
+        weekly_hour_cap: u16,
+    ) -> Result<()> {
+        require!(hourly_rate > 0 && weekly_hour_cap > 0, ErrorCode::InvalidHourlyTerms);
+        initialize_contract(&mut ctx, contract_id, total_amount, 0, ContractType::Hourly)?;
+
+        let contract = &mut ctx.accounts.contract;
+        contract.hourly_rate = hourly_rate;
+        contract.weekly_hour_cap = weekly_hour_cap;
+        Ok(())
+    }
+
+    // Freelancer splits future releases between several payees before the contract is funded
+    pub fn set_payee_split(ctx: Context<SetPayeeSplit>, payees: Vec<Payee>) -> Result<()> {
+        validate_payees(&payees)?;
+        let contract = &mut ctx.accounts.contract;
+        require!(!contract.is_active, ErrorCode::InvalidContractState);
+
+        let now = Clock::get()?.unix_timestamp;
+        let payee_split = &mut ctx.accounts.payee_split;
+        payee_split.version = PayeeSplit::VERSION;
+        payee_split.contract = contract.key();
+        payee_split.payees = payees.clone();
+        payee_split.updated_at = now;
+        contract.has_payee_split = true;
+
+        emit!(PayeeSplitUpdated {
+            contract_id: contract.contract_id,
+            payees,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Change the payee split; needs both the freelancer and the client to sign
+    pub fn update_payee_split(ctx: Context<UpdatePayeeSplit>, payees: Vec<Payee>) -> Result<()> {
+        validate_payees(&payees)?;
+        let contract = &ctx.accounts.contract;
+
+        let now = Clock::get()?.unix_timestamp;
+        let payee_split = &mut ctx.accounts.payee_split;
+        payee_split.payees = payees.clone();
+        payee_split.updated_at = now;
+
+        emit!(PayeeSplitUpdated {
+            contract_id: contract.contract_id,
+            payees,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Client hands milestone sign-off to an M-of-N set of approvers before the contract is funded
+    pub fn set_approver_set(
+        ctx: Context<SetApproverSet>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(!members.is_empty() && members.len() <= MAX_APPROVERS, ErrorCode::InvalidApproverSet);
+        require!(threshold > 0 && threshold as usize <= members.len(), ErrorCode::InvalidApproverSet);
+        for (i, member) in members.iter().enumerate() {
+            require!(!members[..i].contains(member), ErrorCode::InvalidApproverSet);
+        }
+
+        let contract = &mut ctx.accounts.contract;
+        require!(!contract.is_active, ErrorCode::InvalidContractState);
+
+        let approver_set = &mut ctx.accounts.approver_set;
+        approver_set.version = ApproverSet::VERSION;
+        approver_set.contract = contract.key();
+        approver_set.members = members.clone();
+        approver_set.threshold = threshold;
+        contract.has_approver_set = true;
+
+        emit!(ApproverSetCreated {
+            contract_id: contract.contract_id,
+            members,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Client registers a second key that must co-sign approvals above `threshold`; both keys sign
+    pub fn set_co_signer(ctx: Context<SetCoSigner>, threshold: u64) -> Result<()> {
         let contract = &mut ctx.accounts.contract;
-        contract.contract_id = contract_id;
-        contract.freelancer = ctx.accounts.freelancer.key();
-        contract.client = ctx.accounts.client.key();
-        contract.total_amount = total_amount;
-        contract.milestone_count = milestone_count;
-        contract.completed_milestones = 0;
-        contract.amount_released = 0;
-        contract.escrow_balance = 0;
-        contract.is_active = false;
-        contract.is_completed = false;
-        contract.is_disputed = false;
-        contract.dispute_reason = String::new();
-        contract.created_at = Clock::get()?.unix_timestamp;
-        contract.completed_at = 0;
+        let co_signer = ctx.accounts.co_signer.key();
+        require_keys_neq!(co_signer, contract.client, ErrorCode::InvalidCoSigner);
+
+        let now = Clock::get()?.unix_timestamp;
+        let policy = &mut ctx.accounts.co_signer_policy;
+        policy.version = CoSignerPolicy::VERSION;
+        policy.contract = contract.key();
+        policy.co_signer = co_signer;
+        policy.threshold = threshold;
+        policy.updated_at = now;
+        contract.has_co_signer = true;
+
+        emit!(CoSignerUpdated {
+            contract_id: contract.contract_id,
+            co_signer,
+            threshold,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Replace the co-signer key or threshold; needs both the client and the current co-signer
+    pub fn update_co_signer(
+        ctx: Context<UpdateCoSigner>,
+        new_co_signer: Pubkey,
+        threshold: u64,
+    ) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        require_keys_neq!(new_co_signer, contract.client, ErrorCode::InvalidCoSigner);
+
+        let now = Clock::get()?.unix_timestamp;
+        let policy = &mut ctx.accounts.co_signer_policy;
+        policy.co_signer = new_co_signer;
+        policy.threshold = threshold;
+        policy.updated_at = now;
+
+        emit!(CoSignerUpdated {
+            contract_id: contract.contract_id,
+            co_signer: new_co_signer,
+            threshold,
+            timestamp: now,
+        });
+
         Ok(())
     }
 
@@ -37,6 +185,15 @@ pub mod freelance_escrow {
         ctx: Context<DepositFunds>,
         amount: u64,
     ) -> Result<()> {
+        require!(!ctx.accounts.config.is_paused, ErrorCode::ProgramPaused);
+        require!(ctx.accounts.client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+        require!(ctx.accounts.freelancer_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+        // Only the client can settle which token a migrated contract's escrow holds
+        if ctx.accounts.contract.mint == Pubkey::default() {
+            require_keys_eq!(ctx.accounts.client.key(), ctx.accounts.contract.client, ErrorCode::UnauthorizedClient);
+        }
+        ctx.accounts.contract.pin_mint(ctx.accounts.usdc_mint.key())?;
+
         // Transfer USDC from client to escrow account
         let cpi_accounts = Transfer {
             from: ctx.accounts.client_token_account.to_account_info(),
@@ -53,7 +210,7 @@ pub mod freelance_escrow {
         contract.escrow_balance += amount;
         
         emit!(FundsDeposited {
-            contract_id: contract.contract_id.clone(),
+            contract_id: contract.contract_id,
             amount,
             timestamp: Clock::get()?.unix_timestamp,
         });
@@ -61,109 +218,533 @@ pub mod freelance_escrow {
         Ok(())
     }
 
+    // Top up escrow for the active milestone from the client's payment authorization, no client signature needed
+    pub fn fund_from_authorization(ctx: Context<FundFromAuthorization>) -> Result<()> {
+        require!(!ctx.accounts.config.is_paused, ErrorCode::ProgramPaused);
+        require!(ctx.accounts.client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+        require!(ctx.accounts.freelancer_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+        // The client's authorized token account decides the mint, as a deposit by the client would
+        let mint = ctx.accounts.client_token_account.mint;
+        ctx.accounts.contract.pin_mint(mint)?;
+        let contract = &ctx.accounts.contract;
+        let payment_authorization = &ctx.accounts.payment_authorization;
+        require!(contract.contract_type == ContractType::Milestone, ErrorCode::InvalidContractState);
+        require!(!contract.is_completed && !contract.is_disputed, ErrorCode::InvalidContractState);
+        require!(
+            payment_authorization.contract_id == contract.contract_id
+                && payment_authorization.client == contract.client
+                && payment_authorization.freelancer == contract.freelancer,
+            ErrorCode::AuthorizationMismatch
+        );
+
+        // Escrow only needs to cover the milestone currently being worked on
+        let milestone_amount = contract
+            .total_amount
+            .checked_div(contract.milestone_count as u64)
+            .ok_or(ErrorCode::InvalidMilestone)?;
+        let amount = milestone_amount.saturating_sub(contract.escrow_balance);
+        require!(amount > 0, ErrorCode::EscrowAlreadyFunded);
+
+        let seeds = &[
+            b"escrow",
+            contract.escrow_seed_bytes(),
+            &[ctx.bumps.escrow_account],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = payment_auth::cpi::accounts::FundEscrow {
+            payment_authorization: ctx.accounts.payment_authorization.to_account_info(),
+            escrow_authority: ctx.accounts.escrow_account.to_account_info(),
+            client_token_account: ctx.accounts.client_token_account.to_account_info(),
+            escrow_token_account: ctx.accounts.escrow_token_account.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.payment_auth_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        let milestone_id = format!("{}-{}", contract.contract_id_str(), contract.completed_milestones);
+        payment_auth::cpi::fund_escrow(cpi_ctx, milestone_id, amount)?;
+
+        // Update contract state
+        let contract = &mut ctx.accounts.contract;
+        contract.is_active = true;
+        contract.escrow_balance += amount;
+
+        emit!(FundsDeposited {
+            contract_id: contract.contract_id,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
     // Freelancer submits milestone for approval
     pub fn submit_milestone(
         ctx: Context<SubmitMilestone>,
         milestone_index: u8,
         proof_uri: String,
     ) -> Result<()> {
-        let contract = &mut ctx.accounts.contract;
-        require!(contract.is_active, ErrorCode::ContractNotActive);
-        require!(milestone_index < contract.milestone_count, ErrorCode::InvalidMilestone);
-        
-        // Record milestone submission
-        let milestone = &mut ctx.accounts.milestone;
-        milestone.contract = contract.key();
-        milestone.index = milestone_index;
-        milestone.submitted_at = Clock::get()?.unix_timestamp;
-        milestone.approved_at = 0;
-        milestone.proof_uri = proof_uri;
-        milestone.is_approved = false;
-        milestone.payment_amount = 0;
-        
-        emit!(MilestoneSubmitted {
-            contract_id: contract.contract_id.clone(),
+        record_milestone_submission(
+            &ctx.accounts.config,
+            &ctx.accounts.contract,
+            &mut ctx.accounts.milestone,
             milestone_index,
-            timestamp: milestone.submitted_at,
-        });
-        
+            proof_uri,
+        )
+    }
+
+    // Freelancer submits a milestone as a list of content-hashed files. The deliverables account
+    // cannot change afterwards, so the client and any arbiter can check what was handed over
+    pub fn submit_milestone_deliverables(
+        ctx: Context<SubmitMilestoneDeliverables>,
+        milestone_index: u8,
+        items: Vec<Deliverable>,
+    ) -> Result<()> {
+        validate_deliverables(&items)?;
+        // The first item doubles as the milestone's proof URI for readers unaware of deliverables
+        record_milestone_submission(
+            &ctx.accounts.config,
+            &ctx.accounts.contract,
+            &mut ctx.accounts.milestone,
+            milestone_index,
+            items[0].uri.clone(),
+        )?;
+        ctx.accounts.milestone.has_deliverables = true;
+
+        let deliverables = &mut ctx.accounts.deliverables;
+        deliverables.version = Deliverables::VERSION;
+        deliverables.milestone = ctx.accounts.milestone.key();
+        deliverables.items = items;
+        deliverables.submitted_at = ctx.accounts.milestone.submitted_at;
+
         Ok(())
     }
 
-    // Client approves milestone and triggers payment
-    pub fn approve_milestone(
-        ctx: Context<ApproveMilestone>,
+    // Client approves milestone and triggers payment of the outstanding amount
+    pub fn approve_milestone<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveMilestone<'info>>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        release_milestone_payment(ctx, milestone_index, BPS_DENOMINATOR, None)
+    }
+
+    // Client approves a basis-point fraction of a milestone, leaving the rest open
+    pub fn approve_milestone_partial<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveMilestone<'info>>,
+        milestone_index: u8,
+        approval_bps: u16,
+    ) -> Result<()> {
+        require!(
+            approval_bps > 0 && approval_bps <= BPS_DENOMINATOR,
+            ErrorCode::InvalidApprovalBps
+        );
+        release_milestone_payment(ctx, milestone_index, approval_bps, None)
+    }
+
+    // Relayer submits an approval the client signed off-chain, so the client needs no SOL for fees.
+    // The instruction right before this one must be an Ed25519 program check of the client's
+    // signature over `signed_approval_message`
+    pub fn approve_milestone_signed<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveMilestone<'info>>,
         milestone_index: u8,
+        approval_bps: u16,
+        nonce: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(
+            approval_bps > 0 && approval_bps <= BPS_DENOMINATOR,
+            ErrorCode::InvalidApprovalBps
+        );
+        release_milestone_payment(ctx, milestone_index, approval_bps, Some(SignedApproval { nonce, expires_at }))
+    }
+
+    // Client approves several milestones in full with one aggregated transfer. The milestone accounts
+    // come first in the remaining accounts, in `milestone_indices` order, followed by any payee accounts;
+    // one bad milestone fails the whole batch
+    pub fn approve_milestones<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveMilestones<'info>>,
+        milestone_indices: Vec<u8>,
     ) -> Result<()> {
+        require!(!ctx.accounts.config.is_paused, ErrorCode::ProgramPaused);
+        let count = milestone_indices.len();
+        require!(
+            count > 0 && count <= MAX_BATCH_MILESTONES && ctx.remaining_accounts.len() >= count,
+            ErrorCode::InvalidMilestoneBatch
+        );
         let contract = &mut ctx.accounts.contract;
-        let milestone = &mut ctx.accounts.milestone;
-        
         require!(contract.is_active, ErrorCode::ContractNotActive);
-        require!(!milestone.is_approved, ErrorCode::MilestoneAlreadyApproved);
-        
-        // Calculate payment amount for this milestone
-        let milestone_amount = contract.total_amount / contract.milestone_count as u64;
-        
-        // Transfer from escrow to freelancer
+        // Approver sets release each milestone against its own proposal
+        require!(!contract.has_approver_set, ErrorCode::BatchApprovalUnsupported);
+
+        let (milestone_accounts, mut payee_accounts) = ctx.remaining_accounts.split_at(count);
+        let mut milestones = Vec::with_capacity(count);
+        let mut release_amount: u64 = 0;
+        let mut milestones_total: u64 = 0;
+        for (i, (index, account_info)) in milestone_indices.iter().zip(milestone_accounts).enumerate() {
+            require!(!milestone_indices[..i].contains(index), ErrorCode::InvalidMilestoneBatch);
+            require!(account_info.is_writable, ErrorCode::InvalidMilestoneBatch);
+            let milestone = Account::<Milestone>::try_from(account_info)?;
+            require!(
+                milestone.contract == contract.key() && milestone.index == *index,
+                ErrorCode::InvalidMilestoneBatch
+            );
+            require!(!milestone.is_approved, ErrorCode::MilestoneAlreadyApproved);
+            release_amount += milestone.payment_amount - milestone.amount_paid;
+            milestones_total += milestone.payment_amount;
+            milestones.push(milestone);
+        }
+        require!(release_amount <= contract.escrow_balance, ErrorCode::InsufficientEscrowBalance);
+
+        // The co-signer threshold applies to the batch total, partial releases already made included
+        require_co_signer(
+            contract,
+            ctx.accounts.co_signer_policy.as_deref(),
+            ctx.accounts.co_signer.as_ref(),
+            milestones_total,
+        )?;
+
+        payee_accounts = require_release_not_sanctioned(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            &ctx.accounts.client_sanction,
+            &ctx.accounts.recipient_sanction,
+            payee_accounts,
+        )?;
+
         let seeds = &[
             b"escrow",
-            contract.contract_id.as_bytes(),
+            contract.escrow_seed_bytes(),
             &[ctx.bumps.escrow_account],
         ];
         let signer = &[&seeds[..]];
-        
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.escrow_token_account.to_account_info(),
-            to: ctx.accounts.freelancer_token_account.to_account_info(),
-            authority: ctx.accounts.escrow_account.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::transfer(cpi_ctx, milestone_amount)?;
-        
-        // Update state
-        milestone.is_approved = true;
-        milestone.approved_at = Clock::get()?.unix_timestamp;
-        milestone.payment_amount = milestone_amount;
-        
-        contract.completed_milestones += 1;
-        contract.amount_released += milestone_amount;
-        contract.escrow_balance -= milestone_amount;
-        
-        // Check if contract is complete
+
+        let payouts = payout_shares(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            ctx.accounts.freelancer_token_account.to_account_info(),
+            payee_accounts,
+            release_amount,
+        )?;
+        for (destination, share) in payouts {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: destination,
+                authority: ctx.accounts.escrow_account.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, share)?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        contract.amount_released += release_amount;
+        contract.escrow_balance -= release_amount;
+        for mut milestone in milestones {
+            let amount = milestone.payment_amount - milestone.amount_paid;
+            milestone.amount_paid = milestone.payment_amount;
+            milestone.is_approved = true;
+            milestone.approved_at = now;
+            milestone.exit(&crate::ID)?;
+            contract.completed_milestones += 1;
+
+            emit!(MilestoneApproved {
+                contract_id: contract.contract_id,
+                milestone_index: milestone.index,
+                amount,
+                approval_bps: BPS_DENOMINATOR,
+                total_paid: milestone.amount_paid,
+                is_fully_approved: true,
+                timestamp: now,
+            });
+        }
+
         if contract.completed_milestones == contract.milestone_count {
             contract.is_completed = true;
-            contract.completed_at = Clock::get()?.unix_timestamp;
+            contract.completed_at = now;
         }
-        
-        emit!(MilestoneApproved {
-            contract_id: contract.contract_id.clone(),
+
+        Ok(())
+    }
+
+    // Approver proposes releasing `approval_bps` of a milestone and signs the proposal
+    pub fn propose_milestone_approval(
+        ctx: Context<ProposeMilestoneApproval>,
+        milestone_index: u8,
+        approval_bps: u16,
+    ) -> Result<()> {
+        require!(
+            approval_bps > 0 && approval_bps <= BPS_DENOMINATOR,
+            ErrorCode::InvalidApprovalBps
+        );
+        let approver = ctx.accounts.approver.key();
+        require!(ctx.accounts.approver_set.members.contains(&approver), ErrorCode::NotAnApprover);
+
+        let milestone_approval = &mut ctx.accounts.milestone_approval;
+        milestone_approval.version = MilestoneApproval::VERSION;
+        milestone_approval.milestone = ctx.accounts.milestone.key();
+        milestone_approval.round = 0;
+        milestone_approval.approval_bps = approval_bps;
+        milestone_approval.approvals = vec![approver];
+        milestone_approval.executed = false;
+
+        emit!(MilestoneApprovalSigned {
+            contract_id: ctx.accounts.contract.contract_id,
             milestone_index,
-            amount: milestone_amount,
+            approver,
+            approval_bps,
+            signatures: 1,
+            threshold: ctx.accounts.approver_set.threshold,
             timestamp: Clock::get()?.unix_timestamp,
         });
-        
+
         Ok(())
     }
 
-    // Emergency dispute resolution (funds returned to client)
-    pub fn dispute_contract(
-        ctx: Context<DisputeContract>,
-        reason: String,
+    // Approver starts a new round on an existing proposal, discarding signatures collected so far.
+    // A pending round can only be revised by its proposer; once executed, any approver opens the next
+    pub fn revise_milestone_approval(
+        ctx: Context<SignMilestoneApproval>,
+        milestone_index: u8,
+        approval_bps: u16,
+    ) -> Result<()> {
+        require!(
+            approval_bps > 0 && approval_bps <= BPS_DENOMINATOR,
+            ErrorCode::InvalidApprovalBps
+        );
+        let approver = ctx.accounts.approver.key();
+        require!(ctx.accounts.approver_set.members.contains(&approver), ErrorCode::NotAnApprover);
+
+        let milestone_approval = &mut ctx.accounts.milestone_approval;
+        require!(
+            milestone_approval.executed || milestone_approval.approvals.first() == Some(&approver),
+            ErrorCode::NotTheProposer
+        );
+        milestone_approval.round += 1;
+        milestone_approval.approval_bps = approval_bps;
+        milestone_approval.approvals = vec![approver];
+        milestone_approval.executed = false;
+
+        emit!(MilestoneApprovalSigned {
+            contract_id: ctx.accounts.contract.contract_id,
+            milestone_index,
+            approver,
+            approval_bps,
+            signatures: 1,
+            threshold: ctx.accounts.approver_set.threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Approver adds their signature to the pending proposal for a milestone
+    pub fn sign_milestone_approval(
+        ctx: Context<SignMilestoneApproval>,
+        milestone_index: u8,
+    ) -> Result<()> {
+        let approver = ctx.accounts.approver.key();
+        require!(ctx.accounts.approver_set.members.contains(&approver), ErrorCode::NotAnApprover);
+
+        let milestone_approval = &mut ctx.accounts.milestone_approval;
+        require!(!milestone_approval.executed, ErrorCode::ApprovalAlreadyExecuted);
+        require!(!milestone_approval.approvals.contains(&approver), ErrorCode::AlreadySigned);
+        milestone_approval.approvals.push(approver);
+
+        emit!(MilestoneApprovalSigned {
+            contract_id: ctx.accounts.contract.contract_id,
+            milestone_index,
+            approver,
+            approval_bps: milestone_approval.approval_bps,
+            signatures: milestone_approval.approvals.len() as u8,
+            threshold: ctx.accounts.approver_set.threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Client sends a bonus to the freelancer from their wallet or from unallocated escrow
+    pub fn pay_bonus<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayBonus<'info>>,
+        amount: u64,
+        from_escrow: bool,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidBonusAmount);
+        let contract = &mut ctx.accounts.contract;
+        let payee_accounts = require_release_not_sanctioned(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            &ctx.accounts.client_sanction,
+            &ctx.accounts.recipient_sanction,
+            ctx.remaining_accounts,
+        )?;
+
+        if from_escrow {
+            // A bonus out of the client's own wallet is their business; escrow stays locked while paused
+            require!(!ctx.accounts.config.is_paused, ErrorCode::ProgramPaused);
+            // Only escrow not reserved for outstanding milestones can be paid out as a bonus
+            let outstanding = contract.total_amount.saturating_sub(contract.amount_released);
+            let leftover = contract.escrow_balance.saturating_sub(outstanding);
+            require!(amount <= leftover, ErrorCode::InsufficientEscrowBalance);
+            require_co_signer(
+                contract,
+                ctx.accounts.co_signer_policy.as_deref(),
+                ctx.accounts.co_signer.as_ref(),
+                amount,
+            )?;
+
+            let seeds = &[
+                b"escrow",
+                contract.escrow_seed_bytes(),
+                &[ctx.bumps.escrow_account],
+            ];
+            let signer = &[&seeds[..]];
+
+            let payouts = payout_shares(
+                contract,
+                ctx.accounts.payee_split.as_deref(),
+                ctx.accounts.freelancer_token_account.to_account_info(),
+                payee_accounts,
+                amount,
+            )?;
+            for (destination, share) in payouts {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    to: destination,
+                    authority: ctx.accounts.escrow_account.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token::transfer(cpi_ctx, share)?;
+            }
+
+            contract.escrow_balance -= amount;
+        } else {
+            let payouts = payout_shares(
+                contract,
+                ctx.accounts.payee_split.as_deref(),
+                ctx.accounts.freelancer_token_account.to_account_info(),
+                payee_accounts,
+                amount,
+            )?;
+            for (destination, share) in payouts {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.client_token_account.to_account_info(),
+                    to: destination,
+                    authority: ctx.accounts.client.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token::transfer(cpi_ctx, share)?;
+            }
+        }
+
+        contract.bonus_paid += amount;
+
+        emit!(BonusPaid {
+            contract_id: contract.contract_id,
+            amount,
+            from_escrow,
+            total_bonus_paid: contract.bonus_paid,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Freelancer withdraws whatever has vested on a streaming contract so far
+    pub fn withdraw_vested<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawVested<'info>>,
     ) -> Result<()> {
         let contract = &mut ctx.accounts.contract;
+        require!(contract.contract_type == ContractType::Streaming, ErrorCode::NotStreamingContract);
+        require!(contract.is_active && !contract.is_completed, ErrorCode::ContractNotActive);
+
+        let now = Clock::get()?.unix_timestamp;
+        let vested = contract.vested_amount(now);
+        let withdrawable = vested
+            .saturating_sub(contract.amount_released)
+            .min(contract.escrow_balance);
+        require!(withdrawable > 0, ErrorCode::NothingToWithdraw);
+        let payee_accounts = require_release_not_sanctioned(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            &ctx.accounts.client_sanction,
+            &ctx.accounts.recipient_sanction,
+            ctx.remaining_accounts,
+        )?;
+
+        let seeds = &[
+            b"escrow",
+            contract.escrow_seed_bytes(),
+            &[ctx.bumps.escrow_account],
+        ];
+        let signer = &[&seeds[..]];
+
+        let payouts = payout_shares(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            ctx.accounts.freelancer_token_account.to_account_info(),
+            payee_accounts,
+            withdrawable,
+        )?;
+        for (destination, share) in payouts {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: destination,
+                authority: ctx.accounts.escrow_account.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, share)?;
+        }
+
+        contract.amount_released += withdrawable;
+        contract.escrow_balance -= withdrawable;
+
+        if contract.amount_released == contract.total_amount {
+            contract.is_completed = true;
+            contract.completed_at = now;
+        }
+
+        emit!(VestedWithdrawn {
+            contract_id: contract.contract_id,
+            amount: withdrawable,
+            total_withdrawn: contract.amount_released,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Client stops a streaming contract and recovers only the unvested portion
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        require!(contract.contract_type == ContractType::Streaming, ErrorCode::NotStreamingContract);
         require!(contract.is_active && !contract.is_completed, ErrorCode::InvalidContractState);
-        
-        // Return remaining escrow balance to client
-        let remaining_balance = contract.escrow_balance;
-        if remaining_balance > 0 {
+
+        let now = Clock::get()?.unix_timestamp;
+        let vested = contract.vested_amount(now);
+        let owed_to_freelancer = vested - contract.amount_released;
+        let refund = contract.escrow_balance.saturating_sub(owed_to_freelancer);
+
+        if refund > 0 {
+            // A blocklisted client's refund stays frozen in escrow
+            require!(ctx.accounts.client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+            require_co_signer(
+                contract,
+                ctx.accounts.co_signer_policy.as_deref(),
+                ctx.accounts.co_signer.as_ref(),
+                refund,
+            )?;
+
             let seeds = &[
                 b"escrow",
-                contract.contract_id.as_bytes(),
+                contract.escrow_seed_bytes(),
                 &[ctx.bumps.escrow_account],
             ];
             let signer = &[&seeds[..]];
-            
+
             let cpi_accounts = Transfer {
                 from: ctx.accounts.escrow_token_account.to_account_info(),
                 to: ctx.accounts.client_token_account.to_account_info(),
@@ -171,136 +752,2201 @@ pub mod freelance_escrow {
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
             let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-            token::transfer(cpi_ctx, remaining_balance)?;
-            
-            contract.escrow_balance = 0;
+            token::transfer(cpi_ctx, refund)?;
+
+            contract.escrow_balance -= refund;
         }
-        
-        contract.is_disputed = true;
-        contract.dispute_reason = reason;
-        
-        emit!(ContractDisputed {
-            contract_id: contract.contract_id.clone(),
-            reason,
-            timestamp: Clock::get()?.unix_timestamp,
+
+        // Truncate the stream so nothing vests past cancellation; the freelancer can still withdraw what is owed
+        contract.total_amount = vested;
+        contract.stream_end = now.max(contract.stream_start);
+        contract.is_cancelled = true;
+        if contract.amount_released == contract.total_amount {
+            contract.is_completed = true;
+            contract.completed_at = now;
+        }
+
+        emit!(StreamCancelled {
+            contract_id: contract.contract_id,
+            refunded: refund,
+            vested,
+            timestamp: now,
         });
-        
+
         Ok(())
     }
-}
 
-// Context structures for each instruction
-#[derive(Accounts)]
-#[instruction(contract_id: String)]
-pub struct CreateContract<'info> {
-    #[account(
-        init,
-        payer = freelancer,
-        space = 8 + FreelanceContract::INIT_SPACE,
-        seeds = [b"contract", contract_id.as_bytes()],
-        bump
-    )]
-    pub contract: Account<'info, FreelanceContract>,
-    #[account(mut)]
-    pub freelancer: Signer<'info>,
-    /// CHECK: Client public key for the contract
-    pub client: AccountInfo<'info>,
+    // Freelancer submits hours worked for one week of an hourly contract
+    pub fn submit_timesheet(
+        ctx: Context<SubmitTimesheet>,
+        week_index: u32,
+        hours: u16,
+        description_hash: [u8; 32],
+    ) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        require!(contract.contract_type == ContractType::Hourly, ErrorCode::NotHourlyContract);
+        require!(contract.is_active && !contract.is_completed, ErrorCode::ContractNotActive);
+        require!(hours > 0, ErrorCode::InvalidTimesheetHours);
+        require!(hours <= contract.weekly_hour_cap, ErrorCode::ExceedsWeeklyCap);
+
+        // Weeks are counted from contract creation and can only be billed once they have started
+        let now = Clock::get()?.unix_timestamp;
+        let period_start = contract.created_at + week_index as i64 * SECONDS_PER_WEEK;
+        require!(now >= period_start, ErrorCode::InvalidTimesheetPeriod);
+
+        let timesheet = &mut ctx.accounts.timesheet;
+        timesheet.version = Timesheet::VERSION;
+        timesheet.contract = contract.key();
+        timesheet.week_index = week_index;
+        timesheet.period_start = period_start;
+        timesheet.period_end = period_start + SECONDS_PER_WEEK;
+        timesheet.hours = hours;
+        timesheet.description_hash = description_hash;
+        timesheet.amount = (hours as u64)
+            .checked_mul(contract.hourly_rate)
+            .ok_or(ErrorCode::ExceedsContractBudget)?;
+        timesheet.status = TimesheetStatus::Submitted;
+        timesheet.submitted_at = now;
+        timesheet.resolved_at = 0;
+
+        emit!(TimesheetSubmitted {
+            contract_id: contract.contract_id,
+            week_index,
+            hours,
+            amount: timesheet.amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Client approves a timesheet and pays hours x rate from escrow
+    pub fn approve_timesheet<'info>(
+        ctx: Context<'_, '_, '_, 'info, ApproveTimesheet<'info>>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.config.is_paused, ErrorCode::ProgramPaused);
+        let contract = &mut ctx.accounts.contract;
+        let timesheet = &mut ctx.accounts.timesheet;
+
+        require!(contract.is_active && !contract.is_completed, ErrorCode::ContractNotActive);
+        require!(timesheet.status == TimesheetStatus::Submitted, ErrorCode::TimesheetNotPending);
+
+        let amount = timesheet.amount;
+        let released = contract
+            .amount_released
+            .checked_add(amount)
+            .ok_or(ErrorCode::ExceedsContractBudget)?;
+        require!(released <= contract.total_amount, ErrorCode::ExceedsContractBudget);
+        require!(amount <= contract.escrow_balance, ErrorCode::InsufficientEscrowBalance);
+        require_co_signer(
+            contract,
+            ctx.accounts.co_signer_policy.as_deref(),
+            ctx.accounts.co_signer.as_ref(),
+            amount,
+        )?;
+        let payee_accounts = require_release_not_sanctioned(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            &ctx.accounts.client_sanction,
+            &ctx.accounts.recipient_sanction,
+            ctx.remaining_accounts,
+        )?;
+
+        let seeds = &[
+            b"escrow",
+            contract.escrow_seed_bytes(),
+            &[ctx.bumps.escrow_account],
+        ];
+        let signer = &[&seeds[..]];
+
+        let payouts = payout_shares(
+            contract,
+            ctx.accounts.payee_split.as_deref(),
+            ctx.accounts.freelancer_token_account.to_account_info(),
+            payee_accounts,
+            amount,
+        )?;
+        for (destination, share) in payouts {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: destination,
+                authority: ctx.accounts.escrow_account.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, share)?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        timesheet.status = TimesheetStatus::Approved;
+        timesheet.resolved_at = now;
+        contract.amount_released = released;
+        contract.escrow_balance -= amount;
+
+        emit!(TimesheetApproved {
+            contract_id: contract.contract_id,
+            week_index: timesheet.week_index,
+            hours: timesheet.hours,
+            amount,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Client disputes a timesheet; no payment is made for it
+    pub fn dispute_timesheet(ctx: Context<DisputeTimesheet>) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        let timesheet = &mut ctx.accounts.timesheet;
+        require!(timesheet.status == TimesheetStatus::Submitted, ErrorCode::TimesheetNotPending);
+
+        let now = Clock::get()?.unix_timestamp;
+        timesheet.status = TimesheetStatus::Disputed;
+        timesheet.resolved_at = now;
+
+        emit!(TimesheetDisputed {
+            contract_id: contract.contract_id,
+            week_index: timesheet.week_index,
+            hours: timesheet.hours,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Emergency dispute resolution (funds returned to client)
+    pub fn dispute_contract(
+        ctx: Context<DisputeContract>,
+        reason: String,
+    ) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        require!(contract.is_active && !contract.is_completed, ErrorCode::InvalidContractState);
+        let now = Clock::get()?.unix_timestamp;
+
+        // What a stream has vested is already the freelancer's; like cancel_stream, the stream is
+        // truncated and only the unvested remainder goes back, the rest stays withdrawable
+        let mut owed_to_freelancer = 0;
+        if contract.contract_type == ContractType::Streaming {
+            let vested = contract.vested_amount(now);
+            owed_to_freelancer = vested - contract.amount_released;
+            contract.total_amount = vested;
+            contract.stream_end = now.max(contract.stream_start);
+        }
+
+        // Return the rest of the escrow balance to client
+        let refund = contract.escrow_balance.saturating_sub(owed_to_freelancer);
+        if refund > 0 {
+            // A blocklisted client's refund stays frozen in escrow
+            require!(ctx.accounts.client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+            // The refund leaves escrow like a release, so large ones need the co-signer as well
+            require_co_signer(
+                contract,
+                ctx.accounts.co_signer_policy.as_deref(),
+                ctx.accounts.co_signer.as_ref(),
+                refund,
+            )?;
+
+            let seeds = &[
+                b"escrow",
+                contract.escrow_seed_bytes(),
+                &[ctx.bumps.escrow_account],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.client_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_account.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, refund)?;
+            
+            contract.escrow_balance -= refund;
+        }
+        
+        contract.is_disputed = true;
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.version = Dispute::VERSION;
+        dispute.contract = contract.key();
+        dispute.reason = reason.clone();
+        dispute.opened_at = now;
+        
+        emit!(ContractDisputed {
+            contract_id: contract.contract_id,
+            reason,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    // Client or freelancer files a piece of evidence for arbitration. Each party gets its own
+    // numbered evidence accounts, up to MAX_EVIDENCE_PER_PARTY, until EVIDENCE_WINDOW after the dispute opened
+    pub fn submit_dispute_evidence(
+        ctx: Context<SubmitDisputeEvidence>,
+        evidence_index: u8,
+        hash: [u8; 32],
+        uri: String,
+    ) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        let dispute = &mut ctx.accounts.dispute;
+        let submitter = ctx.accounts.submitter.key();
+        let now = Clock::get()?.unix_timestamp;
+        require!(contract.is_disputed, ErrorCode::InvalidContractState);
+        require!(now <= dispute.opened_at + EVIDENCE_WINDOW, ErrorCode::EvidenceWindowClosed);
+        require!(
+            !uri.is_empty() && uri.len() <= MAX_EVIDENCE_URI_LEN && hash != [0; 32],
+            ErrorCode::InvalidEvidence
+        );
+
+        let count = if submitter == contract.client {
+            &mut dispute.client_evidence_count
+        } else if submitter == contract.freelancer {
+            &mut dispute.freelancer_evidence_count
+        } else {
+            return err!(ErrorCode::NotADisputeParty);
+        };
+        require!((*count as usize) < MAX_EVIDENCE_PER_PARTY, ErrorCode::EvidenceLimitReached);
+        // Indexes run 0, 1, 2... per party, so neither side can skip ahead or overwrite
+        require!(evidence_index == *count, ErrorCode::InvalidEvidence);
+        *count += 1;
+
+        let evidence = &mut ctx.accounts.evidence;
+        evidence.version = DisputeEvidence::VERSION;
+        evidence.dispute = dispute.key();
+        evidence.submitter = submitter;
+        evidence.index = evidence_index;
+        evidence.hash = hash;
+        evidence.uri = uri;
+        evidence.submitted_at = now;
+
+        emit!(EvidenceSubmitted {
+            contract_id: contract.contract_id,
+            submitter,
+            evidence_index,
+            hash,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Anyone can true up escrow_balance against the escrow token account; surplus goes back to the client,
+    // and so does whatever a completed or cancelled contract still records above what it owes
+    pub fn reconcile_escrow(ctx: Context<ReconcileEscrow>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        let recorded_balance = contract.escrow_balance;
+        let actual_balance = ctx.accounts.escrow_token_account.amount;
+        let surplus = actual_balance.saturating_sub(recorded_balance);
+        if surplus == 0 {
+            // Escrow holds less than recorded; follow the token account so releases stay payable
+            contract.escrow_balance = actual_balance;
+        }
+
+        // Milestone rounding dust and unused bonus deposits stay recorded after the last release
+        let mut leftover = 0;
+        if contract.is_completed || contract.is_cancelled {
+            let outstanding = if contract.is_completed {
+                0
+            } else {
+                contract.total_amount - contract.amount_released
+            };
+            leftover = contract.escrow_balance.saturating_sub(outstanding);
+        }
+
+        let refund = surplus + leftover;
+        if refund > 0 {
+            // Direct transfers and leftovers are not owed to anyone under the contract terms
+            require!(ctx.accounts.client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+
+            let seeds = &[
+                b"escrow",
+                contract.escrow_seed_bytes(),
+                &[ctx.bumps.escrow_account],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.client_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_account.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, refund)?;
+
+            contract.escrow_balance -= leftover;
+        }
+
+        emit!(EscrowReconciled {
+            contract_id: contract.contract_id,
+            recorded_balance,
+            actual_balance,
+            delta: (actual_balance as i128 - recorded_balance as i128) as i64,
+            surplus_refunded: surplus,
+            leftover_refunded: leftover,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Rewrite a contract created before accounts were versioned in the current layout
+    pub fn migrate_contract(ctx: Context<MigrateContract>) -> Result<()> {
+        let legacy = migrate_account::<FreelanceContractV0, FreelanceContract>(
+            &ctx.accounts.contract,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            |legacy| {
+                // Legacy ids are the PDA seed, so they can be at most 32 bytes; shorter ones are
+                // zero-padded into escrow_seed and the contract gets a hashed 32-byte id
+                let legacy_id = legacy.contract_id.as_bytes();
+                require!(!legacy_id.is_empty() && legacy_id.len() <= 32, ErrorCode::InvalidContractId);
+                let mut escrow_seed = [0; 32];
+                escrow_seed[..legacy_id.len()].copy_from_slice(legacy_id);
+                // Legacy contracts did not record their mint, so only the client can name it
+                let mint = match &ctx.accounts.mint {
+                    Some(mint) => {
+                        require_keys_eq!(ctx.accounts.payer.key(), legacy.client, ErrorCode::UnauthorizedClient);
+                        mint.key()
+                    }
+                    None => Pubkey::default(),
+                };
+                Ok(FreelanceContract {
+                    version: FreelanceContract::VERSION,
+                    contract_id: FreelanceContract::migrated_contract_id(&legacy.contract_id),
+                    freelancer: legacy.freelancer,
+                    client: legacy.client,
+                    // Legacy contracts keep their id-seeded address and escrow
+                    escrow_seed,
+                    contract_type: ContractType::Milestone,
+                    total_amount: legacy.total_amount,
+                    milestone_count: legacy.milestone_count,
+                    completed_milestones: legacy.completed_milestones,
+                    amount_released: legacy.amount_released,
+                    escrow_balance: legacy.escrow_balance,
+                    bonus_paid: 0,
+                    stream_start: 0,
+                    stream_end: 0,
+                    hourly_rate: 0,
+                    weekly_hour_cap: 0,
+                    has_payee_split: false,
+                    has_approver_set: false,
+                    is_active: legacy.is_active,
+                    is_completed: legacy.is_completed,
+                    is_disputed: legacy.is_disputed,
+                    created_at: legacy.created_at,
+                    completed_at: legacy.completed_at,
+                    has_co_signer: false,
+                    approval_nonce: 0,
+                    is_cancelled: false,
+                    escrow_seed_len: if legacy_id.len() == 32 { 0 } else { legacy_id.len() as u8 },
+                    mint,
+                    reserved: [0; 16],
+                })
+            },
+        )?;
+        // Same check `has_one = freelancer` makes on a deserialized contract
+        require_keys_eq!(
+            ctx.accounts.freelancer.key(),
+            legacy.freelancer,
+            anchor_lang::error::ErrorCode::ConstraintHasOne
+        );
+
+        // The compact layout needs less rent; the surplus goes back to the freelancer who paid it
+        let contract_info = ctx.accounts.contract.to_account_info();
+        let rent = Rent::get()?.minimum_balance(contract_info.data_len());
+        let surplus = contract_info.lamports().saturating_sub(rent);
+        if surplus > 0 {
+            **contract_info.try_borrow_mut_lamports()? -= surplus;
+            **ctx.accounts.freelancer.try_borrow_mut_lamports()? += surplus;
+        }
+
+        // The dispute reason moves out of the contract into its own account
+        if legacy.is_disputed {
+            let contract_key = ctx.accounts.contract.key();
+            let space = 8 + Dispute::INIT_SPACE;
+            let seeds = &[
+                b"dispute",
+                contract_key.as_ref(),
+                &[ctx.bumps.dispute],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = system_program::CreateAccount {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.dispute.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            system_program::create_account(
+                cpi_ctx,
+                Rent::get()?.minimum_balance(space),
+                space as u64,
+                &crate::ID,
+            )?;
+
+            let dispute = Dispute {
+                version: Dispute::VERSION,
+                contract: contract_key,
+                reason: legacy.dispute_reason,
+                // Legacy contracts did not record when the dispute was raised, so the evidence
+                // window runs from the migration
+                opened_at: Clock::get()?.unix_timestamp,
+                client_evidence_count: 0,
+                freelancer_evidence_count: 0,
+                reserved: [0; 30],
+            };
+            let mut data = ctx.accounts.dispute.try_borrow_mut_data()?;
+            dispute.try_serialize(&mut &mut data[..])?;
+        }
+
+        Ok(())
+    }
+
+    // Rewrite a milestone created before accounts were versioned in the current layout
+    pub fn migrate_milestone(ctx: Context<MigrateMilestone>) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        migrate_account::<MilestoneV0, Milestone>(
+            &ctx.accounts.account,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            |legacy| {
+                require_keys_eq!(legacy.contract, contract.key(), anchor_lang::error::ErrorCode::ConstraintHasOne);
+                // Size the payment from the parent contract, as submit_milestone does for new milestones
+                let payment_amount = contract
+                    .total_amount
+                    .checked_div(contract.milestone_count as u64)
+                    .ok_or(ErrorCode::InvalidMilestone)?;
+                Ok(Milestone {
+                    version: Milestone::VERSION,
+                    contract: legacy.contract,
+                    index: legacy.index,
+                    submitted_at: legacy.submitted_at,
+                    approved_at: legacy.approved_at,
+                    proof_uri: legacy.proof_uri.clone(),
+                    is_approved: legacy.is_approved,
+                    payment_amount,
+                    // Legacy approvals always paid the whole milestone
+                    amount_paid: if legacy.is_approved { payment_amount } else { 0 },
+                    has_deliverables: false,
+                    reserved: [0; 31],
+                })
+            },
+        )?;
+        Ok(())
+    }
+
+    // One-time setup of the program config by the upgrade authority; later changes go through the timelock
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        guardian: Pubkey,
+        admin: Pubkey,
+        timelock_delay: i64,
+    ) -> Result<()> {
+        require!(
+            (MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&timelock_delay),
+            ErrorCode::InvalidTimelockDelay
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.version = EscrowConfig::VERSION;
+        config.guardian = guardian;
+        config.is_paused = false;
+        config.paused_at = 0;
+        config.admin = admin;
+        config.timelock_delay = timelock_delay;
+        config.change_count = 0;
+        // KYC gating stays off until a `KycRequirement` change sets a threshold
+        config.kyc_threshold = 0;
+        config.kyc_min_level = 0;
+        config.reserved = [0; 7];
+        Ok(())
+    }
+
+    // Guardian halts or resumes deposits, submissions and approvals; refunds stay open while paused
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        config.is_paused = paused;
+        config.paused_at = if paused { now } else { 0 };
+
+        emit!(PauseChanged {
+            guardian: config.guardian,
+            paused,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Admin queues a config change that anyone can execute once the timelock delay has passed
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        if let ConfigChange::TimelockDelay(delay) = change {
+            require!((MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&delay), ErrorCode::InvalidTimelockDelay);
+        }
+
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        let pending = &mut ctx.accounts.pending_change;
+        pending.version = PendingConfigChange::VERSION;
+        pending.change_id = config.change_count;
+        pending.change = change.clone();
+        pending.proposer = ctx.accounts.admin.key();
+        pending.rent_payer = ctx.accounts.payer.key();
+        pending.queued_at = now;
+        pending.eta = now + config.timelock_delay;
+        pending.reserved = [0; 32];
+        config.change_count += 1;
+
+        emit!(ConfigChangeQueued {
+            change_id: pending.change_id,
+            change,
+            eta: pending.eta,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Apply a queued change after its delay; permissionless so a change cannot be held back
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending.eta, ErrorCode::TimelockNotElapsed);
+
+        let config = &mut ctx.accounts.config;
+        match pending.change {
+            ConfigChange::Guardian(guardian) => config.guardian = guardian,
+            ConfigChange::Admin(admin) => config.admin = admin,
+            ConfigChange::TimelockDelay(delay) => config.timelock_delay = delay,
+            ConfigChange::KycRequirement { threshold, min_level } => {
+                config.kyc_threshold = threshold;
+                config.kyc_min_level = min_level;
+            }
+        }
+
+        emit!(ConfigChangeExecuted {
+            change_id: pending.change_id,
+            change: pending.change.clone(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    // Admin or guardian drops a queued change before it is executed
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let canceller = ctx.accounts.canceller.key();
+        require!(
+            canceller == config.admin || canceller == config.guardian,
+            ErrorCode::NotConfigAdmin
+        );
+
+        let pending = &ctx.accounts.pending_change;
+        emit!(ConfigChangeCancelled {
+            change_id: pending.change_id,
+            change: pending.change.clone(),
+            canceller,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
+
+pub const BPS_DENOMINATOR: u16 = 10_000;
+pub const SECONDS_PER_WEEK: i64 = 7 * 24 * 60 * 60;
+pub const MAX_PAYEES: usize = 10;
+pub const MAX_APPROVERS: usize = 10;
+pub const MAX_BATCH_MILESTONES: usize = 10;
+pub const MAX_DELIVERABLES: usize = 8;
+pub const MAX_DELIVERABLE_URI_LEN: usize = 200;
+pub const MAX_MIME_TYPE_LEN: usize = 64;
+pub const MAX_EVIDENCE_PER_PARTY: usize = 5;
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+pub const EVIDENCE_WINDOW: i64 = 14 * 24 * 60 * 60;
+// A zero delay would let the admin apply changes before the guardian can cancel them
+pub const MIN_TIMELOCK_DELAY: i64 = 24 * 60 * 60;
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+pub const SIGNED_APPROVAL_DOMAIN: &[u8] = b"smartflo:approve_milestone:v1";
+
+// Bytes a client signs off-chain to approve releasing `amount` of a milestone through a relayer
+pub fn signed_approval_message(
+    contract: &Pubkey,
+    milestone_index: u8,
+    amount: u64,
+    nonce: u64,
+    expires_at: i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(SIGNED_APPROVAL_DOMAIN.len() + 57);
+    message.extend_from_slice(SIGNED_APPROVAL_DOMAIN);
+    message.extend_from_slice(contract.as_ref());
+    message.push(milestone_index);
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+// Nonce and expiry of a client approval relayed with an Ed25519 signature
+struct SignedApproval {
+    nonce: u64,
+    expires_at: i64,
+}
+
+// Shared initialization for every contract type
+fn initialize_contract(
+    ctx: &mut Context<CreateContract>,
+    contract_id: [u8; 32],
+    total_amount: u64,
+    milestone_count: u8,
+    contract_type: ContractType,
+) -> Result<()> {
+    require!(
+        contract_id.iter().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')),
+        ErrorCode::InvalidContractId
+    );
+    require!(
+        contract_type != ContractType::Milestone || milestone_count > 0,
+        ErrorCode::InvalidMilestone
+    );
+
+    // Large contracts need both participants to hold a current payment_auth KYC attestation
+    let now = Clock::get()?.unix_timestamp;
+    let config = &ctx.accounts.config;
+    if config.kyc_threshold > 0 && total_amount > config.kyc_threshold {
+        for attestation in [&ctx.accounts.freelancer_kyc, &ctx.accounts.client_kyc] {
+            let attested = matches!(
+                KycAttestation::load(attestation),
+                Some(kyc) if kyc.is_valid(config.kyc_min_level, now)
+            );
+            require!(attested, ErrorCode::KycRequired);
+        }
+    }
+
+    let contract = &mut ctx.accounts.contract;
+    contract.version = FreelanceContract::VERSION;
+    contract.contract_id = contract_id;
+    contract.freelancer = ctx.accounts.freelancer.key();
+    contract.client = ctx.accounts.client.key();
+    contract.escrow_seed = contract.key().to_bytes();
+    contract.contract_type = contract_type;
+    contract.total_amount = total_amount;
+    contract.milestone_count = milestone_count;
+    contract.completed_milestones = 0;
+    contract.amount_released = 0;
+    contract.escrow_balance = 0;
+    contract.bonus_paid = 0;
+    contract.stream_start = 0;
+    contract.stream_end = 0;
+    contract.hourly_rate = 0;
+    contract.weekly_hour_cap = 0;
+    contract.has_payee_split = false;
+    contract.has_approver_set = false;
+    contract.is_active = false;
+    contract.is_completed = false;
+    contract.is_disputed = false;
+    contract.created_at = now;
+    contract.completed_at = 0;
+    contract.has_co_signer = false;
+    contract.approval_nonce = 0;
+    contract.is_cancelled = false;
+    contract.escrow_seed_len = 0;
+    contract.mint = ctx.accounts.mint.key();
+    Ok(())
+}
+
+// Shares must be non-zero, unique per wallet and add up to 100%
+fn validate_payees(payees: &[Payee]) -> Result<()> {
+    require!(!payees.is_empty() && payees.len() <= MAX_PAYEES, ErrorCode::InvalidPayeeSplit);
+    let mut total_bps: u32 = 0;
+    for (i, payee) in payees.iter().enumerate() {
+        require!(payee.share_bps > 0, ErrorCode::InvalidPayeeSplit);
+        require!(
+            payees[..i].iter().all(|other| other.wallet != payee.wallet),
+            ErrorCode::InvalidPayeeSplit
+        );
+        total_bps += payee.share_bps as u32;
+    }
+    require!(total_bps == BPS_DENOMINATOR as u32, ErrorCode::InvalidPayeeSplit);
+    Ok(())
+}
+
+// Validation and milestone record shared by both ways of submitting a milestone
+fn record_milestone_submission(
+    config: &EscrowConfig,
+    contract: &Account<FreelanceContract>,
+    milestone: &mut Account<Milestone>,
+    milestone_index: u8,
+    proof_uri: String,
+) -> Result<()> {
+    require!(!config.is_paused, ErrorCode::ProgramPaused);
+    require!(contract.is_active, ErrorCode::ContractNotActive);
+    require!(milestone_index < contract.milestone_count, ErrorCode::InvalidMilestone);
+
+    milestone.version = Milestone::VERSION;
+    milestone.contract = contract.key();
+    milestone.index = milestone_index;
+    milestone.submitted_at = Clock::get()?.unix_timestamp;
+    milestone.approved_at = 0;
+    milestone.proof_uri = proof_uri;
+    milestone.is_approved = false;
+    milestone.payment_amount = contract.total_amount / contract.milestone_count as u64;
+    milestone.amount_paid = 0;
+
+    emit!(MilestoneSubmitted {
+        contract_id: contract.contract_id,
+        milestone_index,
+        timestamp: milestone.submitted_at,
+    });
+
+    Ok(())
+}
+
+// Every item needs a URI, a MIME label and a hash, within the account's size limits
+fn validate_deliverables(items: &[Deliverable]) -> Result<()> {
+    require!(!items.is_empty() && items.len() <= MAX_DELIVERABLES, ErrorCode::InvalidDeliverables);
+    for item in items {
+        require!(
+            !item.uri.is_empty() && item.uri.len() <= MAX_DELIVERABLE_URI_LEN,
+            ErrorCode::InvalidDeliverables
+        );
+        require!(
+            !item.mime_type.is_empty() && item.mime_type.len() <= MAX_MIME_TYPE_LEN,
+            ErrorCode::InvalidDeliverables
+        );
+        require!(item.sha256 != [0; 32], ErrorCode::InvalidDeliverables);
+    }
+    Ok(())
+}
+
+// Destination token accounts and amounts for a release; split contracts pay each payee's
+// token account (passed as remaining accounts in split order), the last payee absorbing rounding
+fn payout_shares<'info>(
+    contract: &FreelanceContract,
+    payee_split: Option<&PayeeSplit>,
+    freelancer_token_account: AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+    if !contract.has_payee_split {
+        return Ok(vec![(freelancer_token_account, amount)]);
+    }
+
+    let split = payee_split.ok_or(ErrorCode::PayeeSplitRequired)?;
+    require!(remaining_accounts.len() == split.payees.len(), ErrorCode::PayeeAccountsMismatch);
+
+    let mut payouts = Vec::with_capacity(split.payees.len());
+    let mut distributed: u64 = 0;
+    for (i, (payee, account_info)) in split.payees.iter().zip(remaining_accounts).enumerate() {
+        let token_account = TokenAccount::try_deserialize(&mut &account_info.try_borrow_data()?[..])?;
+        require_keys_eq!(token_account.owner, payee.wallet, ErrorCode::PayeeAccountsMismatch);
+
+        let share = if i + 1 == split.payees.len() {
+            amount - distributed
+        } else {
+            (amount as u128 * payee.share_bps as u128 / BPS_DENOMINATOR as u128) as u64
+        };
+        distributed += share;
+        if share > 0 {
+            payouts.push((account_info.clone(), share));
+        }
+    }
+    Ok(payouts)
+}
+
+// Neither the paying client nor any receiving wallet may be on the blocklist. Split releases pass each
+// payee's blocklist entry after the payee token accounts; returns just the token accounts
+fn require_release_not_sanctioned<'a, 'info>(
+    contract: &FreelanceContract,
+    payee_split: Option<&PayeeSplit>,
+    client_sanction: &AccountInfo<'info>,
+    recipient_sanction: &AccountInfo<'info>,
+    payee_accounts: &'a [AccountInfo<'info>],
+) -> Result<&'a [AccountInfo<'info>]> {
+    require!(client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+    if !contract.has_payee_split {
+        require!(recipient_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+        return Ok(payee_accounts);
+    }
+    let split = payee_split.ok_or(ErrorCode::PayeeSplitRequired)?;
+    require!(
+        payee_accounts.len() == 2 * split.payees.len(),
+        ErrorCode::PayeeAccountsMismatch
+    );
+    let (token_accounts, sanction_entries) = payee_accounts.split_at(split.payees.len());
+    require_payees_not_sanctioned(split, sanction_entries)?;
+    Ok(token_accounts)
+}
+
+// Outflows above the co-signer threshold need the policy's second key as a signer
+fn require_co_signer(
+    contract: &FreelanceContract,
+    policy: Option<&CoSignerPolicy>,
+    co_signer: Option<&Signer>,
+    amount: u64,
+) -> Result<()> {
+    if !contract.has_co_signer {
+        return Ok(());
+    }
+    let policy = policy.ok_or(ErrorCode::CoSignerRequired)?;
+    if amount > policy.threshold {
+        let co_signer = co_signer.ok_or(ErrorCode::CoSignerRequired)?;
+        require_keys_eq!(co_signer.key(), policy.co_signer, ErrorCode::CoSignerRequired);
+    }
+    Ok(())
+}
+
+// Blocklist entries must be the ones of the split's payees, in split order
+fn require_payees_not_sanctioned(split: &PayeeSplit, sanction_entries: &[AccountInfo]) -> Result<()> {
+    for (payee, entry) in split.payees.iter().zip(sanction_entries) {
+        let (address, _) =
+            Pubkey::find_program_address(&[b"sanctioned", payee.wallet.as_ref()], &payment_auth::ID);
+        require_keys_eq!(entry.key(), address, ErrorCode::PayeeAccountsMismatch);
+        require!(entry.data_is_empty(), ErrorCode::SanctionedParty);
+    }
+    Ok(())
+}
+
+// Requires the instruction before the current one to be an Ed25519 program check of `message` signed
+// by `signer`. Every offset must point into that instruction's own data, so the key and message read
+// here are the ones the precompile verified
+fn require_ed25519_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidApprovalSignature);
+    let verify = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(verify.program_id, ed25519_program::ID, ErrorCode::InvalidApprovalSignature);
+
+    // One signature: count and padding, then seven u16 offsets
+    let data = &verify.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidApprovalSignature);
+    let read = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    require!(
+        [read(4), read(8), read(14)].iter().all(|index| *index == u16::MAX),
+        ErrorCode::InvalidApprovalSignature
+    );
+    let public_key_offset = read(6) as usize;
+    let (message_offset, message_size) = (read(10) as usize, read(12) as usize);
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidApprovalSignature)?;
+    let signed = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidApprovalSignature)?;
+    require!(
+        public_key == signer.as_ref() && signed == message,
+        ErrorCode::InvalidApprovalSignature
+    );
+    Ok(())
+}
+
+// Pays `approval_bps` of the milestone from escrow; a full approval settles whatever is still owed.
+// Signed approvals come from a relayer and carry the client's off-chain signature instead
+fn release_milestone_payment<'info>(
+    ctx: Context<'_, '_, '_, 'info, ApproveMilestone<'info>>,
+    milestone_index: u8,
+    approval_bps: u16,
+    signed: Option<SignedApproval>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.is_paused, ErrorCode::ProgramPaused);
+    let contract = &mut ctx.accounts.contract;
+    let milestone = &mut ctx.accounts.milestone;
+
+    require!(contract.is_active, ErrorCode::ContractNotActive);
+    require!(!milestone.is_approved, ErrorCode::MilestoneAlreadyApproved);
+
+    // Contracts with an approver set release only against a proposal that reached its threshold
+    let approver = ctx.accounts.client.key();
+    if signed.is_some() {
+        // The client's signature is checked once the release amount is known
+        require!(!contract.has_approver_set, ErrorCode::SignedApprovalUnsupported);
+    } else if contract.has_approver_set {
+        let approver_set = ctx.accounts.approver_set.as_ref().ok_or(ErrorCode::ApproverSetRequired)?;
+        let milestone_approval = ctx
+            .accounts
+            .milestone_approval
+            .as_mut()
+            .ok_or(ErrorCode::ApproverSetRequired)?;
+        require!(approver_set.members.contains(&approver), ErrorCode::NotAnApprover);
+        require!(!milestone_approval.executed, ErrorCode::ApprovalAlreadyExecuted);
+        require!(milestone_approval.approval_bps == approval_bps, ErrorCode::ApprovalMismatch);
+        require!(
+            milestone_approval.approvals.len() >= approver_set.threshold as usize,
+            ErrorCode::ApprovalThresholdNotMet
+        );
+        milestone_approval.executed = true;
+    } else {
+        require_keys_eq!(approver, contract.client, ErrorCode::UnauthorizedClient);
+    }
+
+    // Calculate payment amount for this approval
+    let remaining = milestone.payment_amount - milestone.amount_paid;
+    let release_amount = if approval_bps == BPS_DENOMINATOR {
+        remaining
+    } else {
+        (milestone.payment_amount as u128 * approval_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    };
+    require!(release_amount > 0, ErrorCode::InvalidApprovalBps);
+    require!(release_amount <= remaining, ErrorCode::ApprovalExceedsRemaining);
+    require!(release_amount <= contract.escrow_balance, ErrorCode::InsufficientEscrowBalance);
+
+    // The signed message pins the amount, and the nonce is consumed so the approval cannot be replayed
+    if let Some(signed) = signed {
+        require!(
+            Clock::get()?.unix_timestamp <= signed.expires_at,
+            ErrorCode::ApprovalSignatureExpired
+        );
+        require!(signed.nonce == contract.approval_nonce, ErrorCode::InvalidApprovalNonce);
+        let instructions = ctx.accounts.instructions.as_ref().ok_or(ErrorCode::InvalidApprovalSignature)?;
+        let message = signed_approval_message(
+            &contract.key(),
+            milestone_index,
+            release_amount,
+            signed.nonce,
+            signed.expires_at,
+        );
+        require_ed25519_signature(instructions, &contract.client, &message)?;
+        contract.approval_nonce += 1;
+    }
+
+    // Releases above the co-signer threshold need the second key too, so a stolen client key alone cannot pay out.
+    // It counts what the milestone has already paid, so a release split into pieces cannot slip under it
+    require_co_signer(
+        contract,
+        ctx.accounts.co_signer_policy.as_deref(),
+        ctx.accounts.co_signer.as_ref(),
+        milestone.amount_paid + release_amount,
+    )?;
+
+    let payee_accounts = require_release_not_sanctioned(
+        contract,
+        ctx.accounts.payee_split.as_deref(),
+        &ctx.accounts.client_sanction,
+        &ctx.accounts.recipient_sanction,
+        ctx.remaining_accounts,
+    )?;
+
+    // Transfer from escrow to freelancer
+    let seeds = &[
+        b"escrow",
+        contract.escrow_seed_bytes(),
+        &[ctx.bumps.escrow_account],
+    ];
+    let signer = &[&seeds[..]];
+
+    let payouts = payout_shares(
+        contract,
+        ctx.accounts.payee_split.as_deref(),
+        ctx.accounts.freelancer_token_account.to_account_info(),
+        payee_accounts,
+        release_amount,
+    )?;
+    for (destination, share) in payouts {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: destination,
+            authority: ctx.accounts.escrow_account.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, share)?;
+    }
+
+    // Update state
+    let now = Clock::get()?.unix_timestamp;
+    milestone.amount_paid += release_amount;
+    contract.amount_released += release_amount;
+    contract.escrow_balance -= release_amount;
+
+    // Milestone stays open until it has been paid in full
+    if milestone.amount_paid == milestone.payment_amount {
+        milestone.is_approved = true;
+        milestone.approved_at = now;
+        contract.completed_milestones += 1;
+    }
+
+    // Check if contract is complete
+    if contract.completed_milestones == contract.milestone_count {
+        contract.is_completed = true;
+        contract.completed_at = now;
+    }
+
+    emit!(MilestoneApproved {
+        contract_id: contract.contract_id,
+        milestone_index,
+        amount: release_amount,
+        approval_bps,
+        total_paid: milestone.amount_paid,
+        is_fully_approved: milestone.is_approved,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// Reallocates a legacy account to the current layout and rewrites it; the payer covers any extra rent
+fn migrate_account<'info, Legacy, Current>(
+    account: &UncheckedAccount<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    upgrade: impl FnOnce(&Legacy) -> Result<Current>,
+) -> Result<Legacy>
+where
+    Legacy: AnchorDeserialize + Space,
+    Current: AccountSerialize + Discriminator + Space,
+{
+    let legacy_len = 8 + Legacy::INIT_SPACE;
+    let current_len = 8 + Current::INIT_SPACE;
+
+    let legacy = {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Current::DISCRIMINATOR,
+            ErrorCode::UnknownAccountLayout
+        );
+        require!(data.len() != current_len, ErrorCode::AccountAlreadyMigrated);
+        require!(data.len() == legacy_len, ErrorCode::UnknownAccountLayout);
+        Legacy::deserialize(&mut &data[8..])?
+    };
+    let current = upgrade(&legacy)?;
+
+    let rent = Rent::get()?.minimum_balance(current_len);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.to_account_info(),
+            to: account.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, top_up)?;
+    }
+
+    account.realloc(current_len, false)?;
+    let mut data = account.try_borrow_mut_data()?;
+    current.try_serialize(&mut &mut data[..])?;
+    Ok(legacy)
+}
+
+// Context structures for each instruction
+#[derive(Accounts)]
+#[instruction(contract_id: [u8; 32])]
+pub struct CreateContract<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + FreelanceContract::INIT_SPACE,
+        // Namespaced by both participants so nobody else can claim an id first
+        seeds = [b"contract", freelancer.key().as_ref(), client.key().as_ref(), contract_id.as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, FreelanceContract>,
+    pub freelancer: Signer<'info>,
+    // Pays the rent; the freelancer or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Client public key for the contract
+    pub client: AccountInfo<'info>,
+    // Token the escrow will hold; deposits, funding and reconciliation are bound to it
+    pub mint: Account<'info, Mint>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    /// CHECK: payment_auth KYC attestation of the freelancer; only read above the config's threshold
+    #[account(seeds = [b"kyc", freelancer.key().as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub freelancer_kyc: UncheckedAccount<'info>,
+    /// CHECK: payment_auth KYC attestation of the client; only read above the config's threshold
+    #[account(seeds = [b"kyc", client.key().as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_kyc: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayeeSplit<'info> {
+    #[account(mut, has_one = freelancer)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PayeeSplit::INIT_SPACE,
+        seeds = [b"payee_split", contract.key().as_ref()],
+        bump
+    )]
+    pub payee_split: Account<'info, PayeeSplit>,
+    pub freelancer: Signer<'info>,
+    // Pays the rent; the freelancer or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePayeeSplit<'info> {
+    #[account(has_one = freelancer, has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        mut,
+        seeds = [b"payee_split", contract.key().as_ref()],
+        bump
+    )]
+    pub payee_split: Account<'info, PayeeSplit>,
+    pub freelancer: Signer<'info>,
+    pub client: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetApproverSet<'info> {
+    #[account(mut, has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ApproverSet::INIT_SPACE,
+        seeds = [b"approver_set", contract.key().as_ref()],
+        bump
+    )]
+    pub approver_set: Account<'info, ApproverSet>,
+    pub client: Signer<'info>,
+    // Pays the rent; the client or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCoSigner<'info> {
+    #[account(mut, has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CoSignerPolicy::INIT_SPACE,
+        seeds = [b"co_signer", contract.key().as_ref()],
+        bump
+    )]
+    pub co_signer_policy: Account<'info, CoSignerPolicy>,
+    pub client: Signer<'info>,
+    // Pays the rent; the client or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // Signs so a mistyped key cannot lock releases
+    pub co_signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCoSigner<'info> {
+    #[account(has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        mut,
+        seeds = [b"co_signer", contract.key().as_ref()],
+        bump,
+        has_one = co_signer @ ErrorCode::UnauthorizedCoSigner
+    )]
+    pub co_signer_policy: Account<'info, CoSignerPolicy>,
+    pub client: Signer<'info>,
+    pub co_signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositFunds<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut)]
+    pub contract: Account<'info, FreelanceContract>,
+    pub client: Signer<'info>,
+    // Pays the rent; the client or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub client_token_account: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = usdc_mint,
+        associated_token::authority = escrow_account
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    /// CHECK: USDC mint address
+    pub usdc_mint: AccountInfo<'info>,
+    /// CHECK: payment_auth blocklist entry of the client; must not exist
+    #[account(seeds = [b"sanctioned", client.key().as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer; must not exist
+    #[account(seeds = [b"sanctioned", contract.freelancer.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub freelancer_sanction: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundFromAuthorization<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(mut)]
+    pub payment_authorization: Account<'info, PaymentAuthorization>,
+    #[account(
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = client_token_account.mint,
+        associated_token::authority = escrow_account
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub client_token_account: Account<'info, TokenAccount>,
+    /// CHECK: payment_auth blocklist entry of the client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer; must not exist
+    #[account(seeds = [b"sanctioned", contract.freelancer.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub freelancer_sanction: UncheckedAccount<'info>,
+    pub payment_auth_program: Program<'info, PaymentAuth>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct SubmitMilestone<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut, has_one = freelancer)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Milestone::INIT_SPACE,
+        seeds = [b"milestone", contract.key().as_ref(), &[milestone_index]],
+        bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+    pub freelancer: Signer<'info>,
+    // Pays the rent; the freelancer or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct DepositFunds<'info> {
+#[instruction(milestone_index: u8, items: Vec<Deliverable>)]
+pub struct SubmitMilestoneDeliverables<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(has_one = freelancer)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Milestone::INIT_SPACE,
+        seeds = [b"milestone", contract.key().as_ref(), &[milestone_index]],
+        bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+    // Never reinitialized or written again, which keeps the submitted list fixed
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Deliverables::space_for(&items),
+        seeds = [b"deliverables", milestone.key().as_ref()],
+        bump
+    )]
+    pub deliverables: Account<'info, Deliverables>,
+    pub freelancer: Signer<'info>,
+    // Pays the rent; the freelancer or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ApproveMilestone<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(mut, seeds = [b"milestone", contract.key().as_ref(), &[milestone_index]], bump)]
+    pub milestone: Account<'info, Milestone>,
+    // Client or approver; the relayer for signed approvals
+    #[account(mut)]
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    // A relayer submitting a signed approval must not pick where the release goes
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == contract.freelancer
+    )]
+    pub freelancer_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"payee_split", contract.key().as_ref()], bump)]
+    pub payee_split: Option<Account<'info, PayeeSplit>>,
+    #[account(seeds = [b"approver_set", contract.key().as_ref()], bump)]
+    pub approver_set: Option<Account<'info, ApproverSet>>,
+    #[account(
+        mut,
+        seeds = [b"milestone_approval", milestone.key().as_ref()],
+        bump
+    )]
+    pub milestone_approval: Option<Account<'info, MilestoneApproval>>,
+    #[account(seeds = [b"co_signer", contract.key().as_ref()], bump)]
+    pub co_signer_policy: Option<Account<'info, CoSignerPolicy>>,
+    pub co_signer: Option<Signer<'info>>,
+    /// CHECK: instructions sysvar, read for the Ed25519 check of signed approvals
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer token account's owner; must not exist
+    #[account(
+        seeds = [b"sanctioned", freelancer_token_account.owner.as_ref()],
+        bump,
+        seeds::program = payment_auth::ID
+    )]
+    pub recipient_sanction: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveMilestones<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut, has_one = client @ ErrorCode::UnauthorizedClient)]
+    pub contract: Account<'info, FreelanceContract>,
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == contract.freelancer
+    )]
+    pub freelancer_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"payee_split", contract.key().as_ref()], bump)]
+    pub payee_split: Option<Account<'info, PayeeSplit>>,
+    #[account(seeds = [b"co_signer", contract.key().as_ref()], bump)]
+    pub co_signer_policy: Option<Account<'info, CoSignerPolicy>>,
+    pub co_signer: Option<Signer<'info>>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer token account's owner; must not exist
+    #[account(
+        seeds = [b"sanctioned", freelancer_token_account.owner.as_ref()],
+        bump,
+        seeds::program = payment_auth::ID
+    )]
+    pub recipient_sanction: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct ProposeMilestoneApproval<'info> {
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(seeds = [b"milestone", contract.key().as_ref(), &[milestone_index]], bump)]
+    pub milestone: Account<'info, Milestone>,
+    #[account(seeds = [b"approver_set", contract.key().as_ref()], bump)]
+    pub approver_set: Account<'info, ApproverSet>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MilestoneApproval::INIT_SPACE,
+        seeds = [b"milestone_approval", milestone.key().as_ref()],
+        bump
+    )]
+    pub milestone_approval: Account<'info, MilestoneApproval>,
+    pub approver: Signer<'info>,
+    // Pays the rent; the approver or a sponsoring platform
     #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(milestone_index: u8)]
+pub struct SignMilestoneApproval<'info> {
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(seeds = [b"milestone", contract.key().as_ref(), &[milestone_index]], bump)]
+    pub milestone: Account<'info, Milestone>,
+    #[account(seeds = [b"approver_set", contract.key().as_ref()], bump)]
+    pub approver_set: Account<'info, ApproverSet>,
+    #[account(
+        mut,
+        seeds = [b"milestone_approval", milestone.key().as_ref()],
+        bump
+    )]
+    pub milestone_approval: Account<'info, MilestoneApproval>,
+    pub approver: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PayBonus<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut, has_one = client)]
     pub contract: Account<'info, FreelanceContract>,
     #[account(mut)]
     pub client: Signer<'info>,
     #[account(mut)]
     pub client_token_account: Account<'info, TokenAccount>,
     #[account(
-        init_if_needed,
-        payer = client,
-        seeds = [b"escrow", contract.contract_id.as_bytes()],
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == contract.freelancer
+    )]
+    pub freelancer_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"payee_split", contract.key().as_ref()], bump)]
+    pub payee_split: Option<Account<'info, PayeeSplit>>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer token account's owner; must not exist
+    #[account(
+        seeds = [b"sanctioned", freelancer_token_account.owner.as_ref()],
+        bump,
+        seeds::program = payment_auth::ID
+    )]
+    pub recipient_sanction: UncheckedAccount<'info>,
+    #[account(seeds = [b"co_signer", contract.key().as_ref()], bump)]
+    pub co_signer_policy: Option<Account<'info, CoSignerPolicy>>,
+    pub co_signer: Option<Signer<'info>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    #[account(mut, has_one = freelancer)]
+    pub contract: Account<'info, FreelanceContract>,
+    pub freelancer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == contract.freelancer
+    )]
+    pub freelancer_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"payee_split", contract.key().as_ref()], bump)]
+    pub payee_split: Option<Account<'info, PayeeSplit>>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer token account's owner; must not exist
+    #[account(
+        seeds = [b"sanctioned", freelancer_token_account.owner.as_ref()],
+        bump,
+        seeds::program = payment_auth::ID
+    )]
+    pub recipient_sanction: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(mut, has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = client_token_account.owner == contract.client
+    )]
+    pub client_token_account: Account<'info, TokenAccount>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    #[account(seeds = [b"co_signer", contract.key().as_ref()], bump)]
+    pub co_signer_policy: Option<Account<'info, CoSignerPolicy>>,
+    pub co_signer: Option<Signer<'info>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(week_index: u32)]
+pub struct SubmitTimesheet<'info> {
+    #[account(has_one = freelancer)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Timesheet::INIT_SPACE,
+        seeds = [b"timesheet", contract.key().as_ref(), &week_index.to_le_bytes()],
+        bump
+    )]
+    pub timesheet: Account<'info, Timesheet>,
+    pub freelancer: Signer<'info>,
+    // Pays the rent; the freelancer or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveTimesheet<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut, has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(mut, has_one = contract)]
+    pub timesheet: Account<'info, Timesheet>,
+    pub client: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = freelancer_token_account.owner == contract.freelancer
+    )]
+    pub freelancer_token_account: Account<'info, TokenAccount>,
+    #[account(seeds = [b"payee_split", contract.key().as_ref()], bump)]
+    pub payee_split: Option<Account<'info, PayeeSplit>>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    /// CHECK: payment_auth blocklist entry of the freelancer token account's owner; must not exist
+    #[account(
+        seeds = [b"sanctioned", freelancer_token_account.owner.as_ref()],
         bump,
-        space = 8 + 32
+        seeds::program = payment_auth::ID
+    )]
+    pub recipient_sanction: UncheckedAccount<'info>,
+    #[account(seeds = [b"co_signer", contract.key().as_ref()], bump)]
+    pub co_signer_policy: Option<Account<'info, CoSignerPolicy>>,
+    pub co_signer: Option<Signer<'info>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeTimesheet<'info> {
+    #[account(has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(mut, has_one = contract)]
+    pub timesheet: Account<'info, Timesheet>,
+    pub client: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeContract<'info> {
+    #[account(mut, has_one = client)]
+    pub contract: Account<'info, FreelanceContract>,
+    // Only disputed contracts pay rent for the reason text
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [b"dispute", contract.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub client: Signer<'info>,
+    // Pays the rent; the client or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
+    )]
+    pub escrow_account: SystemAccount<'info>,
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = client_token_account.owner == contract.client
+    )]
+    pub client_token_account: Account<'info, TokenAccount>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    #[account(seeds = [b"co_signer", contract.key().as_ref()], bump)]
+    pub co_signer_policy: Option<Account<'info, CoSignerPolicy>>,
+    pub co_signer: Option<Signer<'info>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(evidence_index: u8)]
+pub struct SubmitDisputeEvidence<'info> {
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(mut, has_one = contract, seeds = [b"dispute", contract.key().as_ref()], bump)]
+    pub dispute: Account<'info, Dispute>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DisputeEvidence::INIT_SPACE,
+        seeds = [b"evidence", dispute.key().as_ref(), submitter.key().as_ref(), &[evidence_index]],
+        bump
+    )]
+    pub evidence: Account<'info, DisputeEvidence>,
+    // Client or freelancer of the contract
+    pub submitter: Signer<'info>,
+    // Pays the rent; the submitter or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileEscrow<'info> {
+    #[account(mut)]
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(
+        seeds = [b"escrow", contract.escrow_seed_bytes()],
+        bump
     )]
     pub escrow_account: SystemAccount<'info>,
+    // Only the escrow of the contract's own mint; any other token account would read as empty
+    #[account(
+        mut,
+        associated_token::mint = contract.mint,
+        associated_token::authority = escrow_account
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = client_token_account.owner == contract.client,
+        constraint = client_token_account.mint == contract.mint @ ErrorCode::MintMismatch
+    )]
+    pub client_token_account: Account<'info, TokenAccount>,
+    /// CHECK: payment_auth blocklist entry of the contract client; must not exist
+    #[account(seeds = [b"sanctioned", contract.client.as_ref()], bump, seeds::program = payment_auth::ID)]
+    pub client_sanction: UncheckedAccount<'info>,
+    // Permissionless; the caller only pays the transaction fee
+    pub caller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateContract<'info> {
+    /// CHECK: legacy layout is validated by discriminator and size in `migrate_account`
+    #[account(mut, owner = crate::ID)]
+    pub contract: UncheckedAccount<'info>,
+    /// CHECK: created by the instruction only when the legacy contract was disputed
+    #[account(
+        mut,
+        seeds = [b"dispute", contract.key().as_ref()],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+    /// CHECK: receives the rent freed by the smaller layout; checked against the contract
+    #[account(mut)]
+    pub freelancer: UncheckedAccount<'info>,
+    // Escrow mint; only recorded when the client migrates, otherwise their next deposit sets it
+    pub mint: Option<Account<'info, Mint>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMilestone<'info> {
+    /// CHECK: legacy layout is validated by discriminator and size in `migrate_account`
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+    // Parent contract, already in the current layout; sizes the milestone's payment
+    pub contract: Account<'info, FreelanceContract>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EscrowConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, EscrowConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    // Only whoever can upgrade the program may name the guardian
     #[account(
-        init_if_needed,
-        payer = client,
-        associated_token::mint = usdc_mint,
-        associated_token::authority = escrow_account
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::NotUpgradeAuthority
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    /// CHECK: USDC mint address
-    pub usdc_mint: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+    pub program_data: Account<'info, ProgramData>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(milestone_index: u8)]
-pub struct SubmitMilestone<'info> {
-    #[account(mut)]
-    pub contract: Account<'info, FreelanceContract>,
+pub struct SetPaused<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = guardian @ ErrorCode::NotGuardian)]
+    pub config: Account<'info, EscrowConfig>,
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    pub config: Account<'info, EscrowConfig>,
     #[account(
         init,
-        payer = freelancer,
-        space = 8 + Milestone::INIT_SPACE,
-        seeds = [b"milestone", contract.key().as_ref(), &[milestone_index]],
+        payer = payer,
+        space = 8 + PendingConfigChange::INIT_SPACE,
+        seeds = [b"config_change", config.change_count.to_le_bytes().as_ref()],
         bump
     )]
-    pub milestone: Account<'info, Milestone>,
+    pub pending_change: Account<'info, PendingConfigChange>,
+    pub admin: Signer<'info>,
+    // Pays the rent and gets it back when the change is executed or cancelled
     #[account(mut)]
-    pub freelancer: Signer<'info>,
+    pub payer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(milestone_index: u8)]
-pub struct ApproveMilestone<'info> {
-    #[account(mut)]
-    pub contract: Account<'info, FreelanceContract>,
-    #[account(mut)]
-    pub milestone: Account<'info, Milestone>,
-    #[account(mut)]
-    pub client: Signer<'info>,
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
     #[account(
         mut,
-        seeds = [b"escrow", contract.contract_id.as_bytes()],
-        bump
+        seeds = [b"config_change", pending_change.change_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
     )]
-    pub escrow_account: SystemAccount<'info>,
+    pub pending_change: Account<'info, PendingConfigChange>,
+    /// CHECK: receives the pending change's rent; checked by `has_one`
     #[account(mut)]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub freelancer_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub rent_payer: UncheckedAccount<'info>,
+    // Permissionless; the caller only pays the transaction fee
+    pub caller: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct DisputeContract<'info> {
-    #[account(mut)]
-    pub contract: Account<'info, FreelanceContract>,
-    #[account(mut)]
-    pub client: Signer<'info>,
+pub struct CancelConfigChange<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, EscrowConfig>,
     #[account(
         mut,
-        seeds = [b"escrow", contract.contract_id.as_bytes()],
-        bump
+        seeds = [b"config_change", pending_change.change_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
     )]
-    pub escrow_account: SystemAccount<'info>,
+    pub pending_change: Account<'info, PendingConfigChange>,
+    /// CHECK: receives the pending change's rent; checked by `has_one`
     #[account(mut)]
-    pub escrow_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub client_token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub rent_payer: UncheckedAccount<'info>,
+    pub canceller: Signer<'info>,
 }
 
 // Account structures
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowConfig {
+    pub version: u8,
+    // Key allowed to pause and resume the program
+    pub guardian: Pubkey,
+    pub is_paused: bool,
+    pub paused_at: i64,
+    // Key allowed to queue config changes
+    pub admin: Pubkey,
+    // Seconds between queueing a change and when it can be executed
+    pub timelock_delay: i64,
+    // Id of the next queued change
+    pub change_count: u64,
+    // Contracts above this total need KYC attestations; 0 disables the check
+    pub kyc_threshold: u64,
+    // Minimum payment_auth attestation level accepted above the threshold
+    pub kyc_min_level: u8,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 7],
+}
+
+impl EscrowConfig {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfigChange {
+    pub version: u8,
+    pub change_id: u64,
+    pub change: ConfigChange,
+    // Admin who queued the change
+    pub proposer: Pubkey,
+    // Paid the rent; gets it back when the change is executed or cancelled
+    pub rent_payer: Pubkey,
+    pub queued_at: i64,
+    // Earliest time the change can be executed
+    pub eta: i64,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl PendingConfigChange {
+    pub const VERSION: u8 = 1;
+}
+
+// Config parameters that can only change through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    Guardian(Pubkey),
+    Admin(Pubkey),
+    TimelockDelay(i64),
+    KycRequirement { threshold: u64, min_level: u8 },
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct FreelanceContract {
+    pub version: u8,
+    // Backend UUID as 32 lowercase hex digits; seeds the PDA together with both participants
+    pub contract_id: [u8; 32],
+    pub freelancer: Pubkey,
+    pub client: Pubkey,
+    // Seeds the escrow authority: the contract address, or the bare id for legacy contracts
+    pub escrow_seed: [u8; 32],
+    pub contract_type: ContractType,
+    pub total_amount: u64,
+    pub milestone_count: u8,
+    pub completed_milestones: u8,
+    pub amount_released: u64,
+    pub escrow_balance: u64,
+    pub bonus_paid: u64,
+    pub stream_start: i64,
+    pub stream_end: i64,
+    pub hourly_rate: u64,
+    pub weekly_hour_cap: u16,
+    pub has_payee_split: bool,
+    pub has_approver_set: bool,
+    pub is_active: bool,
+    pub is_completed: bool,
+    pub is_disputed: bool,
+    pub created_at: i64,
+    pub completed_at: i64,
+    pub has_co_signer: bool,
+    // Nonce the next signed approval must carry; bumped on every signed release
+    pub approval_nonce: u64,
+    // Set by cancel_stream; the truncated total is all the freelancer is still owed
+    pub is_cancelled: bool,
+    // Length of a legacy id shorter than 32 bytes held in escrow_seed; 0 when all 32 bytes are the seed
+    pub escrow_seed_len: u8,
+    // Token the escrow holds; unset on a migrated contract until the client vouches for it
+    pub mint: Pubkey,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 16],
+}
+
+impl FreelanceContract {
+    pub const VERSION: u8 = 1;
+
+    // Contract ids are validated as hex on creation, so they always read back as text
+    pub fn contract_id_str(&self) -> &str {
+        std::str::from_utf8(&self.contract_id).unwrap_or_default()
+    }
+
+    // Records `mint` as the escrow mint of a migrated contract the client had not vouched for yet,
+    // otherwise requires it to be the recorded one
+    pub fn pin_mint(&mut self, mint: Pubkey) -> Result<()> {
+        if self.mint == Pubkey::default() {
+            self.mint = mint;
+        }
+        require_keys_eq!(self.mint, mint, ErrorCode::MintMismatch);
+        Ok(())
+    }
+
+    // Seed of the escrow authority, without the padding of a short legacy id
+    pub fn escrow_seed_bytes(&self) -> &[u8] {
+        match self.escrow_seed_len {
+            0 => &self.escrow_seed,
+            len => &self.escrow_seed[..len as usize],
+        }
+    }
+
+    // Id a legacy contract is known by once migrated: its own id when that is 32 bytes long, otherwise
+    // the first half of the id's SHA-256 as lowercase hex, like the ids of new contracts
+    pub fn migrated_contract_id(legacy_id: &str) -> [u8; 32] {
+        // The contract's payment authorization migrates to the same id, so the two stay linked
+        PaymentAuthorization::migrated_contract_id(legacy_id)
+    }
+
+    // Portion of a streaming contract's total that has vested at `now`
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now <= self.stream_start {
+            return 0;
+        }
+        if now >= self.stream_end {
+            return self.total_amount;
+        }
+        let elapsed = (now - self.stream_start) as u128;
+        let duration = (self.stream_end - self.stream_start) as u128;
+        (self.total_amount as u128 * elapsed / duration) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ContractType {
+    Milestone,
+    Streaming,
+    Hourly,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Milestone {
+    pub version: u8,
+    pub contract: Pubkey,
+    pub index: u8,
+    pub submitted_at: i64,
+    pub approved_at: i64,
+    #[max_len(200)]
+    pub proof_uri: String,
+    pub is_approved: bool,
+    pub payment_amount: u64,
+    pub amount_paid: u64,
+    // Submitted with a `Deliverables` account
+    pub has_deliverables: bool,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 31],
+}
+
+impl Milestone {
+    pub const VERSION: u8 = 1;
+}
+
+// Files a milestone was delivered as, each pinned by its SHA-256 hash
+#[account]
+#[derive(InitSpace)]
+pub struct Deliverables {
+    pub version: u8,
+    pub milestone: Pubkey,
+    #[max_len(MAX_DELIVERABLES)]
+    pub items: Vec<Deliverable>,
+    pub submitted_at: i64,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl Deliverables {
+    pub const VERSION: u8 = 1;
+
+    // The list never changes after submission, so the account is sized for exactly these items
+    pub fn space_for(items: &[Deliverable]) -> usize {
+        let items_len: usize = items
+            .iter()
+            .map(|item| 4 + item.uri.len() + 32 + 4 + item.mime_type.len())
+            .sum();
+        Self::INIT_SPACE - MAX_DELIVERABLES * Deliverable::INIT_SPACE + items_len
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Deliverable {
+    #[max_len(200)]
+    pub uri: String,
+    pub sha256: [u8; 32],
+    // MIME type of the file, e.g. `application/pdf`
+    #[max_len(64)]
+    pub mime_type: String,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Timesheet {
+    pub version: u8,
+    pub contract: Pubkey,
+    pub week_index: u32,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub hours: u16,
+    pub description_hash: [u8; 32],
+    pub amount: u64,
+    pub status: TimesheetStatus,
+    pub submitted_at: i64,
+    pub resolved_at: i64,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl Timesheet {
+    pub const VERSION: u8 = 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TimesheetStatus {
+    Submitted,
+    Approved,
+    Disputed,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PayeeSplit {
+    pub version: u8,
+    pub contract: Pubkey,
+    #[max_len(10)]
+    pub payees: Vec<Payee>,
+    pub updated_at: i64,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl PayeeSplit {
+    pub const VERSION: u8 = 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct Payee {
+    pub wallet: Pubkey,
+    pub share_bps: u16,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ApproverSet {
+    pub version: u8,
+    pub contract: Pubkey,
+    #[max_len(10)]
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl ApproverSet {
+    pub const VERSION: u8 = 1;
+}
+
+// Second key the client requires on large approvals
+#[account]
+#[derive(InitSpace)]
+pub struct CoSignerPolicy {
+    pub version: u8,
+    pub contract: Pubkey,
+    pub co_signer: Pubkey,
+    // Releases above this amount need the co-signer's signature
+    pub threshold: u64,
+    pub updated_at: i64,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl CoSignerPolicy {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MilestoneApproval {
+    pub version: u8,
+    pub milestone: Pubkey,
+    pub round: u32,
+    pub approval_bps: u16,
+    #[max_len(10)]
+    pub approvals: Vec<Pubkey>,
+    pub executed: bool,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl MilestoneApproval {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    pub version: u8,
+    pub contract: Pubkey,
+    #[max_len(500)]
+    pub reason: String,
+    pub opened_at: i64,
+    // Evidence accounts each party has filed
+    pub client_evidence_count: u8,
+    pub freelancer_evidence_count: u8,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 30],
+}
+
+impl Dispute {
+    pub const VERSION: u8 = 1;
+}
+
+// One piece of dispute evidence: a hash of the material and where to find it
+#[account]
+#[derive(InitSpace)]
+pub struct DisputeEvidence {
+    pub version: u8,
+    pub dispute: Pubkey,
+    pub submitter: Pubkey,
+    // Position among the submitter's evidence for this dispute
+    pub index: u8,
+    pub hash: [u8; 32],
+    #[max_len(MAX_EVIDENCE_URI_LEN)]
+    pub uri: String,
+    pub submitted_at: i64,
+    // Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl DisputeEvidence {
+    pub const VERSION: u8 = 1;
+}
+
+// Layouts from before accounts carried a version, read only by the migrate_* instructions
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct FreelanceContractV0 {
     #[max_len(64)]
     pub contract_id: String,
     pub freelancer: Pubkey,
@@ -319,9 +2965,8 @@ pub struct FreelanceContract {
     pub completed_at: i64,
 }
 
-#[account]
-#[derive(InitSpace)]
-pub struct Milestone {
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct MilestoneV0 {
     pub contract: Pubkey,
     pub index: u8,
     pub submitted_at: i64,
@@ -333,35 +2978,173 @@ pub struct Milestone {
 }
 
 // Events for monitoring
+#[event]
+pub struct PayeeSplitUpdated {
+    pub contract_id: [u8; 32],
+    pub payees: Vec<Payee>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CoSignerUpdated {
+    pub contract_id: [u8; 32],
+    pub co_signer: Pubkey,
+    pub threshold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApproverSetCreated {
+    pub contract_id: [u8; 32],
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneApprovalSigned {
+    pub contract_id: [u8; 32],
+    pub milestone_index: u8,
+    pub approver: Pubkey,
+    pub approval_bps: u16,
+    pub signatures: u8,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct FundsDeposited {
-    pub contract_id: String,
+    pub contract_id: [u8; 32],
     pub amount: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct MilestoneSubmitted {
-    pub contract_id: String,
+    pub contract_id: [u8; 32],
     pub milestone_index: u8,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct MilestoneApproved {
-    pub contract_id: String,
+    pub contract_id: [u8; 32],
     pub milestone_index: u8,
     pub amount: u64,
+    pub approval_bps: u16,
+    pub total_paid: u64,
+    pub is_fully_approved: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BonusPaid {
+    pub contract_id: [u8; 32],
+    pub amount: u64,
+    pub from_escrow: bool,
+    pub total_bonus_paid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedWithdrawn {
+    pub contract_id: [u8; 32],
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreamCancelled {
+    pub contract_id: [u8; 32],
+    pub refunded: u64,
+    pub vested: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimesheetSubmitted {
+    pub contract_id: [u8; 32],
+    pub week_index: u32,
+    pub hours: u16,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimesheetApproved {
+    pub contract_id: [u8; 32],
+    pub week_index: u32,
+    pub hours: u16,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TimesheetDisputed {
+    pub contract_id: [u8; 32],
+    pub week_index: u32,
+    pub hours: u16,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct ContractDisputed {
-    pub contract_id: String,
+    pub contract_id: [u8; 32],
     pub reason: String,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct EvidenceSubmitted {
+    pub contract_id: [u8; 32],
+    pub submitter: Pubkey,
+    pub evidence_index: u8,
+    pub hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowReconciled {
+    pub contract_id: [u8; 32],
+    pub recorded_balance: u64,
+    pub actual_balance: u64,
+    pub delta: i64,
+    pub surplus_refunded: u64,
+    pub leftover_refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub guardian: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub canceller: Pubkey,
+    pub timestamp: i64,
+}
+
 // Custom error codes
 #[error_code]
 pub enum ErrorCode {
@@ -373,4 +3156,112 @@ pub enum ErrorCode {
     MilestoneAlreadyApproved,
     #[msg("Invalid contract state for this operation")]
     InvalidContractState,
+    #[msg("Approval basis points must be between 1 and 10000")]
+    InvalidApprovalBps,
+    #[msg("Approval exceeds the unpaid remainder of the milestone")]
+    ApprovalExceedsRemaining,
+    #[msg("Insufficient escrow balance")]
+    InsufficientEscrowBalance,
+    #[msg("Bonus amount must be greater than zero")]
+    InvalidBonusAmount,
+    #[msg("Stream end must be after stream start")]
+    InvalidStreamSchedule,
+    #[msg("Contract is not a streaming contract")]
+    NotStreamingContract,
+    #[msg("Nothing has vested since the last withdrawal")]
+    NothingToWithdraw,
+    #[msg("Hourly rate and weekly hour cap must be greater than zero")]
+    InvalidHourlyTerms,
+    #[msg("Contract is not an hourly contract")]
+    NotHourlyContract,
+    #[msg("Timesheet hours must be greater than zero")]
+    InvalidTimesheetHours,
+    #[msg("Timesheet hours exceed the weekly cap")]
+    ExceedsWeeklyCap,
+    #[msg("Timesheet period has not started yet")]
+    InvalidTimesheetPeriod,
+    #[msg("Timesheet has already been approved or disputed")]
+    TimesheetNotPending,
+    #[msg("Payment would exceed the contract budget")]
+    ExceedsContractBudget,
+    #[msg("Payee shares must be unique, non-zero and sum to 10000 basis points")]
+    InvalidPayeeSplit,
+    #[msg("Contract pays a payee split but no split account was provided")]
+    PayeeSplitRequired,
+    #[msg("Payee token accounts do not match the payee split")]
+    PayeeAccountsMismatch,
+    #[msg("Approver set needs unique members and a threshold between 1 and the member count")]
+    InvalidApproverSet,
+    #[msg("Signer is not a member of the approver set")]
+    NotAnApprover,
+    #[msg("Approver has already signed this proposal")]
+    AlreadySigned,
+    #[msg("Milestone approval proposal has already been executed")]
+    ApprovalAlreadyExecuted,
+    #[msg("Contract requires approver set sign-off but the approval accounts were not provided")]
+    ApproverSetRequired,
+    #[msg("Approval does not match the signed proposal")]
+    ApprovalMismatch,
+    #[msg("Milestone approval has not reached the approver threshold")]
+    ApprovalThresholdNotMet,
+    #[msg("Signer is not the contract client")]
+    UnauthorizedClient,
+    #[msg("Payment authorization does not belong to this contract")]
+    AuthorizationMismatch,
+    #[msg("Escrow already covers the active milestone")]
+    EscrowAlreadyFunded,
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Account data does not match any known layout")]
+    UnknownAccountLayout,
+    #[msg("Contract id must be 32 lowercase hex digits")]
+    InvalidContractId,
+    #[msg("Escrow program is paused")]
+    ProgramPaused,
+    #[msg("Signer is not the pause guardian")]
+    NotGuardian,
+    #[msg("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority,
+    #[msg("Signer is not the config admin")]
+    NotConfigAdmin,
+    #[msg("Timelock delay must be between one and 30 days")]
+    InvalidTimelockDelay,
+    #[msg("Config change is still in its timelock window")]
+    TimelockNotElapsed,
+    #[msg("A party to this payment is on the sanctions blocklist")]
+    SanctionedParty,
+    #[msg("Contract is above the KYC threshold and a participant has no valid attestation")]
+    KycRequired,
+    #[msg("Co-signer must be a different key than the client")]
+    InvalidCoSigner,
+    #[msg("Signer is not the registered co-signer")]
+    UnauthorizedCoSigner,
+    #[msg("Release is above the co-signer threshold and the co-signer did not sign")]
+    CoSignerRequired,
+    #[msg("Signed approval has no matching Ed25519 signature by the client")]
+    InvalidApprovalSignature,
+    #[msg("Signed approval has expired")]
+    ApprovalSignatureExpired,
+    #[msg("Signed approval nonce does not match the contract's next nonce")]
+    InvalidApprovalNonce,
+    #[msg("Contracts with an approver set cannot take signed approvals")]
+    SignedApprovalUnsupported,
+    #[msg("Milestone batch must list 1 to 10 unique milestones of this contract, each with its account")]
+    InvalidMilestoneBatch,
+    #[msg("Contracts with an approver set cannot approve milestones in a batch")]
+    BatchApprovalUnsupported,
+    #[msg("Deliverables must list 1 to 8 items, each with a URI, a MIME type and a SHA-256 hash")]
+    InvalidDeliverables,
+    #[msg("Signer is neither the client nor the freelancer of the contract")]
+    NotADisputeParty,
+    #[msg("Evidence window of the dispute has closed")]
+    EvidenceWindowClosed,
+    #[msg("Party has already filed the maximum number of evidence items")]
+    EvidenceLimitReached,
+    #[msg("Evidence needs a URI of at most 200 bytes, a SHA-256 hash and the party's next evidence index")]
+    InvalidEvidence,
+    #[msg("Only the approver who proposed a pending round can revise it")]
+    NotTheProposer,
+    #[msg("Token account is not of the contract's escrow mint")]
+    MintMismatch,
 }
\ No newline at end of file
@@ -0,0 +1,118 @@
+//! `approve_milestones` pays several milestones in full with one transfer, and any bad
+//! milestone in the batch leaves every milestone unpaid.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{ErrorCode, FreelanceContract, Milestone};
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn batch_pays_every_listed_milestone_in_full() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("batch", 900, 3).await;
+    for index in 0..3 {
+        env.send(&[fixture.submit_ix(index, "ipfs://proof")], &[&fixture.freelancer])
+            .await
+            .unwrap();
+    }
+    // A partly paid milestone is settled for what it still owes
+    env.send(&[fixture.approve_partial_ix(1, 5_000)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    env.send(
+        &[fixture.approve_batch_ix(vec![2, 0, 1], &ReleaseOptions::default())],
+        &[&fixture.client],
+    )
+    .await
+    .unwrap();
+
+    for index in 0..3 {
+        let milestone: Milestone = env.account(&fixture.milestone(index)).await;
+        assert!(milestone.is_approved);
+        assert_eq!(milestone.amount_paid, 300);
+    }
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(contract.completed_milestones, 3);
+    assert_eq!(contract.amount_released, 900);
+    assert_eq!(contract.escrow_balance, 0);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 900);
+}
+
+#[tokio::test]
+async fn one_bad_milestone_fails_the_whole_batch() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("batch-atomic", 900, 3).await;
+    for index in 0..2 {
+        env.send(&[fixture.submit_ix(index, "ipfs://proof")], &[&fixture.freelancer])
+            .await
+            .unwrap();
+    }
+    env.send(&[fixture.approve_ix(1)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let result = env
+        .send(
+            &[fixture.approve_batch_ix(vec![0, 1], &ReleaseOptions::default())],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::MilestoneAlreadyApproved);
+
+    // Milestone 2 was never submitted, so it has no account yet
+    let result = env
+        .send(
+            &[fixture.approve_batch_ix(vec![0, 2], &ReleaseOptions::default())],
+            &[&fixture.client],
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result = env
+        .send(
+            &[fixture.approve_batch_ix(vec![0, 0], &ReleaseOptions::default())],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidMilestoneBatch);
+
+    // Each index has to come with its own milestone's account
+    let mismatched = replace_account(
+        fixture.approve_batch_ix(vec![2], &ReleaseOptions::default()),
+        fixture.milestone(2),
+        fixture.milestone(0),
+    );
+    let result = env.send(&[mismatched], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::InvalidMilestoneBatch);
+
+    let milestone: Milestone = env.account(&fixture.milestone(0)).await;
+    assert!(!milestone.is_approved);
+    assert_eq!(milestone.amount_paid, 0);
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.amount_released, 300);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 300);
+}
+
+#[tokio::test]
+async fn approver_set_contracts_cannot_batch() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("batch-approvers", 1_000, 1).await;
+    let approver = env.funded_wallet().await;
+    env.send(&[fixture.set_approver_set_ix(vec![approver.pubkey()], 1)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let result = env
+        .send(
+            &[fixture.approve_batch_ix(vec![0], &ReleaseOptions::default())],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::BatchApprovalUnsupported);
+}
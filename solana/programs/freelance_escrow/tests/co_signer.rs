@@ -0,0 +1,340 @@
+//! Releases, refunds, escrow bonuses and timesheet payments above a client's co-signer threshold
+//! need the co-signer's signature as well, and the co-signer and threshold change only with both keys.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{CoSignerPolicy, ErrorCode, FreelanceContract};
+use payment_auth::PaymentAuthorization;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn approvals_above_the_threshold_need_the_co_signer() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("co-signed", 1_000, 2).await;
+    let co_signer = Keypair::new();
+    env.send(&[fixture.set_co_signer_ix(co_signer.pubkey(), 300)], &[&fixture.client, &co_signer])
+        .await
+        .unwrap();
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.has_co_signer);
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    // The policy has to come along even for small releases
+    let result = env.send(&[fixture.approve_partial_ix(0, 2_000)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+
+    let policy_only = ReleaseOptions {
+        co_signer_policy: true,
+        ..ReleaseOptions::default()
+    };
+    env.send(
+        &[fixture.approve_with_ix(fixture.client.pubkey(), 0, Some(2_000), &policy_only)],
+        &[&fixture.client],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 100);
+
+    // The remaining 400 is above the threshold
+    let result = env
+        .send(&[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &policy_only)], &[&fixture.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+
+    let stranger = Keypair::new();
+    let wrong_signer = ReleaseOptions {
+        co_signer: Some(stranger.pubkey()),
+        ..policy_only
+    };
+    let result = env
+        .send(
+            &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &wrong_signer)],
+            &[&fixture.client, &stranger],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+
+    let co_signed = ReleaseOptions {
+        co_signer_policy: true,
+        co_signer: Some(co_signer.pubkey()),
+        ..ReleaseOptions::default()
+    };
+    env.send(
+        &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &co_signed)],
+        &[&fixture.client, &co_signer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn partial_releases_count_towards_the_threshold() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("co-signed-split", 1_000, 2).await;
+    let co_signer = Keypair::new();
+    env.send(&[fixture.set_co_signer_ix(co_signer.pubkey(), 300)], &[&fixture.client, &co_signer])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let policy_only = ReleaseOptions {
+        co_signer_policy: true,
+        ..ReleaseOptions::default()
+    };
+
+    // 100 and then 200 of the 500 milestone bring it to the threshold without passing it
+    for approval_bps in [2_000, 4_000] {
+        env.send(
+            &[fixture.approve_with_ix(fixture.client.pubkey(), 0, Some(approval_bps), &policy_only)],
+            &[&fixture.client],
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 300);
+
+    // Another 100 is small on its own but takes the milestone past the threshold
+    let result = env
+        .send(
+            &[fixture.approve_with_ix(fixture.client.pubkey(), 0, Some(2_000), &policy_only)],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+}
+
+#[tokio::test]
+async fn co_signer_changes_need_both_keys() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("co-signer-rotation", 1_000, 1).await;
+    let co_signer = Keypair::new();
+    let replacement = Keypair::new();
+    env.send(&[fixture.set_co_signer_ix(co_signer.pubkey(), 300)], &[&fixture.client, &co_signer])
+        .await
+        .unwrap();
+
+    let stranger = Keypair::new();
+    let result = env
+        .send(
+            &[fixture.update_co_signer_ix(stranger.pubkey(), stranger.pubkey(), 0)],
+            &[&fixture.client, &stranger],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::UnauthorizedCoSigner);
+
+    env.send(
+        &[fixture.update_co_signer_ix(co_signer.pubkey(), replacement.pubkey(), 2_000)],
+        &[&fixture.client, &co_signer],
+    )
+    .await
+    .unwrap();
+    let policy: CoSignerPolicy = env.account(&fixture.co_signer_policy()).await;
+    assert_eq!(policy.co_signer, replacement.pubkey());
+    assert_eq!(policy.threshold, 2_000);
+
+    // Below the raised threshold the client approves alone
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let release = ReleaseOptions {
+        co_signer_policy: true,
+        ..ReleaseOptions::default()
+    };
+    env.send(
+        &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &release)],
+        &[&fixture.client],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 1_000);
+}
+
+#[tokio::test]
+async fn dispute_refunds_and_escrow_bonuses_above_the_threshold_need_the_co_signer() {
+    let mut env = TestEnv::start().await;
+    let co_signer = Keypair::new();
+    let policy_only = ReleaseOptions {
+        co_signer_policy: true,
+        ..ReleaseOptions::default()
+    };
+    let co_signed = ReleaseOptions {
+        co_signer: Some(co_signer.pubkey()),
+        ..policy_only.clone()
+    };
+
+    let disputed = env.funded_contract("co-signed-dispute", 1_000, 2).await;
+    env.send(&[disputed.set_co_signer_ix(co_signer.pubkey(), 300)], &[&disputed.client, &co_signer])
+        .await
+        .unwrap();
+    let result = env.send(&[disputed.dispute_ix("refund everything")], &[&disputed.client]).await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+    let result = env
+        .send(&[disputed.dispute_with_ix("refund everything", &policy_only)], &[&disputed.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+    env.send(
+        &[disputed.dispute_with_ix("refund everything", &co_signed)],
+        &[&disputed.client, &co_signer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&disputed.client_token).await, 1_000);
+
+    // Escrow left over above the contract total can only go out as a large bonus with the co-signer
+    let fixture = env.contract("co-signed-bonus", 1_000, 1).await;
+    let top_up = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &env.mint,
+        &fixture.client_token,
+        &env.context.payer.pubkey(),
+        &[],
+        500,
+    )
+    .unwrap();
+    env.send(&[top_up], &[]).await.unwrap();
+    env.send(&[fixture.deposit_ix(1_500)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.set_co_signer_ix(co_signer.pubkey(), 300)], &[&fixture.client, &co_signer])
+        .await
+        .unwrap();
+
+    let result = env.send(&[fixture.pay_bonus_ix(400, true, &policy_only)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+    env.send(&[fixture.pay_bonus_ix(100, true, &policy_only)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.pay_bonus_ix(400, true, &co_signed)], &[&fixture.client, &co_signer])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn stream_refunds_and_timesheet_approvals_above_the_threshold_need_the_co_signer() {
+    let mut env = TestEnv::start().await;
+    let co_signer = Keypair::new();
+    let policy_only = ReleaseOptions {
+        co_signer_policy: true,
+        ..ReleaseOptions::default()
+    };
+    let co_signed = ReleaseOptions {
+        co_signer: Some(co_signer.pubkey()),
+        ..policy_only.clone()
+    };
+
+    let stream = env.parties("co-signed-stream", 1_000).await;
+    let start = env.now().await;
+    env.send(&[stream.create_streaming_ix(1_000, start, start + 1_000)], &[&stream.freelancer])
+        .await
+        .unwrap();
+    env.send(&[stream.deposit_ix(1_000)], &[&stream.client])
+        .await
+        .unwrap();
+    env.send(&[stream.set_co_signer_ix(co_signer.pubkey(), 300)], &[&stream.client, &co_signer])
+        .await
+        .unwrap();
+    // Cancelling halfway refunds the unvested 500
+    env.set_time(start + 500);
+    let result = env.send(&[stream.cancel_stream_with_ix(&policy_only)], &[&stream.client]).await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+    env.send(&[stream.cancel_stream_with_ix(&co_signed)], &[&stream.client, &co_signer])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&stream.client_token).await, 500);
+
+    let hourly = env.parties("co-signed-hourly", 2_000).await;
+    env.send(&[hourly.create_hourly_ix(2_000, 25, 40)], &[&hourly.freelancer])
+        .await
+        .unwrap();
+    env.send(&[hourly.deposit_ix(2_000)], &[&hourly.client])
+        .await
+        .unwrap();
+    env.send(&[hourly.set_co_signer_ix(co_signer.pubkey(), 300)], &[&hourly.client, &co_signer])
+        .await
+        .unwrap();
+    env.send(&[hourly.submit_timesheet_ix(0, 40)], &[&hourly.freelancer])
+        .await
+        .unwrap();
+    let result = env.send(&[hourly.approve_timesheet_with_ix(0, &policy_only)], &[&hourly.client]).await;
+    assert_escrow_error(result, ErrorCode::CoSignerRequired);
+    env.send(&[hourly.approve_timesheet_with_ix(0, &co_signed)], &[&hourly.client, &co_signer])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&hourly.freelancer_token).await, 1_000);
+}
+
+#[tokio::test]
+async fn authorization_payments_above_the_threshold_need_the_co_signer() {
+    let mut env = TestEnv::start().await;
+    let auth = env.authorization("co-signed-auth", 1_000, 500, 1_000).await;
+    let co_signer = Keypair::new();
+    env.send(&[auth.set_co_signer_ix(co_signer.pubkey(), 200)], &[&auth.client, &co_signer])
+        .await
+        .unwrap();
+
+    let result = env.send(&[auth.set_co_signer_ix(co_signer.pubkey(), 0)], &[&auth.client, &co_signer]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::CoSignerAlreadySet);
+
+    env.send(&[auth.process_payment_ix("m-1", 200)], &[&auth.client])
+        .await
+        .unwrap();
+    let result = env.send(&[auth.process_payment_ix("m-2", 300)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::CoSignerRequired);
+    env.send(
+        &[auth.process_payment_co_signed_ix("m-2", 300, co_signer.pubkey())],
+        &[&auth.client, &co_signer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&auth.freelancer_token).await, 500);
+
+    let stranger = Keypair::new();
+    let result = env
+        .send(
+            &[auth.update_co_signer_ix(stranger.pubkey(), stranger.pubkey(), 1_000)],
+            &[&auth.client, &stranger],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::UnauthorizedCoSigner);
+
+    env.send(
+        &[auth.update_co_signer_ix(co_signer.pubkey(), co_signer.pubkey(), 1_000)],
+        &[&auth.client, &co_signer],
+    )
+    .await
+    .unwrap();
+    let authorization: PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(authorization.co_signer, co_signer.pubkey());
+    assert_eq!(authorization.co_sign_threshold, 1_000);
+}
+
+#[tokio::test]
+async fn authorization_payments_count_towards_the_threshold() {
+    let mut env = TestEnv::start().await;
+    let auth = env.authorization("co-signed-auth-split", 1_000, 500, 1_000).await;
+    let co_signer = Keypair::new();
+    env.send(&[auth.set_co_signer_ix(co_signer.pubkey(), 200)], &[&auth.client, &co_signer])
+        .await
+        .unwrap();
+
+    env.send(&[auth.process_payment_ix("m-1", 150)], &[&auth.client])
+        .await
+        .unwrap();
+    // Below the threshold on its own, but not together with what was already paid
+    let result = env.send(&[auth.process_payment_ix("m-2", 100)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::CoSignerRequired);
+    env.send(
+        &[auth.process_payment_co_signed_ix("m-2", 100, co_signer.pubkey())],
+        &[&auth.client, &co_signer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&auth.freelancer_token).await, 250);
+}
@@ -0,0 +1,98 @@
+//! Milestones submitted as a list of content-hashed files keep that list fixed, so what
+//! was delivered can be checked against the hashes later.
+
+mod fixtures;
+
+use anchor_lang::Space;
+use fixtures::*;
+use freelance_escrow::{Deliverable, Deliverables, ErrorCode, Milestone, MAX_DELIVERABLES};
+use solana_sdk::signer::Signer;
+
+fn item(uri: &str, content: &[u8], mime_type: &str) -> Deliverable {
+    Deliverable {
+        uri: uri.to_string(),
+        sha256: solana_sdk::hash::hashv(&[content]).to_bytes(),
+        mime_type: mime_type.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn deliverables_are_recorded_once_and_the_milestone_pays_as_usual() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("deliverables", 1_000, 2).await;
+    let items = vec![
+        item("ipfs://report", b"report", "application/pdf"),
+        item("ipfs://source", b"source", "application/zip"),
+    ];
+
+    env.send(&[fixture.submit_deliverables_ix(0, items.clone())], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let milestone: Milestone = env.account(&fixture.milestone(0)).await;
+    assert!(milestone.has_deliverables);
+    assert_eq!(milestone.proof_uri, "ipfs://report");
+    let deliverables: Deliverables = env.account(&fixture.deliverables(0)).await;
+    assert_eq!(deliverables.milestone, fixture.milestone(0));
+    assert!(deliverables.items == items);
+    assert_eq!(deliverables.submitted_at, milestone.submitted_at);
+    // Rent is paid for the submitted items only, not for a full list of maximum-length ones
+    let account = env.context.banks_client.get_account(fixture.deliverables(0)).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + Deliverables::space_for(&items));
+    assert!(account.data.len() < 8 + Deliverables::INIT_SPACE);
+
+    // Neither path can submit the milestone a second time
+    let swapped = vec![item("ipfs://report", b"other report", "application/pdf")];
+    let result = env
+        .send(&[fixture.submit_deliverables_ix(0, swapped)], &[&fixture.freelancer])
+        .await;
+    assert!(result.is_err());
+    let result = env.send(&[fixture.submit_ix(0, "ipfs://other")], &[&fixture.freelancer]).await;
+    assert!(result.is_err());
+    let deliverables: Deliverables = env.account(&fixture.deliverables(0)).await;
+    assert!(deliverables.items == items);
+
+    env.send(&[fixture.approve_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn deliverables_must_be_complete_and_within_limits() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("deliverables-limits", 1_000, 1).await;
+    let valid = item("ipfs://report", b"report", "application/pdf");
+
+    let too_many = vec![valid.clone(); MAX_DELIVERABLES + 1];
+    let unhashed = Deliverable {
+        sha256: [0; 32],
+        ..valid.clone()
+    };
+    let unlabelled = Deliverable {
+        mime_type: String::new(),
+        ..valid.clone()
+    };
+    let long_uri = Deliverable {
+        uri: format!("ipfs://{}", "a".repeat(200)),
+        ..valid.clone()
+    };
+    for items in [vec![], too_many, vec![unhashed], vec![valid.clone(), unlabelled], vec![long_uri]] {
+        let result = env
+            .send(&[fixture.submit_deliverables_ix(0, items)], &[&fixture.freelancer])
+            .await;
+        assert_escrow_error(result, ErrorCode::InvalidDeliverables);
+    }
+
+    // Only the contract's freelancer can submit
+    let stranger = env.funded_wallet().await;
+    let ix = replace_account(
+        fixture.submit_deliverables_ix(0, vec![valid]),
+        fixture.freelancer.pubkey(),
+        stranger.pubkey(),
+    );
+    let result = env.send(&[ix], &[&stranger]).await;
+    assert!(result.is_err());
+    let ix = replace_account(fixture.submit_ix(0, "ipfs://proof"), fixture.freelancer.pubkey(), stranger.pubkey());
+    let result = env.send(&[ix], &[&stranger]).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,107 @@
+//! Both parties of a disputed contract can file hashed evidence, each up to a cap and only
+//! while the evidence window after the dispute is open.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{Dispute, DisputeEvidence, ErrorCode, EVIDENCE_WINDOW, MAX_EVIDENCE_PER_PARTY};
+use solana_sdk::signer::Signer;
+
+fn hash(content: &[u8]) -> [u8; 32] {
+    solana_sdk::hash::hashv(&[content]).to_bytes()
+}
+
+#[tokio::test]
+async fn both_parties_file_evidence_during_a_dispute() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("evidence", 1_000, 2).await;
+
+    // Nothing to file evidence against before the dispute is opened
+    let result = env
+        .send(&[fixture.submit_evidence_ix(&fixture.client, 0, hash(b"chat log"), "ipfs://chat")], &[&fixture.client])
+        .await;
+    assert!(result.is_err());
+
+    env.send(&[fixture.dispute_ix("work never delivered")], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_evidence_ix(&fixture.client, 0, hash(b"chat log"), "ipfs://chat")], &[&fixture.client])
+        .await
+        .unwrap();
+    for (index, uri) in ["ipfs://commits", "ipfs://handover"].into_iter().enumerate() {
+        env.send(
+            &[fixture.submit_evidence_ix(&fixture.freelancer, index as u8, hash(uri.as_bytes()), uri)],
+            &[&fixture.freelancer],
+        )
+        .await
+        .unwrap();
+    }
+
+    let dispute: Dispute = env.account(&fixture.dispute()).await;
+    assert_eq!(dispute.client_evidence_count, 1);
+    assert_eq!(dispute.freelancer_evidence_count, 2);
+    let evidence: DisputeEvidence = env.account(&fixture.evidence(&fixture.freelancer.pubkey(), 1)).await;
+    assert_eq!(evidence.dispute, fixture.dispute());
+    assert_eq!(evidence.submitter, fixture.freelancer.pubkey());
+    assert_eq!(evidence.index, 1);
+    assert_eq!(evidence.hash, hash(b"ipfs://handover"));
+    assert_eq!(evidence.uri, "ipfs://handover");
+    assert!(evidence.submitted_at >= dispute.opened_at);
+
+    // Indexes follow each party's own count, so filed evidence cannot be skipped or replaced
+    let result = env
+        .send(&[fixture.submit_evidence_ix(&fixture.client, 2, hash(b"late"), "ipfs://late")], &[&fixture.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidEvidence);
+    let result = env
+        .send(&[fixture.submit_evidence_ix(&fixture.client, 0, hash(b"other"), "ipfs://other")], &[&fixture.client])
+        .await;
+    assert!(result.is_err());
+    for (content, uri) in [(hash(b"blank"), ""), ([0; 32], "ipfs://unhashed")] {
+        let result = env
+            .send(&[fixture.submit_evidence_ix(&fixture.client, 1, content, uri)], &[&fixture.client])
+            .await;
+        assert_escrow_error(result, ErrorCode::InvalidEvidence);
+    }
+
+    let stranger = env.funded_wallet().await;
+    let result = env
+        .send(&[fixture.submit_evidence_ix(&stranger, 0, hash(b"rumour"), "ipfs://rumour")], &[&stranger])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotADisputeParty);
+}
+
+#[tokio::test]
+async fn evidence_is_capped_per_party_and_closes_with_the_window() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("evidence-limits", 1_000, 1).await;
+    env.send(&[fixture.dispute_ix("scope disagreement")], &[&fixture.client])
+        .await
+        .unwrap();
+
+    for index in 0..MAX_EVIDENCE_PER_PARTY as u8 {
+        env.send(
+            &[fixture.submit_evidence_ix(&fixture.client, index, hash(&[index]), "ipfs://exhibit")],
+            &[&fixture.client],
+        )
+        .await
+        .unwrap();
+    }
+    let result = env
+        .send(
+            &[fixture.submit_evidence_ix(&fixture.client, MAX_EVIDENCE_PER_PARTY as u8, hash(b"more"), "ipfs://more")],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::EvidenceLimitReached);
+
+    let dispute: Dispute = env.account(&fixture.dispute()).await;
+    env.set_time(dispute.opened_at + EVIDENCE_WINDOW + 1);
+    let result = env
+        .send(
+            &[fixture.submit_evidence_ix(&fixture.freelancer, 0, hash(b"late"), "ipfs://late")],
+            &[&fixture.freelancer],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::EvidenceWindowClosed);
+}
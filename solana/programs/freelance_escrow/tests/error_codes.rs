@@ -0,0 +1,640 @@
+//! Every custom error is reachable: each check below drives an instruction into one
+//! specific `require!` and asserts the error code that comes back.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{ErrorCode, Payee};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn milestone_errors() {
+    let mut env = TestEnv::start().await;
+
+    let unfunded = env.contract("err-unfunded", 1_000, 2).await;
+    let result = env.send(&[unfunded.submit_ix(0, "ipfs://proof")], &[&unfunded.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::ContractNotActive);
+
+    let fixture = env.funded_contract("err-milestone", 1_000, 2).await;
+    let result = env.send(&[fixture.submit_ix(2, "ipfs://proof")], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidMilestone);
+
+    // A milestone contract with no milestones could never be funded or paid out
+    let empty = env.parties("err-no-milestones", 1_000).await;
+    let result = env.send(&[empty.create_ix(1_000, 0)], &[&empty.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidMilestone);
+
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let result = env
+        .send(
+            &[fixture.approve_with_ix(fixture.freelancer.pubkey(), 0, None, &ReleaseOptions::default())],
+            &[&fixture.freelancer],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::UnauthorizedClient);
+
+    for approval_bps in [0, 10_001] {
+        let result = env.send(&[fixture.approve_partial_ix(0, approval_bps)], &[&fixture.client]).await;
+        assert_escrow_error(result, ErrorCode::InvalidApprovalBps);
+    }
+
+    env.send(&[fixture.approve_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::MilestoneAlreadyApproved);
+
+    let underfunded = env.contract("err-underfunded", 1_000, 1).await;
+    env.send(&[underfunded.deposit_ix(500)], &[&underfunded.client])
+        .await
+        .unwrap();
+    env.send(&[underfunded.submit_ix(0, "ipfs://proof")], &[&underfunded.freelancer])
+        .await
+        .unwrap();
+    let result = env.send(&[underfunded.approve_ix(0)], &[&underfunded.client]).await;
+    assert_escrow_error(result, ErrorCode::InsufficientEscrowBalance);
+
+    let result = env
+        .send(&[fixture.pay_bonus_ix(0, false, &ReleaseOptions::default())], &[&fixture.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidBonusAmount);
+}
+
+#[tokio::test]
+async fn streaming_errors() {
+    let mut env = TestEnv::start().await;
+    let now = env.now().await;
+
+    let fixture = env.parties("err-stream", 1_000).await;
+    let result = env
+        .send(&[fixture.create_streaming_ix(1_000, now, now)], &[&fixture.freelancer])
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidStreamSchedule);
+
+    let milestone_contract = env.funded_contract("err-not-stream", 1_000, 1).await;
+    let result = env
+        .send(&[milestone_contract.withdraw_vested_ix()], &[&milestone_contract.freelancer])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotStreamingContract);
+
+    let result = env
+        .send(&[milestone_contract.cancel_stream_ix()], &[&milestone_contract.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotStreamingContract);
+
+    env.send(&[fixture.create_streaming_ix(1_000, now + 100, now + 1_100)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.set_time(now);
+    let result = env.send(&[fixture.withdraw_vested_ix()], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::NothingToWithdraw);
+}
+
+#[tokio::test]
+async fn hourly_errors() {
+    let mut env = TestEnv::start().await;
+
+    let fixture = env.parties("err-hourly", 1_500).await;
+    for (hourly_rate, weekly_hour_cap) in [(0, 40), (25, 0)] {
+        let result = env
+            .send(&[fixture.create_hourly_ix(1_000, hourly_rate, weekly_hour_cap)], &[&fixture.freelancer])
+            .await;
+        assert_escrow_error(result, ErrorCode::InvalidHourlyTerms);
+    }
+
+    env.send(&[fixture.create_hourly_ix(1_000, 25, 40)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_500)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let result = env.send(&[fixture.submit_timesheet_ix(0, 0)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidTimesheetHours);
+
+    let result = env.send(&[fixture.submit_timesheet_ix(0, 41)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::ExceedsWeeklyCap);
+
+    let result = env.send(&[fixture.submit_timesheet_ix(1, 10)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidTimesheetPeriod);
+
+    env.send(&[fixture.submit_timesheet_ix(0, 40)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_timesheet_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.dispute_timesheet_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::TimesheetNotPending);
+
+    // The budget is spent even though escrow still holds the over-deposit
+    let contract: freelance_escrow::FreelanceContract = env.account(&fixture.address()).await;
+    env.set_time(contract.created_at + freelance_escrow::SECONDS_PER_WEEK);
+    env.send(&[fixture.submit_timesheet_ix(1, 10)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.approve_timesheet_ix(1)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ExceedsContractBudget);
+
+    // Hours times a huge rate would wrap around to a small amount
+    let overflow = env.parties("err-hourly-overflow", 0).await;
+    env.send(&[overflow.create_hourly_ix(1_000, u64::MAX / 2, 40)], &[&overflow.freelancer])
+        .await
+        .unwrap();
+    let result = env.send(&[overflow.submit_timesheet_ix(0, 3)], &[&overflow.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::ExceedsContractBudget);
+
+    let milestone_contract = env.funded_contract("err-not-hourly", 1_000, 1).await;
+    let result = env
+        .send(&[milestone_contract.submit_timesheet_ix(0, 10)], &[&milestone_contract.freelancer])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotHourlyContract);
+}
+
+#[tokio::test]
+async fn payee_split_errors() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.contract("err-split", 1_000, 1).await;
+    let partner = Keypair::new();
+    let payees = |freelancer_bps, partner_bps| {
+        vec![
+            Payee {
+                wallet: fixture.freelancer.pubkey(),
+                share_bps: freelancer_bps,
+            },
+            Payee {
+                wallet: partner.pubkey(),
+                share_bps: partner_bps,
+            },
+        ]
+    };
+
+    for (freelancer_bps, partner_bps) in [(5_000, 4_000), (10_000, 0)] {
+        let result = env
+            .send(&[fixture.set_payee_split_ix(payees(freelancer_bps, partner_bps))], &[&fixture.freelancer])
+            .await;
+        assert_escrow_error(result, ErrorCode::InvalidPayeeSplit);
+    }
+
+    env.send(&[fixture.set_payee_split_ix(payees(5_000, 5_000))], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    // One account short, then the right count in the wrong order
+    let partner_token = env.token_account(&partner.pubkey(), 0).await;
+    for payee_token_accounts in [
+        vec![fixture.freelancer_token],
+        vec![partner_token, fixture.freelancer_token],
+    ] {
+        let release = ReleaseOptions {
+            payee_token_accounts,
+            payee_wallets: vec![fixture.freelancer.pubkey(), partner.pubkey()],
+            ..ReleaseOptions::default()
+        };
+        let result = env
+            .send(
+                &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &release)],
+                &[&fixture.client],
+            )
+            .await;
+        assert_escrow_error(result, ErrorCode::PayeeAccountsMismatch);
+    }
+
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::PayeeSplitRequired);
+
+    // The split is locked once the contract is funded
+    let late = env.funded_contract("err-split-late", 1_000, 1).await;
+    let result = env
+        .send(
+            &[late.set_payee_split_ix(vec![Payee {
+                wallet: late.freelancer.pubkey(),
+                share_bps: 10_000,
+            }])],
+            &[&late.freelancer],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidContractState);
+}
+
+#[tokio::test]
+async fn approver_set_errors() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.contract("err-approvers", 1_000, 1).await;
+    let approvers = [
+        env.funded_wallet().await,
+        env.funded_wallet().await,
+        env.funded_wallet().await,
+    ];
+    let members: Vec<_> = approvers.iter().map(|approver| approver.pubkey()).collect();
+    let outsider = env.funded_wallet().await;
+
+    let result = env
+        .send(&[fixture.set_approver_set_ix(members[..2].to_vec(), 3)], &[&fixture.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidApproverSet);
+
+    env.send(&[fixture.set_approver_set_ix(members, 2)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ApproverSetRequired);
+    let expires_at = env.now().await + 60;
+    let result = env
+        .send(&fixture.approve_signed_ixs(outsider.pubkey(), 0, 10_000, 1_000, 0, expires_at), &[&outsider])
+        .await;
+    assert_escrow_error(result, ErrorCode::SignedApprovalUnsupported);
+
+    let result = env
+        .send(&[fixture.propose_approval_ix(outsider.pubkey(), 0, 5_000)], &[&outsider])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotAnApprover);
+
+    let [first, second, third] = &approvers;
+    env.send(&[fixture.propose_approval_ix(first.pubkey(), 0, 5_000)], &[first])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.sign_approval_ix(first.pubkey(), 0)], &[first]).await;
+    assert_escrow_error(result, ErrorCode::AlreadySigned);
+
+    env.send(&[fixture.sign_approval_ix(second.pubkey(), 0)], &[second])
+        .await
+        .unwrap();
+    // Another approver cannot throw away the signatures on a pending round
+    let result = env
+        .send(&[fixture.revise_approval_ix(second.pubkey(), 0, 10_000)], &[second])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotTheProposer);
+
+    let release = ReleaseOptions {
+        approver_set: true,
+        ..ReleaseOptions::default()
+    };
+    let result = env
+        .send(&[fixture.approve_with_ix(first.pubkey(), 0, None, &release)], &[first])
+        .await;
+    assert_escrow_error(result, ErrorCode::ApprovalMismatch);
+
+    env.send(&[fixture.approve_with_ix(first.pubkey(), 0, Some(5_000), &release)], &[first])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.sign_approval_ix(third.pubkey(), 0)], &[third]).await;
+    assert_escrow_error(result, ErrorCode::ApprovalAlreadyExecuted);
+    // Once executed, any approver can open the next round
+    env.send(&[fixture.revise_approval_ix(third.pubkey(), 0, 5_000)], &[third])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn authorization_funding_errors() {
+    let mut env = TestEnv::start().await;
+
+    let fixture = env.contract("err-funding", 1_000, 2).await;
+    env.send(&[fixture.deposit_ix(100)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    // An authorization from another client for the same contract id
+    let foreign = env.authorization("err-funding", 1_000, 500, 1_000).await;
+    let own = pda::payment_authorization(&fixture.client.pubkey(), &fixture.id.into()).0;
+    let funding = replace_account(fixture.fund_from_authorization_ix(), own, foreign.address());
+    let result = env.send(&[funding], &[]).await;
+    assert_escrow_error(result, ErrorCode::AuthorizationMismatch);
+
+    let auth = env.contract_authorization(&fixture, 500, 1_000).await;
+    let result = env.send(&[fixture.fund_from_authorization_ix()], &[]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::EscrowNotLinked);
+
+    let result = env
+        .send(
+            &[replace_account(auth.link_escrow_ix(fixture.escrow_token()), auth.client_token, foreign.client_token)],
+            &[&auth.client],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::UnauthorizedClient);
+
+    let result = env.send(&[auth.process_payment_ix("m-1", 0)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::InvalidAmount);
+
+    let result = env.send(&[auth.update_ix(Some(0), None)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::InvalidAmount);
+}
+
+#[tokio::test]
+async fn dispute_errors() {
+    let mut env = TestEnv::start().await;
+
+    let unfunded = env.contract("err-dispute", 1_000, 1).await;
+    let result = env.send(&[unfunded.dispute_ix("no work started")], &[&unfunded.client]).await;
+    assert_escrow_error(result, ErrorCode::InvalidContractState);
+
+    let completed = env.funded_contract("err-dispute-done", 1_000, 1).await;
+    env.send(&[completed.submit_ix(0, "ipfs://proof")], &[&completed.freelancer])
+        .await
+        .unwrap();
+    env.send(&[completed.approve_ix(0)], &[&completed.client])
+        .await
+        .unwrap();
+    let result = env.send(&[completed.dispute_ix("too late")], &[&completed.client]).await;
+    assert_escrow_error(result, ErrorCode::InvalidContractState);
+
+    // Only the contract's client can dispute, and only into its own token account
+    let fixture = env.funded_contract("err-dispute-parties", 1_000, 1).await;
+    let stranger = env.funded_wallet().await;
+    let result = env
+        .send(
+            &[replace_account(fixture.dispute_ix("not my contract"), fixture.client.pubkey(), stranger.pubkey())],
+            &[&stranger],
+        )
+        .await;
+    assert!(result.is_err());
+    let result = env
+        .send(
+            &[replace_account(fixture.dispute_ix("refund elsewhere"), fixture.client_token, fixture.freelancer_token)],
+            &[&fixture.client],
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 1_000);
+}
+
+#[tokio::test]
+async fn contract_id_errors() {
+    let mut env = TestEnv::start().await;
+
+    // Uppercase hex would give the same UUID a second set of addresses
+    let mut fixture = env.parties("err-contract-id", 1_000).await;
+    fixture.id = *b"9E4F2A6B1C3D4E8FA5B7C9D0E1F2A3B4";
+    let result = env.send(&[fixture.create_ix(1_000, 1)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidContractId);
+
+    let auth = AuthFixture {
+        contract_id: *b"not-a-uuid-but-thirty-two-bytes!",
+        mint: fixture.mint,
+        client: Keypair::from_bytes(&fixture.client.to_bytes()).unwrap(),
+        freelancer: fixture.freelancer.pubkey(),
+        client_token: fixture.client_token,
+        freelancer_token: fixture.freelancer_token,
+        legacy_id: None,
+    };
+    let result = env.send(&[auth.create_ix(500, 1_000)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::InvalidContractId);
+}
+
+#[tokio::test]
+async fn pause_errors() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("err-pause", 1_000, 2).await;
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let stranger = env.funded_wallet().await;
+    let result = env.send(&[escrow::set_paused(stranger.pubkey(), true)], &[&stranger]).await;
+    assert_escrow_error(result, ErrorCode::NotGuardian);
+
+    let guardian = Keypair::from_bytes(&env.guardian.to_bytes()).unwrap();
+    env.send(&[escrow::set_paused(guardian.pubkey(), true)], &[&guardian])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.deposit_ix(100)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ProgramPaused);
+    let result = env.send(&[fixture.submit_ix(1, "ipfs://proof")], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::ProgramPaused);
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ProgramPaused);
+
+    // Only the upgrade authority can set up a config, here against a cleared one
+    env.set_account_data(&pda::config().0, &anchor_lang::system_program::ID, vec![])
+        .await;
+    let result = env
+        .send(
+            &[escrow::initialize_config(stranger.pubkey(), stranger.pubkey(), stranger.pubkey(), TIMELOCK_DELAY)],
+            &[&stranger],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::NotUpgradeAuthority);
+}
+
+#[tokio::test]
+async fn governance_errors() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let stranger = env.funded_wallet().await;
+
+    let change = freelance_escrow::ConfigChange::Admin(stranger.pubkey());
+    let result = env
+        .send(&[escrow::queue_config_change(stranger.pubkey(), stranger.pubkey(), 0, change)], &[&stranger])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotConfigAdmin);
+
+    for delay in [-1, 0, freelance_escrow::MIN_TIMELOCK_DELAY - 1] {
+        let change = freelance_escrow::ConfigChange::TimelockDelay(delay);
+        let result = env
+            .send(&[escrow::queue_config_change(admin.pubkey(), admin.pubkey(), 0, change)], &[&admin])
+            .await;
+        assert_escrow_error(result, ErrorCode::InvalidTimelockDelay);
+    }
+
+    let change = freelance_escrow::ConfigChange::TimelockDelay(freelance_escrow::MIN_TIMELOCK_DELAY);
+    env.send(&[escrow::queue_config_change(admin.pubkey(), admin.pubkey(), 0, change)], &[&admin])
+        .await
+        .unwrap();
+    let result = env
+        .send(&[escrow::cancel_config_change(stranger.pubkey(), 0, admin.pubkey())], &[&stranger])
+        .await;
+    assert_escrow_error(result, ErrorCode::NotConfigAdmin);
+    let result = env
+        .send(&[escrow::execute_config_change(stranger.pubkey(), 0, admin.pubkey())], &[&stranger])
+        .await;
+    assert_escrow_error(result, ErrorCode::TimelockNotElapsed);
+
+    let change = payment_auth::ConfigChange::Admin(stranger.pubkey());
+    let result = env
+        .send(&[authorization::queue_config_change(stranger.pubkey(), stranger.pubkey(), 0, change)], &[&stranger])
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::UnauthorizedAdmin);
+
+    let change = payment_auth::ConfigChange::FreezeAdmins(vec![stranger.pubkey(); 6]);
+    let result = env
+        .send(&[authorization::queue_config_change(admin.pubkey(), admin.pubkey(), 0, change)], &[&admin])
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::TooManyFreezeAdmins);
+
+    for delay in [0, 31 * 24 * 60 * 60] {
+        let change = payment_auth::ConfigChange::TimelockDelay(delay);
+        let result = env
+            .send(&[authorization::queue_config_change(admin.pubkey(), admin.pubkey(), 0, change)], &[&admin])
+            .await;
+        assert_auth_error(result, payment_auth::ErrorCode::InvalidTimelockDelay);
+    }
+
+    let change = payment_auth::ConfigChange::TimelockDelay(payment_auth::MIN_TIMELOCK_DELAY);
+    env.send(&[authorization::queue_config_change(admin.pubkey(), admin.pubkey(), 0, change)], &[&admin])
+        .await
+        .unwrap();
+    let result = env
+        .send(&[authorization::cancel_config_change(stranger.pubkey(), 0, admin.pubkey())], &[&stranger])
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::UnauthorizedAdmin);
+
+    env.set_account_data(&pda::auth_config().0, &anchor_lang::system_program::ID, vec![])
+        .await;
+    let result = env
+        .send(
+            &[authorization::initialize_config(
+                stranger.pubkey(),
+                stranger.pubkey(),
+                vec![],
+                stranger.pubkey(),
+                TIMELOCK_DELAY,
+            )],
+            &[&stranger],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::NotUpgradeAuthority);
+}
+
+#[tokio::test]
+async fn sanctions_errors() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let stranger = env.funded_wallet().await;
+
+    let result = env
+        .send(
+            &[authorization::add_sanctioned_wallet(stranger.pubkey(), stranger.pubkey(), stranger.pubkey())],
+            &[&stranger],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::NotComplianceAuthority);
+
+    let fixture = env.contract("err-sanctioned", 1_000, 1).await;
+    let auth = env.authorization("err-sanctioned-auth", 1_000, 500, 1_000).await;
+    env.send(
+        &[
+            authorization::add_sanctioned_wallet(compliance.pubkey(), compliance.pubkey(), fixture.client.pubkey()),
+            authorization::add_sanctioned_wallet(compliance.pubkey(), compliance.pubkey(), auth.client.pubkey()),
+        ],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .send(
+            &[authorization::remove_sanctioned_wallet(stranger.pubkey(), auth.client.pubkey(), stranger.pubkey())],
+            &[&stranger],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::NotComplianceAuthority);
+
+    let result = env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+    let result = env.send(&[auth.process_payment_ix("m-1", 100)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::SanctionedParty);
+}
+
+#[tokio::test]
+async fn kyc_errors() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let stranger = env.funded_wallet().await;
+    let now = env.now().await;
+    env.set_time(now);
+
+    let result = env
+        .send(
+            &[authorization::issue_kyc_attestation(
+                stranger.pubkey(),
+                stranger.pubkey(),
+                stranger.pubkey(),
+                1,
+                now + 100,
+            )],
+            &[&stranger],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::NotComplianceAuthority);
+
+    for (level, expires_at) in [(0, now + 100), (1, now)] {
+        let result = env
+            .send(
+                &[authorization::issue_kyc_attestation(
+                    compliance.pubkey(),
+                    compliance.pubkey(),
+                    stranger.pubkey(),
+                    level,
+                    expires_at,
+                )],
+                &[&compliance],
+            )
+            .await;
+        assert_auth_error(result, payment_auth::ErrorCode::InvalidKycAttestation);
+    }
+
+    env.send(
+        &[authorization::issue_kyc_attestation(
+            compliance.pubkey(),
+            compliance.pubkey(),
+            stranger.pubkey(),
+            1,
+            now + 100,
+        )],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    let result = env
+        .send(
+            &[authorization::revoke_kyc_attestation(stranger.pubkey(), stranger.pubkey(), stranger.pubkey())],
+            &[&stranger],
+        )
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::NotComplianceAuthority);
+}
+
+#[tokio::test]
+async fn co_signer_errors() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("err-co-signer", 1_000, 1).await;
+    let auth = env.authorization("err-co-signer-auth", 1_000, 500, 1_000).await;
+
+    // The client cannot be its own second key
+    let result = env.send(&[fixture.set_co_signer_ix(fixture.client.pubkey(), 0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::InvalidCoSigner);
+    let result = env.send(&[auth.set_co_signer_ix(auth.client.pubkey(), 0)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::InvalidCoSigner);
+
+    let co_signer = Keypair::new();
+    env.send(&[fixture.set_co_signer_ix(co_signer.pubkey(), 0)], &[&fixture.client, &co_signer])
+        .await
+        .unwrap();
+    let result = env
+        .send(
+            &[fixture.update_co_signer_ix(co_signer.pubkey(), fixture.client.pubkey(), 0)],
+            &[&fixture.client, &co_signer],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidCoSigner);
+}
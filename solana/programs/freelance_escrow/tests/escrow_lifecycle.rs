@@ -0,0 +1,601 @@
+//! End-to-end flows for each contract type against a local test validator.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{
+    Dispute, ErrorCode, EscrowConfig, FreelanceContract, Milestone, MilestoneApproval, Payee,
+    Timesheet, TimesheetStatus, SECONDS_PER_WEEK,
+};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn milestone_contract_completes_after_every_milestone_is_approved() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("full-release", 900, 3).await;
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_active);
+    assert_eq!(contract.escrow_balance, 900);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 900);
+
+    for index in 0..3 {
+        env.send(&[fixture.submit_ix(index, "ipfs://proof")], &[&fixture.freelancer])
+            .await
+            .unwrap();
+        env.send(&[fixture.approve_ix(index)], &[&fixture.client])
+            .await
+            .unwrap();
+
+        let milestone: Milestone = env.account(&fixture.milestone(index)).await;
+        assert!(milestone.is_approved);
+        assert_eq!(milestone.amount_paid, 300);
+    }
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(contract.completed_milestones, 3);
+    assert_eq!(contract.amount_released, 900);
+    assert_eq!(contract.escrow_balance, 0);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 900);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 0);
+}
+
+#[tokio::test]
+async fn an_id_claimed_by_other_parties_does_not_block_the_real_contract() {
+    let mut env = TestEnv::start().await;
+    let squatter = env.contract("squatted-id", 1_000, 1).await;
+    let fixture = env.funded_contract("squatted-id", 1_000, 1).await;
+    assert_eq!(squatter.id, fixture.id);
+    assert_ne!(squatter.address(), fixture.address());
+    assert_ne!(squatter.escrow_authority(), fixture.escrow_authority());
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.freelancer, fixture.freelancer.pubkey());
+    assert_eq!(contract.escrow_seed, fixture.address().to_bytes());
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 1_000);
+}
+
+#[tokio::test]
+async fn partial_approvals_release_in_steps_and_full_approval_pays_the_remainder() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("partial-release", 1_000, 1).await;
+    env.send(&[fixture.submit_ix(0, "ipfs://draft")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    env.send(&[fixture.approve_partial_ix(0, 2_500)], &[&fixture.client])
+        .await
+        .unwrap();
+    let milestone: Milestone = env.account(&fixture.milestone(0)).await;
+    assert_eq!(milestone.amount_paid, 250);
+    assert!(!milestone.is_approved);
+
+    env.send(&[fixture.approve_partial_ix(0, 5_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 750);
+
+    // Another half would overpay the milestone
+    let result = env.send(&[fixture.approve_partial_ix(0, 5_000)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ApprovalExceedsRemaining);
+
+    env.send(&[fixture.approve_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    let milestone: Milestone = env.account(&fixture.milestone(0)).await;
+    assert!(milestone.is_approved);
+    assert_eq!(milestone.amount_paid, 1_000);
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 1_000);
+}
+
+#[tokio::test]
+async fn dispute_refunds_the_unreleased_escrow_to_the_client() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("dispute-refund", 1_000, 2).await;
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    env.send(&[fixture.dispute_ix("second milestone never delivered")], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_disputed);
+    assert_eq!(contract.escrow_balance, 0);
+    let dispute: Dispute = env.account(&fixture.dispute()).await;
+    assert_eq!(dispute.contract, fixture.address());
+    assert_eq!(dispute.reason, "second milestone never delivered");
+    assert_eq!(env.token_balance(&fixture.client_token).await, 500);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 0);
+}
+
+#[tokio::test]
+async fn pause_halts_releases_but_not_refunds() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("pause", 1_000, 2).await;
+    let disputed = env.funded_contract("pause-exit", 1_000, 2).await;
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let guardian = Keypair::from_bytes(&env.guardian.to_bytes()).unwrap();
+    env.send(&[escrow::set_paused(guardian.pubkey(), true)], &[&guardian])
+        .await
+        .unwrap();
+    let config: EscrowConfig = env.account(&pda::config().0).await;
+    assert!(config.is_paused);
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ProgramPaused);
+
+    // Clients can still pull their funds out while the program is halted
+    env.send(&[disputed.dispute_ix("exiting during pause")], &[&disputed.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&disputed.client_token).await, 1_000);
+    assert_eq!(env.token_balance(&disputed.escrow_token()).await, 0);
+
+    env.send(&[escrow::set_paused(guardian.pubkey(), false)], &[&guardian])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn pause_halts_escrow_bonuses_and_timesheet_approvals() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.parties("pause-hourly", 2_100).await;
+    env.send(&[fixture.create_hourly_ix(1_000, 25, 40)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    // Half the deposit is left over for a bonus
+    env.send(&[fixture.deposit_ix(2_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_timesheet_ix(0, 40)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let guardian = Keypair::from_bytes(&env.guardian.to_bytes()).unwrap();
+    env.send(&[escrow::set_paused(guardian.pubkey(), true)], &[&guardian])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.approve_timesheet_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::ProgramPaused);
+    let result = env
+        .send(&[fixture.pay_bonus_ix(100, true, &ReleaseOptions::default())], &[&fixture.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::ProgramPaused);
+
+    // A bonus out of the client's own wallet never touches escrow
+    env.send(&[fixture.pay_bonus_ix(100, false, &ReleaseOptions::default())], &[&fixture.client])
+        .await
+        .unwrap();
+
+    env.send(&[escrow::set_paused(guardian.pubkey(), false)], &[&guardian])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_timesheet_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.pay_bonus_ix(100, true, &ReleaseOptions::default())], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 1_200);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 900);
+}
+
+#[tokio::test]
+async fn bonus_is_paid_from_wallet_or_from_unallocated_escrow() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.contract("bonus", 1_000, 1).await;
+    // Deposit more than the contract total so there is escrow left over for a bonus
+    let top_up = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &env.mint,
+        &fixture.client_token,
+        &env.context.payer.pubkey(),
+        &[],
+        500,
+    )
+    .unwrap();
+    env.send(&[top_up], &[]).await.unwrap();
+    env.send(&[fixture.deposit_ix(1_200)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    env.send(&[fixture.pay_bonus_ix(100, false, &ReleaseOptions::default())], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.pay_bonus_ix(200, true, &ReleaseOptions::default())], &[&fixture.client])
+        .await
+        .unwrap();
+
+    // Everything else in escrow is reserved for the unpaid milestone
+    let result = env
+        .send(&[fixture.pay_bonus_ix(1, true, &ReleaseOptions::default())], &[&fixture.client])
+        .await;
+    assert_escrow_error(result, ErrorCode::InsufficientEscrowBalance);
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.bonus_paid, 300);
+    assert_eq!(contract.escrow_balance, 1_000);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 300);
+    assert_eq!(env.token_balance(&fixture.client_token).await, 200);
+}
+
+#[tokio::test]
+async fn streaming_contract_vests_linearly_and_cancel_refunds_the_unvested_part() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.parties("stream", 1_000).await;
+    let start = env.now().await;
+    env.send(&[fixture.create_streaming_ix(1_000, start, start + 1_000)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    env.set_time(start + 250);
+    env.send(&[fixture.withdraw_vested_ix()], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 250);
+
+    let result = env.send(&[fixture.withdraw_vested_ix()], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::NothingToWithdraw);
+
+    env.set_time(start + 500);
+    env.send(&[fixture.cancel_stream_ix()], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.client_token).await, 500);
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.total_amount, 500);
+    assert_eq!(contract.stream_end, start + 500);
+    assert!(!contract.is_completed);
+
+    // Nothing vests after cancellation, but what was owed can still be withdrawn
+    env.set_time(start + 900);
+    env.send(&[fixture.withdraw_vested_ix()], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(contract.amount_released, 500);
+    assert_eq!(contract.escrow_balance, 0);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn disputing_a_stream_refunds_only_the_unvested_part() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.parties("stream-dispute", 1_000).await;
+    let start = env.now().await;
+    env.send(&[fixture.create_streaming_ix(1_000, start, start + 1_000)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.set_time(start + 200);
+    env.send(&[fixture.withdraw_vested_ix()], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    // 600 has vested, 200 of it withdrawn; the other 400 stays in escrow for the freelancer
+    env.set_time(start + 600);
+    env.send(&[fixture.dispute_ix("stopped responding")], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.client_token).await, 400);
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_disputed);
+    assert_eq!(contract.total_amount, 600);
+    assert_eq!(contract.escrow_balance, 400);
+
+    env.set_time(start + 900);
+    env.send(&[fixture.withdraw_vested_ix()], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 600);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 0);
+}
+
+#[tokio::test]
+async fn hourly_contract_pays_approved_timesheets_only() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.parties("hourly", 2_000).await;
+    env.send(&[fixture.create_hourly_ix(2_000, 25, 40)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(2_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+
+    env.send(&[fixture.submit_timesheet_ix(0, 40)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_timesheet_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    // Week 1 cannot be billed before it starts
+    let result = env.send(&[fixture.submit_timesheet_ix(1, 10)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidTimesheetPeriod);
+
+    env.set_time(contract.created_at + SECONDS_PER_WEEK);
+    env.send(&[fixture.submit_timesheet_ix(1, 30)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.dispute_timesheet_ix(1)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let approved: Timesheet = env.account(&fixture.timesheet(0)).await;
+    assert_eq!(approved.amount, 1_000);
+    assert!(approved.status == TimesheetStatus::Approved);
+    let disputed: Timesheet = env.account(&fixture.timesheet(1)).await;
+    assert!(disputed.status == TimesheetStatus::Disputed);
+
+    let result = env.send(&[fixture.approve_timesheet_ix(1)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::TimesheetNotPending);
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.amount_released, 1_000);
+    assert_eq!(contract.escrow_balance, 1_000);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 1_000);
+}
+
+#[tokio::test]
+async fn payee_split_distributes_each_release() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.contract("split", 1_001, 1).await;
+    let partner = Keypair::new();
+    let freelancer_share = env.token_account(&fixture.freelancer.pubkey(), 0).await;
+    let partner_share = env.token_account(&partner.pubkey(), 0).await;
+    let payees = vec![
+        Payee {
+            wallet: fixture.freelancer.pubkey(),
+            share_bps: 6_000,
+        },
+        Payee {
+            wallet: partner.pubkey(),
+            share_bps: 4_000,
+        },
+    ];
+
+    env.send(&[fixture.set_payee_split_ix(payees)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_001)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    // Releases must name every payee's token account
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::PayeeSplitRequired);
+
+    let release = ReleaseOptions {
+        payee_token_accounts: vec![freelancer_share, partner_share],
+        payee_wallets: vec![fixture.freelancer.pubkey(), partner.pubkey()],
+        ..ReleaseOptions::default()
+    };
+    env.send(
+        &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &release)],
+        &[&fixture.client],
+    )
+    .await
+    .unwrap();
+
+    // The last payee absorbs the rounding remainder
+    assert_eq!(env.token_balance(&freelancer_share).await, 600);
+    assert_eq!(env.token_balance(&partner_share).await, 401);
+}
+
+#[tokio::test]
+async fn approver_set_releases_once_the_threshold_has_signed() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.contract("approvers", 1_000, 1).await;
+    let approvers = [
+        env.funded_wallet().await,
+        env.funded_wallet().await,
+        env.funded_wallet().await,
+    ];
+    let members = approvers.iter().map(|approver| approver.pubkey()).collect();
+
+    env.send(&[fixture.set_approver_set_ix(members, 2)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let release = ReleaseOptions {
+        approver_set: true,
+        ..ReleaseOptions::default()
+    };
+    let first = &approvers[0];
+    env.send(&[fixture.propose_approval_ix(first.pubkey(), 0, 10_000)], &[first])
+        .await
+        .unwrap();
+
+    let result = env
+        .send(&[fixture.approve_with_ix(first.pubkey(), 0, None, &release)], &[first])
+        .await;
+    assert_escrow_error(result, ErrorCode::ApprovalThresholdNotMet);
+
+    let second = &approvers[1];
+    env.send(&[fixture.sign_approval_ix(second.pubkey(), 0)], &[second])
+        .await
+        .unwrap();
+    let approval: MilestoneApproval = env.account(&fixture.milestone_approval(0)).await;
+    assert_eq!(approval.approvals.len(), 2);
+
+    // Any member can execute once the threshold is met
+    let third = &approvers[2];
+    env.send(&[fixture.approve_with_ix(third.pubkey(), 0, None, &release)], &[third])
+        .await
+        .unwrap();
+
+    let approval: MilestoneApproval = env.account(&fixture.milestone_approval(0)).await;
+    assert!(approval.executed);
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 1_000);
+}
+
+#[tokio::test]
+async fn payment_authorization_funds_each_milestone_without_a_client_deposit() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.contract("auth-funded", 1_000, 2).await;
+    // A small deposit opens the escrow token account the authorization is linked to
+    env.send(&[fixture.deposit_ix(100)], &[&fixture.client])
+        .await
+        .unwrap();
+    let auth = env.contract_authorization(&fixture, 500, 1_000).await;
+    env.send(&[auth.link_escrow_ix(fixture.escrow_token())], &[&auth.client])
+        .await
+        .unwrap();
+
+    for index in 0..2 {
+        env.send(&[fixture.fund_from_authorization_ix()], &[])
+            .await
+            .unwrap();
+        let result = env.send(&[fixture.fund_from_authorization_ix()], &[]).await;
+        assert_escrow_error(result, ErrorCode::EscrowAlreadyFunded);
+
+        env.send(&[fixture.submit_ix(index, "ipfs://proof")], &[&fixture.freelancer])
+            .await
+            .unwrap();
+        env.send(&[fixture.approve_ix(index)], &[&fixture.client])
+            .await
+            .unwrap();
+    }
+
+    let authorization: payment_auth::PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(authorization.total_spent, 900);
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 1_000);
+    assert_eq!(env.token_balance(&fixture.client_token).await, 0);
+}
+
+#[tokio::test]
+async fn reconcile_returns_direct_transfers_to_the_client() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("reconcile", 1_000, 1).await;
+    let caller = env.funded_wallet().await;
+
+    // Tokens sent straight to the escrow token account bypass escrow_balance
+    let stray = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &env.mint,
+        &fixture.escrow_token(),
+        &env.context.payer.pubkey(),
+        &[],
+        37,
+    )
+    .unwrap();
+    env.send(&[stray], &[]).await.unwrap();
+
+    env.send(&[fixture.reconcile_ix(caller.pubkey())], &[&caller])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.escrow_balance, 1_000);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 1_000);
+    assert_eq!(env.token_balance(&fixture.client_token).await, 37);
+
+    // A second run finds nothing to move
+    env.send(&[fixture.reconcile_ix(caller.pubkey())], &[&caller])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.client_token).await, 37);
+}
+
+#[tokio::test]
+async fn reconcile_only_reads_the_escrow_of_the_contract_mint() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("reconcile-mint", 1_000, 1).await;
+    let caller = env.funded_wallet().await;
+
+    // An empty escrow account and a client account of another mint, both opened by the caller
+    let junk = env.create_mint().await;
+    let junk_escrow = env.associated_token_account_of(junk, &fixture.escrow_authority(), 0).await;
+    let junk_client = env.associated_token_account_of(junk, &fixture.client.pubkey(), 0).await;
+    let reconcile = replace_account(fixture.reconcile_ix(caller.pubkey()), fixture.escrow_token(), junk_escrow);
+    let result = env
+        .send(&[replace_account(reconcile, fixture.client_token, junk_client)], &[&caller])
+        .await;
+    assert!(result.is_err());
+
+    // The recorded balance still stands, so the real escrow has no surplus to hand out
+    env.send(&[fixture.reconcile_ix(caller.pubkey())], &[&caller])
+        .await
+        .unwrap();
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.mint, env.mint);
+    assert_eq!(contract.escrow_balance, 1_000);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 1_000);
+    assert_eq!(env.token_balance(&fixture.client_token).await, 0);
+}
+
+#[tokio::test]
+async fn reconcile_returns_rounding_dust_once_the_contract_is_completed() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("reconcile-dust", 1_000, 3).await;
+    let caller = env.funded_wallet().await;
+
+    for index in 0..3 {
+        env.send(&[fixture.submit_ix(index, "ipfs://proof")], &[&fixture.freelancer])
+            .await
+            .unwrap();
+        // Nothing is handed back while milestones are still open
+        env.send(&[fixture.reconcile_ix(caller.pubkey())], &[&caller])
+            .await
+            .unwrap();
+        assert_eq!(env.token_balance(&fixture.client_token).await, 0);
+        env.send(&[fixture.approve_ix(index)], &[&fixture.client])
+            .await
+            .unwrap();
+    }
+
+    // Three milestones of 333 leave one token recorded in escrow
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(contract.amount_released, 999);
+    assert_eq!(contract.escrow_balance, 1);
+
+    env.send(&[fixture.reconcile_ix(caller.pubkey())], &[&caller])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.escrow_balance, 0);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 0);
+    assert_eq!(env.token_balance(&fixture.client_token).await, 1);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 999);
+}
@@ -0,0 +1,738 @@
+//! Shared setup for the solana-program-test suites: a test validator running both
+//! programs, a mint, funded wallets, token accounts and per-contract shorthands for the
+//! smartflo-client instruction builders.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Clock, Pubkey};
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction, InstructionError};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::{system_program, AccountDeserialize};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use freelance_escrow::{Deliverable, Payee};
+use smartflo_client::authorization::AuthorizationKeys;
+use smartflo_client::escrow::ContractKeys;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub use smartflo_client::escrow::ReleaseOptions;
+pub use smartflo_client::{authorization, escrow, pda};
+
+pub const SOL: u64 = 1_000_000_000;
+
+fn escrow_processor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    freelance_escrow::entry(program_id, accounts, data)
+}
+
+fn payment_auth_processor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    payment_auth::entry(program_id, accounts, data)
+}
+
+pub type TxResult = Result<(), TransactionError>;
+
+/// Contract id for a test label, in the 32 lowercase hex digit form the programs accept
+pub fn contract_id(label: &str) -> [u8; 32] {
+    let digest = solana_sdk::hash::hash(label.as_bytes());
+    let hex: String = digest.to_bytes()[..16].iter().map(|byte| format!("{byte:02x}")).collect();
+    hex.as_bytes().try_into().unwrap()
+}
+
+/// Delay the test configs queue changes with
+pub const TIMELOCK_DELAY: i64 = 2 * 24 * 60 * 60;
+
+/// Ed25519 program instruction verifying `signer`'s signature over `message`
+pub fn ed25519_ix(signer: &Keypair, message: &[u8]) -> Instruction {
+    let signature = signer.sign_message(message);
+    escrow::ed25519_verify_instruction(&signer.pubkey(), signature.as_ref().try_into().unwrap(), message)
+}
+
+/// Swap every occurrence of account `from` in a built instruction for `to`, for sending an
+/// instruction with an account its builder would not pick
+pub fn replace_account(mut ix: Instruction, from: Pubkey, to: Pubkey) -> Instruction {
+    for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == from) {
+        meta.pubkey = to;
+    }
+    ix
+}
+
+/// Test validator with both programs loaded, a 6-decimal mint owned by the payer and both
+/// program configs initialized with a `TIMELOCK_DELAY`
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub mint: Pubkey,
+    /// Escrow pause guardian
+    pub guardian: Keypair,
+    /// Config admin of both programs
+    pub admin: Keypair,
+    /// Only payment_auth freeze admin
+    pub freeze_admin: Keypair,
+    /// Maintains the payment_auth sanctions blocklist
+    pub compliance: Keypair,
+    clock_override: Option<i64>,
+}
+
+impl TestEnv {
+    pub async fn start() -> Self {
+        let mut program_test = ProgramTest::new(
+            "freelance_escrow",
+            freelance_escrow::ID,
+            processor!(escrow_processor),
+        );
+        program_test.add_program("payment_auth", payment_auth::ID, processor!(payment_auth_processor));
+        let context = program_test.start_with_context().await;
+
+        let mut env = Self {
+            context,
+            mint: Pubkey::default(),
+            guardian: Keypair::new(),
+            admin: Keypair::new(),
+            freeze_admin: Keypair::new(),
+            compliance: Keypair::new(),
+            clock_override: None,
+        };
+        env.mint = env.create_mint().await;
+        // Programs loaded natively have no program data accounts, so stand them in naming the
+        // payer as upgrade authority
+        let authority = env.context.payer.pubkey();
+        env.set_program_data(&freelance_escrow::ID, Some(authority)).await;
+        env.set_program_data(&payment_auth::ID, Some(authority)).await;
+        let instructions = [
+            // The admin and compliance authority pay the rent of the accounts they create
+            system_instruction::transfer(&authority, &env.admin.pubkey(), SOL),
+            system_instruction::transfer(&authority, &env.compliance.pubkey(), SOL),
+            escrow::initialize_config(authority, env.guardian.pubkey(), env.admin.pubkey(), TIMELOCK_DELAY),
+            authorization::initialize_config(
+                authority,
+                env.admin.pubkey(),
+                vec![env.freeze_admin.pubkey()],
+                env.compliance.pubkey(),
+                TIMELOCK_DELAY,
+            ),
+        ];
+        env.send(&instructions, &[]).await.unwrap();
+        env
+    }
+
+    /// Overwrite `program_id`'s program data account with `upgrade_authority`
+    pub async fn set_program_data(&mut self, program_id: &Pubkey, upgrade_authority: Option<Pubkey>) {
+        // Bincode `UpgradeableLoaderState::ProgramData { slot, upgrade_authority_address }`
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        match upgrade_authority {
+            Some(authority) => {
+                data.push(1);
+                data.extend_from_slice(authority.as_ref());
+            }
+            None => data.push(0),
+        }
+        self.set_account_data(&pda::program_data(program_id).0, &bpf_loader_upgradeable::ID, data)
+            .await;
+    }
+
+    /// Send a transaction paid by the test payer; a fresh blockhash keeps repeated
+    /// instructions from being rejected as duplicates
+    pub async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TxResult {
+        let transaction = self.transaction(instructions, signers).await;
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|err| err.unwrap())
+    }
+
+    async fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        // New banks recompute the clock, so a pinned time is reapplied before every send
+        if let Some(unix_timestamp) = self.clock_override {
+            let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+            clock.unix_timestamp = unix_timestamp;
+            self.context.set_sysvar(&clock);
+        }
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        )
+    }
+
+    /// New 6-decimal mint with the payer as mint authority
+    pub async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &payer, None, 6).unwrap(),
+        ];
+        self.send(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// New wallet holding `SOL` lamports for rent and fees
+    pub async fn funded_wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        let transfer = system_instruction::transfer(&self.context.payer.pubkey(), &wallet.pubkey(), SOL);
+        self.send(&[transfer], &[]).await.unwrap();
+        wallet
+    }
+
+    /// New token account of the test mint owned by `owner`, holding `amount`
+    pub async fn token_account(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        let mut instructions = vec![
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account(&spl_token::ID, &account.pubkey(), &self.mint, owner)
+                .unwrap(),
+        ];
+        if amount > 0 {
+            instructions.push(
+                spl_token::instruction::mint_to(&spl_token::ID, &self.mint, &account.pubkey(), &payer, &[], amount)
+                    .unwrap(),
+            );
+        }
+        self.send(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    /// Associated token account of the test mint for `owner`, holding `amount`
+    pub async fn associated_token_account(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        self.associated_token_account_of(self.mint, owner, amount).await
+    }
+
+    /// Associated token account of `mint` for `owner`, holding `amount`
+    pub async fn associated_token_account_of(&mut self, mint: Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = get_associated_token_address(owner, &mint);
+        let payer = self.context.payer.pubkey();
+        let mut instructions = vec![Instruction {
+            program_id: associated_token::ID,
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(address, false),
+                AccountMeta::new_readonly(*owner, false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data: vec![],
+        }];
+        if amount > 0 {
+            instructions.push(
+                spl_token::instruction::mint_to(&spl_token::ID, &mint, &address, &payer, &[], amount).unwrap(),
+            );
+        }
+        self.send(&instructions, &[]).await.unwrap();
+        address
+    }
+
+    pub async fn lamports(&mut self, account: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*account).await.unwrap()
+    }
+
+    /// Rent-exempt minimum for an account of `size` bytes
+    pub async fn rent(&mut self, size: usize) -> u64 {
+        self.context.banks_client.get_rent().await.unwrap().minimum_balance(size)
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        let account = self.context.banks_client.get_account(*account).await.unwrap().unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} does not exist"));
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        match self.clock_override {
+            Some(unix_timestamp) => unix_timestamp,
+            None => self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp,
+        }
+    }
+
+    /// Write raw account data, rent exempt for its size, as if an older program version had created it
+    pub async fn set_account_data(&mut self, address: &Pubkey, owner: &Pubkey, data: Vec<u8>) {
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let mut account = AccountSharedData::new(rent.minimum_balance(data.len()), data.len(), owner);
+        account.set_data_from_slice(&data);
+        self.context.set_account(address, &account);
+    }
+
+    /// Pin the on-chain clock for all following transactions
+    pub fn set_time(&mut self, unix_timestamp: i64) {
+        self.clock_override = Some(unix_timestamp);
+    }
+
+    /// Freelancer and client wallets with token accounts; the client holds `client_tokens`
+    pub async fn parties(&mut self, label: &str, client_tokens: u64) -> ContractFixture {
+        let freelancer = self.funded_wallet().await;
+        let client = self.funded_wallet().await;
+        let client_token = self.associated_token_account(&client.pubkey(), client_tokens).await;
+        let freelancer_token = self.associated_token_account(&freelancer.pubkey(), 0).await;
+        ContractFixture {
+            id: contract_id(label),
+            mint: self.mint,
+            freelancer,
+            client,
+            client_token,
+            freelancer_token,
+            legacy_seeds: false,
+            legacy_id: None,
+            sponsor: None,
+        }
+    }
+
+    /// Milestone contract created by the freelancer; the client holds `total_amount` tokens
+    pub async fn contract(&mut self, label: &str, total_amount: u64, milestone_count: u8) -> ContractFixture {
+        let fixture = self.parties(label, total_amount).await;
+        self.send(&[fixture.create_ix(total_amount, milestone_count)], &[&fixture.freelancer])
+            .await
+            .unwrap();
+        fixture
+    }
+
+    /// Milestone contract with the full amount deposited in escrow
+    pub async fn funded_contract(&mut self, label: &str, total_amount: u64, milestone_count: u8) -> ContractFixture {
+        let fixture = self.contract(label, total_amount, milestone_count).await;
+        self.send(&[fixture.deposit_ix(total_amount)], &[&fixture.client])
+            .await
+            .unwrap();
+        fixture
+    }
+}
+
+/// Assert that a transaction failed with a freelance_escrow error
+pub fn assert_escrow_error(result: TxResult, expected: freelance_escrow::ErrorCode) {
+    assert_custom_error(result, u32::from(expected), &expected.name());
+}
+
+/// Assert that a transaction failed with a payment_auth error
+pub fn assert_auth_error(result: TxResult, expected: payment_auth::ErrorCode) {
+    assert_custom_error(result, u32::from(expected), &expected.name());
+}
+
+fn assert_custom_error(result: TxResult, code: u32, name: &str) {
+    match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(actual))) => {
+            assert_eq!(actual, code, "expected {name} ({code}), got custom error {actual}")
+        }
+        other => panic!("expected {name} ({code}), got {other:?}"),
+    }
+}
+
+/// Participants of one freelance_escrow contract and shorthands for its instructions
+pub struct ContractFixture {
+    pub id: [u8; 32],
+    pub mint: Pubkey,
+    pub freelancer: Keypair,
+    pub client: Keypair,
+    pub client_token: Pubkey,
+    pub freelancer_token: Pubkey,
+    /// Derive the contract and escrow from the id alone, as contracts created before the
+    /// addresses included the participants were
+    pub legacy_seeds: bool,
+    /// Legacy id that was not 32 bytes long; seeds the legacy addresses instead of `id`
+    pub legacy_id: Option<String>,
+    /// Pays the rent of the accounts the participants create instead of the signing participant
+    pub sponsor: Option<Pubkey>,
+}
+
+impl ContractFixture {
+    /// SDK keys of the contract, with the fixture's token accounts
+    pub fn keys(&self) -> ContractKeys {
+        ContractKeys {
+            client_token_account: self.client_token,
+            freelancer_token_account: self.freelancer_token,
+            legacy_seeds: self.legacy_seeds,
+            legacy_id: self.legacy_id.clone(),
+            payer: self.sponsor,
+            ..ContractKeys::new(self.id.into(), self.freelancer.pubkey(), self.client.pubkey(), self.mint)
+        }
+    }
+
+    pub fn address(&self) -> Pubkey {
+        self.keys().contract()
+    }
+
+    pub fn escrow_authority(&self) -> Pubkey {
+        self.keys().escrow_authority()
+    }
+
+    pub fn escrow_token(&self) -> Pubkey {
+        self.keys().escrow_token_account()
+    }
+
+    pub fn milestone(&self, index: u8) -> Pubkey {
+        self.keys().milestone(index)
+    }
+
+    pub fn deliverables(&self, index: u8) -> Pubkey {
+        pda::deliverables(&self.milestone(index)).0
+    }
+
+    pub fn dispute(&self) -> Pubkey {
+        self.keys().dispute()
+    }
+
+    pub fn evidence(&self, submitter: &Pubkey, index: u8) -> Pubkey {
+        pda::dispute_evidence(&self.dispute(), submitter, index).0
+    }
+
+    pub fn timesheet(&self, week_index: u32) -> Pubkey {
+        pda::timesheet(&self.address(), week_index).0
+    }
+
+    pub fn payee_split(&self) -> Pubkey {
+        pda::payee_split(&self.address()).0
+    }
+
+    pub fn approver_set(&self) -> Pubkey {
+        pda::approver_set(&self.address()).0
+    }
+
+    pub fn co_signer_policy(&self) -> Pubkey {
+        pda::co_signer_policy(&self.address()).0
+    }
+
+    pub fn milestone_approval(&self, index: u8) -> Pubkey {
+        pda::milestone_approval(&self.milestone(index)).0
+    }
+
+    pub fn create_ix(&self, total_amount: u64, milestone_count: u8) -> Instruction {
+        escrow::create_contract(&self.keys(), total_amount, milestone_count)
+    }
+
+    pub fn create_streaming_ix(&self, total_amount: u64, stream_start: i64, stream_end: i64) -> Instruction {
+        escrow::create_streaming_contract(&self.keys(), total_amount, stream_start, stream_end)
+    }
+
+    pub fn create_hourly_ix(&self, total_amount: u64, hourly_rate: u64, weekly_hour_cap: u16) -> Instruction {
+        escrow::create_hourly_contract(&self.keys(), total_amount, hourly_rate, weekly_hour_cap)
+    }
+
+    pub fn deposit_ix(&self, amount: u64) -> Instruction {
+        escrow::deposit_funds(&self.keys(), amount)
+    }
+
+    pub fn fund_from_authorization_ix(&self) -> Instruction {
+        escrow::fund_from_authorization(&self.keys())
+    }
+
+    pub fn submit_ix(&self, milestone_index: u8, proof_uri: &str) -> Instruction {
+        escrow::submit_milestone(&self.keys(), milestone_index, proof_uri)
+    }
+
+    pub fn submit_deliverables_ix(&self, milestone_index: u8, items: Vec<Deliverable>) -> Instruction {
+        escrow::submit_milestone_deliverables(&self.keys(), milestone_index, items)
+    }
+
+    /// `approve_milestone` signed by the client
+    pub fn approve_ix(&self, milestone_index: u8) -> Instruction {
+        self.approve_with_ix(self.client.pubkey(), milestone_index, None, &ReleaseOptions::default())
+    }
+
+    /// `approve_milestone_partial` signed by the client
+    pub fn approve_partial_ix(&self, milestone_index: u8, approval_bps: u16) -> Instruction {
+        self.approve_with_ix(self.client.pubkey(), milestone_index, Some(approval_bps), &ReleaseOptions::default())
+    }
+
+    /// `approve_milestone` (or `approve_milestone_partial` with `approval_bps`) signed by `approver`
+    pub fn approve_with_ix(
+        &self,
+        approver: Pubkey,
+        milestone_index: u8,
+        approval_bps: Option<u16>,
+        release: &ReleaseOptions,
+    ) -> Instruction {
+        match approval_bps {
+            Some(approval_bps) => {
+                escrow::approve_milestone_partial(&self.keys(), approver, milestone_index, approval_bps, release)
+            }
+            None => escrow::approve_milestone(&self.keys(), approver, milestone_index, release),
+        }
+    }
+
+    /// `approve_milestones` signed by the client
+    pub fn approve_batch_ix(&self, milestone_indices: Vec<u8>, release: &ReleaseOptions) -> Instruction {
+        escrow::approve_milestones(&self.keys(), milestone_indices, release)
+    }
+
+    /// Ed25519 check of the client's signed approval followed by `approve_milestone_signed`
+    /// submitted by `relayer`; `amount` is the release the client signs for
+    pub fn approve_signed_ixs(
+        &self,
+        relayer: Pubkey,
+        milestone_index: u8,
+        approval_bps: u16,
+        amount: u64,
+        nonce: u64,
+        expires_at: i64,
+    ) -> Vec<Instruction> {
+        let keys = self.keys();
+        let message = escrow::signed_approval_message(&keys, milestone_index, amount, nonce, expires_at);
+        vec![
+            ed25519_ix(&self.client, &message),
+            escrow::approve_milestone_signed(
+                &keys,
+                relayer,
+                milestone_index,
+                approval_bps,
+                nonce,
+                expires_at,
+                &ReleaseOptions::default(),
+            ),
+        ]
+    }
+
+    pub fn pay_bonus_ix(&self, amount: u64, from_escrow: bool, release: &ReleaseOptions) -> Instruction {
+        escrow::pay_bonus(&self.keys(), amount, from_escrow, release)
+    }
+
+    pub fn withdraw_vested_ix(&self) -> Instruction {
+        escrow::withdraw_vested(&self.keys(), &ReleaseOptions::default())
+    }
+
+    pub fn cancel_stream_ix(&self) -> Instruction {
+        self.cancel_stream_with_ix(&ReleaseOptions::default())
+    }
+
+    pub fn cancel_stream_with_ix(&self, release: &ReleaseOptions) -> Instruction {
+        escrow::cancel_stream(&self.keys(), release)
+    }
+
+    pub fn submit_timesheet_ix(&self, week_index: u32, hours: u16) -> Instruction {
+        escrow::submit_timesheet(&self.keys(), week_index, hours, [7; 32])
+    }
+
+    pub fn approve_timesheet_ix(&self, week_index: u32) -> Instruction {
+        self.approve_timesheet_with_ix(week_index, &ReleaseOptions::default())
+    }
+
+    pub fn approve_timesheet_with_ix(&self, week_index: u32, release: &ReleaseOptions) -> Instruction {
+        escrow::approve_timesheet(&self.keys(), week_index, release)
+    }
+
+    pub fn dispute_timesheet_ix(&self, week_index: u32) -> Instruction {
+        escrow::dispute_timesheet(&self.keys(), week_index)
+    }
+
+    pub fn set_payee_split_ix(&self, payees: Vec<Payee>) -> Instruction {
+        escrow::set_payee_split(&self.keys(), payees)
+    }
+
+    pub fn update_payee_split_ix(&self, payees: Vec<Payee>) -> Instruction {
+        escrow::update_payee_split(&self.keys(), payees)
+    }
+
+    pub fn set_co_signer_ix(&self, co_signer: Pubkey, threshold: u64) -> Instruction {
+        escrow::set_co_signer(&self.keys(), co_signer, threshold)
+    }
+
+    pub fn update_co_signer_ix(&self, co_signer: Pubkey, new_co_signer: Pubkey, threshold: u64) -> Instruction {
+        escrow::update_co_signer(&self.keys(), co_signer, new_co_signer, threshold)
+    }
+
+    pub fn set_approver_set_ix(&self, members: Vec<Pubkey>, threshold: u8) -> Instruction {
+        escrow::set_approver_set(&self.keys(), members, threshold)
+    }
+
+    pub fn propose_approval_ix(&self, approver: Pubkey, milestone_index: u8, approval_bps: u16) -> Instruction {
+        escrow::propose_milestone_approval(&self.keys(), approver, milestone_index, approval_bps)
+    }
+
+    pub fn revise_approval_ix(&self, approver: Pubkey, milestone_index: u8, approval_bps: u16) -> Instruction {
+        escrow::revise_milestone_approval(&self.keys(), approver, milestone_index, approval_bps)
+    }
+
+    pub fn sign_approval_ix(&self, approver: Pubkey, milestone_index: u8) -> Instruction {
+        escrow::sign_milestone_approval(&self.keys(), approver, milestone_index)
+    }
+
+    pub fn dispute_ix(&self, reason: &str) -> Instruction {
+        self.dispute_with_ix(reason, &ReleaseOptions::default())
+    }
+
+    pub fn dispute_with_ix(&self, reason: &str, release: &ReleaseOptions) -> Instruction {
+        escrow::dispute_contract(&self.keys(), reason, release)
+    }
+
+    pub fn submit_evidence_ix(
+        &self,
+        submitter: &Keypair,
+        evidence_index: u8,
+        hash: [u8; 32],
+        uri: &str,
+    ) -> Instruction {
+        escrow::submit_dispute_evidence(&self.keys(), submitter.pubkey(), evidence_index, hash, uri)
+    }
+
+    pub fn reconcile_ix(&self, caller: Pubkey) -> Instruction {
+        escrow::reconcile_escrow(&self.keys(), caller)
+    }
+
+    pub fn migrate_contract_ix(&self, payer: Pubkey) -> Instruction {
+        escrow::migrate_contract(&self.keys(), payer)
+    }
+
+    pub fn migrate_milestone_ix(&self, payer: Pubkey, milestone_index: u8) -> Instruction {
+        escrow::migrate_milestone(&self.keys(), payer, milestone_index)
+    }
+}
+
+/// Client and freelancer of one payment_auth authorization and shorthands for its instructions;
+/// payments move between the participants' associated token accounts for `mint`
+pub struct AuthFixture {
+    pub contract_id: [u8; 32],
+    pub mint: Pubkey,
+    pub client: Keypair,
+    pub freelancer: Pubkey,
+    pub client_token: Pubkey,
+    pub freelancer_token: Pubkey,
+    /// Legacy id that was not 32 bytes long; seeds the authorization's address instead of `contract_id`
+    pub legacy_id: Option<String>,
+}
+
+impl TestEnv {
+    /// Payment authorization for an existing contract, created by its client
+    pub async fn contract_authorization(
+        &mut self,
+        fixture: &ContractFixture,
+        max_per_milestone: u64,
+        total_authorized: u64,
+    ) -> AuthFixture {
+        let auth = AuthFixture {
+            contract_id: fixture.id,
+            mint: fixture.mint,
+            client: Keypair::from_bytes(&fixture.client.to_bytes()).unwrap(),
+            freelancer: fixture.freelancer.pubkey(),
+            client_token: fixture.client_token,
+            freelancer_token: fixture.freelancer_token,
+            legacy_id: None,
+        };
+        self.send(&[auth.create_ix(max_per_milestone, total_authorized)], &[&auth.client])
+            .await
+            .unwrap();
+        auth
+    }
+
+    /// Payment authorization created by a new client holding `client_tokens`
+    pub async fn authorization(
+        &mut self,
+        label: &str,
+        client_tokens: u64,
+        max_per_milestone: u64,
+        total_authorized: u64,
+    ) -> AuthFixture {
+        let client = self.funded_wallet().await;
+        let freelancer = Keypair::new().pubkey();
+        let client_token = self.associated_token_account(&client.pubkey(), client_tokens).await;
+        let freelancer_token = self.associated_token_account(&freelancer, 0).await;
+        let fixture = AuthFixture {
+            contract_id: contract_id(label),
+            mint: self.mint,
+            client,
+            freelancer,
+            client_token,
+            freelancer_token,
+            legacy_id: None,
+        };
+        self.send(&[fixture.create_ix(max_per_milestone, total_authorized)], &[&fixture.client])
+            .await
+            .unwrap();
+        fixture
+    }
+}
+
+impl AuthFixture {
+    /// SDK keys of the authorization
+    pub fn keys(&self) -> AuthorizationKeys {
+        let mut keys = AuthorizationKeys::new(self.contract_id.into(), self.client.pubkey(), self.freelancer);
+        keys.legacy_id = self.legacy_id.clone();
+        keys
+    }
+
+    pub fn address(&self) -> Pubkey {
+        self.keys().payment_authorization()
+    }
+
+    pub fn create_ix(&self, max_per_milestone: u64, total_authorized: u64) -> Instruction {
+        authorization::create_payment_authorization(&self.keys(), max_per_milestone, total_authorized)
+    }
+
+    pub fn process_payment_ix(&self, milestone_id: &str, amount: u64) -> Instruction {
+        authorization::process_milestone_payment(&self.keys(), &self.mint, milestone_id, amount, None)
+    }
+
+    /// `process_milestone_payment` signed by the client and `co_signer`
+    pub fn process_payment_co_signed_ix(&self, milestone_id: &str, amount: u64, co_signer: Pubkey) -> Instruction {
+        authorization::process_milestone_payment(&self.keys(), &self.mint, milestone_id, amount, Some(co_signer))
+    }
+
+    /// `process_milestone_payment` naming `freelancer` in place of the authorized one
+    pub fn process_payment_to_ix(&self, freelancer: Pubkey, milestone_id: &str, amount: u64) -> Instruction {
+        let keys = AuthorizationKeys {
+            freelancer,
+            ..self.keys()
+        };
+        authorization::process_milestone_payment(&keys, &self.mint, milestone_id, amount, None)
+    }
+
+    pub fn link_escrow_ix(&self, escrow_token: Pubkey) -> Instruction {
+        authorization::link_escrow(&self.keys(), escrow_token, &self.mint)
+    }
+
+    pub fn update_ix(&self, new_max_per_milestone: Option<u64>, additional_authorized: Option<u64>) -> Instruction {
+        authorization::update_authorization(&self.keys(), new_max_per_milestone, additional_authorized)
+    }
+
+    pub fn set_co_signer_ix(&self, co_signer: Pubkey, threshold: u64) -> Instruction {
+        authorization::set_co_signer(&self.keys(), co_signer, threshold)
+    }
+
+    pub fn update_co_signer_ix(&self, co_signer: Pubkey, new_co_signer: Pubkey, threshold: u64) -> Instruction {
+        authorization::update_co_signer(&self.keys(), co_signer, new_co_signer, threshold)
+    }
+
+    pub fn revoke_ix(&self) -> Instruction {
+        authorization::revoke_authorization(&self.keys())
+    }
+
+    pub fn freeze_ix(&self, admin: Pubkey) -> Instruction {
+        authorization::freeze_authorization(&self.keys(), admin)
+    }
+
+    pub fn migrate_ix(&self, payer: Pubkey) -> Instruction {
+        authorization::migrate_authorization(&self.keys(), payer)
+    }
+}
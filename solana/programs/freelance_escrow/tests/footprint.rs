@@ -0,0 +1,45 @@
+//! Rent saved by the compact account layouts.
+//!
+//! Run with `--nocapture` to print the table.
+
+mod fixtures;
+
+use anchor_lang::Space;
+use fixtures::*;
+use freelance_escrow::{Dispute, FreelanceContract, FreelanceContractV0, Milestone, MilestoneV0};
+use payment_auth::{PaymentAuthorization, PaymentAuthorizationV0};
+
+#[tokio::test]
+async fn compact_layouts_need_less_rent_than_the_legacy_ones() {
+    let mut env = TestEnv::start().await;
+
+    let layouts = [
+        ("FreelanceContract", 8 + FreelanceContractV0::INIT_SPACE, 8 + FreelanceContract::INIT_SPACE),
+        ("Milestone", 8 + MilestoneV0::INIT_SPACE, 8 + Milestone::INIT_SPACE),
+        ("PaymentAuthorization", 8 + PaymentAuthorizationV0::INIT_SPACE, 8 + PaymentAuthorization::INIT_SPACE),
+    ];
+    println!("{:<22} {:>12} {:>12} {:>14} {:>14}", "account", "legacy bytes", "bytes", "legacy rent", "rent");
+    for (name, legacy_size, size) in layouts {
+        let legacy_rent = env.rent(legacy_size).await;
+        let rent = env.rent(size).await;
+        println!("{name:<22} {legacy_size:>12} {size:>12} {legacy_rent:>14} {rent:>14}");
+    }
+
+    // Undisputed contracts no longer pay for 500 bytes of reason text they never use; the
+    // few disputed ones pay for a separate `Dispute` account on top
+    let legacy_contract = env.rent(8 + FreelanceContractV0::INIT_SPACE).await;
+    let contract = env.rent(8 + FreelanceContract::INIT_SPACE).await;
+    let dispute = env.rent(8 + Dispute::INIT_SPACE).await;
+    println!(
+        "contract rent: {contract} lamports, {} saved; a dispute adds {dispute}",
+        legacy_contract - contract
+    );
+    // Less than half the legacy rent, the 128-byte account overhead included
+    assert!(contract * 2 < legacy_contract);
+
+    // What the program allocates on chain matches the layout
+    let fixture = env.contract("footprint-rent", 1_000, 1).await;
+    let account = env.context.banks_client.get_account(fixture.address()).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 8 + FreelanceContract::INIT_SPACE);
+    assert_eq!(account.lamports, contract);
+}
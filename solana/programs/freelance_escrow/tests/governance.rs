@@ -0,0 +1,166 @@
+//! Config changes of both programs wait out the timelock delay, can be cancelled during
+//! the window and refund their rent to the admin who queued them.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{ConfigChange, ErrorCode, EscrowConfig, PendingConfigChange, MIN_TIMELOCK_DELAY};
+use payment_auth::AuthConfig;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn escrow_change_executes_once_the_delay_has_passed() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let caller = env.funded_wallet().await;
+    let new_guardian = Keypair::new();
+    let now = env.now().await;
+    env.set_time(now);
+
+    env.send(
+        &[escrow::queue_config_change(
+            admin.pubkey(),
+            admin.pubkey(),
+            0,
+            ConfigChange::Guardian(new_guardian.pubkey()),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let pending_address = pda::config_change(0).0;
+    let pending: PendingConfigChange = env.account(&pending_address).await;
+    assert_eq!(pending.change_id, 0);
+    assert_eq!(pending.proposer, admin.pubkey());
+    assert_eq!(pending.eta, now + TIMELOCK_DELAY);
+    let config: EscrowConfig = env.account(&pda::config().0).await;
+    assert_eq!(config.change_count, 1);
+    assert_eq!(config.guardian, env.guardian.pubkey());
+
+    env.set_time(now + TIMELOCK_DELAY - 1);
+    let result = env
+        .send(&[escrow::execute_config_change(caller.pubkey(), 0, admin.pubkey())], &[&caller])
+        .await;
+    assert_escrow_error(result, ErrorCode::TimelockNotElapsed);
+
+    env.set_time(now + TIMELOCK_DELAY);
+    let admin_lamports = env.lamports(&admin.pubkey()).await;
+    env.send(&[escrow::execute_config_change(caller.pubkey(), 0, admin.pubkey())], &[&caller])
+        .await
+        .unwrap();
+    let config: EscrowConfig = env.account(&pda::config().0).await;
+    assert_eq!(config.guardian, new_guardian.pubkey());
+    assert!(env.context.banks_client.get_account(pending_address).await.unwrap().is_none());
+    assert!(env.lamports(&admin.pubkey()).await > admin_lamports);
+
+    // The pause key moved with the change
+    let old_guardian = Keypair::from_bytes(&env.guardian.to_bytes()).unwrap();
+    let result = env.send(&[escrow::set_paused(old_guardian.pubkey(), true)], &[&old_guardian]).await;
+    assert_escrow_error(result, ErrorCode::NotGuardian);
+    env.send(&[escrow::set_paused(new_guardian.pubkey(), true)], &[&new_guardian])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn admin_or_guardian_can_cancel_during_the_window() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let guardian = Keypair::from_bytes(&env.guardian.to_bytes()).unwrap();
+    let now = env.now().await;
+    env.set_time(now);
+
+    let takeover = Keypair::new();
+    env.send(
+        &[
+            escrow::queue_config_change(admin.pubkey(), admin.pubkey(), 0, ConfigChange::Admin(takeover.pubkey())),
+            escrow::queue_config_change(
+                admin.pubkey(),
+                admin.pubkey(),
+                1,
+                ConfigChange::TimelockDelay(MIN_TIMELOCK_DELAY),
+            ),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    env.send(&[escrow::cancel_config_change(guardian.pubkey(), 0, admin.pubkey())], &[&guardian])
+        .await
+        .unwrap();
+    env.send(&[escrow::cancel_config_change(admin.pubkey(), 1, admin.pubkey())], &[&admin])
+        .await
+        .unwrap();
+
+    env.set_time(now + TIMELOCK_DELAY);
+    for change_id in [0, 1] {
+        assert!(env
+            .context
+            .banks_client
+            .get_account(pda::config_change(change_id).0)
+            .await
+            .unwrap()
+            .is_none());
+        let result = env
+            .send(&[escrow::execute_config_change(admin.pubkey(), change_id, admin.pubkey())], &[&admin])
+            .await;
+        assert!(result.is_err());
+    }
+    let config: EscrowConfig = env.account(&pda::config().0).await;
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.timelock_delay, TIMELOCK_DELAY);
+    assert_eq!(config.change_count, 2);
+}
+
+#[tokio::test]
+async fn payment_auth_freeze_admins_change_through_the_timelock() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let old_freeze_admin = Keypair::from_bytes(&env.freeze_admin.to_bytes()).unwrap();
+    let new_freeze_admin = Keypair::new();
+    let auth = env.authorization("governed-freeze", 1_000, 500, 1_000).await;
+    let now = env.now().await;
+    env.set_time(now);
+
+    let change = payment_auth::ConfigChange::FreezeAdmins(vec![new_freeze_admin.pubkey()]);
+    env.send(&[authorization::queue_config_change(admin.pubkey(), admin.pubkey(), 0, change)], &[&admin])
+        .await
+        .unwrap();
+    let result = env
+        .send(&[authorization::execute_config_change(admin.pubkey(), 0, admin.pubkey())], &[&admin])
+        .await;
+    assert_auth_error(result, payment_auth::ErrorCode::TimelockNotElapsed);
+
+    env.set_time(now + TIMELOCK_DELAY);
+    env.send(&[authorization::execute_config_change(admin.pubkey(), 0, admin.pubkey())], &[&admin])
+        .await
+        .unwrap();
+    let config: AuthConfig = env.account(&pda::auth_config().0).await;
+    assert_eq!(config.freeze_admins, vec![new_freeze_admin.pubkey()]);
+
+    let result = env.send(&[auth.freeze_ix(old_freeze_admin.pubkey())], &[&old_freeze_admin]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::UnauthorizedAdmin);
+    env.send(&[auth.freeze_ix(new_freeze_admin.pubkey())], &[&new_freeze_admin])
+        .await
+        .unwrap();
+
+    // A later change can still be dropped before it lands, by the admin or a freeze admin
+    env.send(
+        &[authorization::queue_config_change(
+            admin.pubkey(),
+            admin.pubkey(),
+            1,
+            payment_auth::ConfigChange::TimelockDelay(payment_auth::MIN_TIMELOCK_DELAY),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    env.send(&[authorization::cancel_config_change(new_freeze_admin.pubkey(), 1, admin.pubkey())], &[&new_freeze_admin])
+        .await
+        .unwrap();
+    let config: AuthConfig = env.account(&pda::auth_config().0).await;
+    assert_eq!(config.timelock_delay, TIMELOCK_DELAY);
+}
@@ -0,0 +1,189 @@
+//! Contracts and authorizations above the configured KYC threshold need a current
+//! attestation of the required level for both participants.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{ErrorCode, EscrowConfig};
+use payment_auth::{AuthConfig, KycAttestation, PaymentAuthorization};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+/// Put a KYC requirement on both programs through the timelock; returns the time it took effect
+async fn require_kyc(env: &mut TestEnv, threshold: u64, min_level: u8) -> i64 {
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let now = env.now().await;
+    env.set_time(now);
+    env.send(
+        &[
+            escrow::queue_config_change(
+                admin.pubkey(),
+                admin.pubkey(),
+                0,
+                freelance_escrow::ConfigChange::KycRequirement { threshold, min_level },
+            ),
+            authorization::queue_config_change(
+                admin.pubkey(),
+                admin.pubkey(),
+                0,
+                payment_auth::ConfigChange::KycRequirement { threshold, min_level },
+            ),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    let effective = now + TIMELOCK_DELAY;
+    env.set_time(effective);
+    env.send(
+        &[
+            escrow::execute_config_change(admin.pubkey(), 0, admin.pubkey()),
+            authorization::execute_config_change(admin.pubkey(), 0, admin.pubkey()),
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    effective
+}
+
+#[tokio::test]
+async fn large_contracts_need_both_participants_attested() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let now = require_kyc(&mut env, 10_000, 2).await;
+    let config: EscrowConfig = env.account(&pda::config().0).await;
+    assert_eq!(config.kyc_threshold, 10_000);
+    assert_eq!(config.kyc_min_level, 2);
+
+    // At the threshold nothing is checked
+    let small = env.parties("kyc-small", 10_000).await;
+    env.send(&[small.create_ix(10_000, 1)], &[&small.freelancer])
+        .await
+        .unwrap();
+
+    let fixture = env.parties("kyc-large", 20_000).await;
+    let result = env.send(&[fixture.create_ix(20_000, 2)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::KycRequired);
+
+    // A level below the requirement does not count, and both participants need one
+    let expires_at = now + 365 * 24 * 60 * 60;
+    env.send(
+        &[
+            authorization::issue_kyc_attestation(authority, authority, fixture.freelancer.pubkey(), 2, expires_at),
+            authorization::issue_kyc_attestation(authority, authority, fixture.client.pubkey(), 1, expires_at),
+        ],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    let result = env.send(&[fixture.create_ix(20_000, 2)], &[&fixture.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::KycRequired);
+
+    // Issuing again renews the existing attestation
+    env.send(
+        &[authorization::issue_kyc_attestation(authority, authority, fixture.client.pubkey(), 3, expires_at)],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    let attestation: KycAttestation = env.account(&pda::kyc_attestation(&fixture.client.pubkey()).0).await;
+    assert_eq!(attestation.level, 3);
+    assert_eq!(attestation.issued_by, compliance.pubkey());
+    env.send(&[fixture.create_ix(20_000, 2)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn expired_and_revoked_attestations_stop_counting() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let now = require_kyc(&mut env, 1_000, 1).await;
+    let config: AuthConfig = env.account(&pda::auth_config().0).await;
+    assert_eq!(config.kyc_threshold, 1_000);
+
+    // At the threshold nothing is checked
+    env.authorization("kyc-auth-small", 1_000, 500, 1_000).await;
+
+    let client = env.funded_wallet().await;
+    let freelancer = Keypair::new().pubkey();
+    let client_token = env.associated_token_account(&client.pubkey(), 5_000).await;
+    let freelancer_token = env.associated_token_account(&freelancer, 0).await;
+    let auth = AuthFixture {
+        contract_id: contract_id("kyc-auth-large"),
+        mint: env.mint,
+        client,
+        freelancer,
+        client_token,
+        freelancer_token,
+        legacy_id: None,
+    };
+    let expires_at = now + 100;
+    env.send(
+        &[
+            authorization::issue_kyc_attestation(authority, authority, auth.client.pubkey(), 1, expires_at),
+            authorization::issue_kyc_attestation(authority, authority, auth.freelancer, 1, expires_at),
+        ],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+
+    let compliance_lamports = env.lamports(&compliance.pubkey()).await;
+    env.send(&[authorization::revoke_kyc_attestation(authority, auth.client.pubkey(), authority)], &[&compliance])
+        .await
+        .unwrap();
+    assert!(env.lamports(&compliance.pubkey()).await > compliance_lamports);
+    let result = env.send(&[auth.create_ix(1_000, 5_000)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::KycRequired);
+
+    env.send(
+        &[authorization::issue_kyc_attestation(authority, authority, auth.client.pubkey(), 1, expires_at)],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    env.set_time(expires_at);
+    let result = env.send(&[auth.create_ix(1_000, 5_000)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::KycRequired);
+
+    env.set_time(expires_at - 1);
+    env.send(&[auth.create_ix(1_000, 5_000)], &[&auth.client])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn topping_up_past_the_threshold_needs_attestations() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let now = require_kyc(&mut env, 1_000, 1).await;
+
+    // Created at the threshold, then topped up past it
+    let auth = env.authorization("kyc-auth-top-up", 5_000, 500, 1_000).await;
+    let result = env.send(&[auth.update_ix(None, Some(4_000))], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::KycRequired);
+
+    env.send(
+        &[
+            authorization::issue_kyc_attestation(authority, authority, auth.client.pubkey(), 1, now + 100),
+            authorization::issue_kyc_attestation(authority, authority, auth.freelancer, 1, now + 100),
+        ],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    env.send(&[auth.update_ix(None, Some(4_000))], &[&auth.client])
+        .await
+        .unwrap();
+    let updated: PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(updated.total_authorized, 5_000);
+
+    let result = env.send(&[auth.update_ix(None, Some(u64::MAX))], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::InvalidAmount);
+}
@@ -0,0 +1,396 @@
+//! Accounts written in the layouts from before versioning are upgraded in place by the
+//! migrate instructions and keep working afterwards.
+
+mod fixtures;
+
+use anchor_lang::{AnchorSerialize, Discriminator, Space};
+use fixtures::*;
+use freelance_escrow::{
+    ContractType, Dispute, ErrorCode, FreelanceContract, FreelanceContractV0, Milestone, MilestoneV0,
+};
+use payment_auth::{PaymentAuthorization, PaymentAuthorizationV0};
+use smartflo_client::{pda, ContractId};
+use solana_sdk::signer::Signer;
+
+/// Account data as the unversioned program allocated it: discriminator, fields, zero padding
+fn legacy_data<T: AnchorSerialize + Space>(discriminator: [u8; 8], value: &T) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    value.serialize(&mut data).unwrap();
+    data.resize(8 + T::INIT_SPACE, 0);
+    data
+}
+
+fn legacy_contract(fixture: &ContractFixture) -> FreelanceContractV0 {
+    FreelanceContractV0 {
+        contract_id: String::from_utf8(fixture.id.to_vec()).unwrap(),
+        freelancer: fixture.freelancer.pubkey(),
+        client: fixture.client.pubkey(),
+        total_amount: 1_000,
+        milestone_count: 2,
+        completed_milestones: 1,
+        amount_released: 500,
+        escrow_balance: 500,
+        is_active: true,
+        is_completed: false,
+        is_disputed: false,
+        dispute_reason: String::new(),
+        created_at: 1_700_000_000,
+        completed_at: 0,
+    }
+}
+
+#[tokio::test]
+async fn legacy_contract_and_milestone_are_upgraded_in_place() {
+    let mut env = TestEnv::start().await;
+    let mut fixture = env.parties("legacy", 0).await;
+    fixture.legacy_seeds = true;
+
+    let legacy = legacy_contract(&fixture);
+    let freelancer_lamports = env.lamports(&fixture.freelancer.pubkey()).await;
+    env.set_account_data(
+        &fixture.address(),
+        &freelance_escrow::ID,
+        legacy_data(FreelanceContract::DISCRIMINATOR, &legacy),
+    )
+    .await;
+    let milestone = MilestoneV0 {
+        contract: fixture.address(),
+        index: 0,
+        submitted_at: 1_700_000_100,
+        approved_at: 1_700_000_200,
+        proof_uri: "ipfs://legacy".to_string(),
+        is_approved: true,
+        payment_amount: 500,
+    };
+    env.set_account_data(
+        &fixture.milestone(0),
+        &freelance_escrow::ID,
+        legacy_data(Milestone::DISCRIMINATOR, &milestone),
+    )
+    .await;
+
+    env.send(
+        &[
+            fixture.migrate_contract_ix(fixture.client.pubkey()),
+            fixture.migrate_milestone_ix(fixture.client.pubkey(), 0),
+        ],
+        &[&fixture.client],
+    )
+    .await
+    .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.version, FreelanceContract::VERSION);
+    assert!(contract.contract_type == ContractType::Milestone);
+    assert_eq!(contract.contract_id, fixture.id);
+    // Still at its id-only address, paying out of its id-seeded escrow
+    assert_eq!(contract.escrow_seed, fixture.id);
+    assert_eq!(contract.client, legacy.client);
+    assert_eq!(contract.completed_milestones, 1);
+    assert_eq!(contract.amount_released, 500);
+    assert_eq!(contract.escrow_balance, 500);
+    assert_eq!(contract.created_at, legacy.created_at);
+    assert!(contract.is_active);
+    // Migrated by the client, who names the escrow mint the legacy layout did not record
+    assert_eq!(contract.mint, fixture.mint);
+
+    // The compact layout frees rent, which goes back to the freelancer
+    let contract_size = 8 + FreelanceContract::INIT_SPACE;
+    assert_eq!(env.lamports(&fixture.address()).await, env.rent(contract_size).await);
+    let refunded = env.rent(8 + FreelanceContractV0::INIT_SPACE).await - env.rent(contract_size).await;
+    assert_eq!(env.lamports(&fixture.freelancer.pubkey()).await, freelancer_lamports + refunded);
+
+    let migrated: Milestone = env.account(&fixture.milestone(0)).await;
+    assert_eq!(migrated.version, Milestone::VERSION);
+    assert_eq!(migrated.proof_uri, "ipfs://legacy");
+    assert_eq!(migrated.amount_paid, 500);
+
+    let result = env.send(&[fixture.migrate_contract_ix(fixture.client.pubkey())], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::AccountAlreadyMigrated);
+
+    // The upgraded contract accepts the next milestone
+    env.send(&[fixture.submit_ix(1, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let submitted: Milestone = env.account(&fixture.milestone(1)).await;
+    assert_eq!(submitted.version, Milestone::VERSION);
+}
+
+#[tokio::test]
+async fn pending_legacy_milestone_is_paid_its_share_after_migration() {
+    let mut env = TestEnv::start().await;
+    let mut fixture = env.parties("legacy-pending", 500).await;
+    fixture.legacy_seeds = true;
+
+    let legacy = FreelanceContractV0 {
+        escrow_balance: 0,
+        ..legacy_contract(&fixture)
+    };
+    env.set_account_data(
+        &fixture.address(),
+        &freelance_escrow::ID,
+        legacy_data(FreelanceContract::DISCRIMINATOR, &legacy),
+    )
+    .await;
+    // Submitted but never approved, so the legacy program had not recorded its amount
+    let milestone = MilestoneV0 {
+        contract: fixture.address(),
+        index: 1,
+        submitted_at: 1_700_000_100,
+        approved_at: 0,
+        proof_uri: "ipfs://legacy".to_string(),
+        is_approved: false,
+        payment_amount: 0,
+    };
+    env.set_account_data(
+        &fixture.milestone(1),
+        &freelance_escrow::ID,
+        legacy_data(Milestone::DISCRIMINATOR, &milestone),
+    )
+    .await;
+
+    // The milestone is sized from the contract, so the contract has to be upgraded first
+    let result = env.send(&[fixture.migrate_milestone_ix(fixture.client.pubkey(), 1)], &[&fixture.client]).await;
+    assert!(result.is_err());
+    env.send(
+        &[
+            fixture.migrate_contract_ix(fixture.client.pubkey()),
+            fixture.migrate_milestone_ix(fixture.client.pubkey(), 1),
+        ],
+        &[&fixture.client],
+    )
+    .await
+    .unwrap();
+
+    let migrated: Milestone = env.account(&fixture.milestone(1)).await;
+    assert_eq!(migrated.payment_amount, 500);
+    assert_eq!(migrated.amount_paid, 0);
+
+    env.send(&[fixture.deposit_ix(500)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_ix(1)], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_completed);
+    assert_eq!(contract.amount_released, 1_000);
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn disputed_legacy_contract_moves_its_reason_to_a_dispute_account() {
+    let mut env = TestEnv::start().await;
+    let mut fixture = env.parties("legacy-disputed", 0).await;
+    fixture.legacy_seeds = true;
+
+    let legacy = FreelanceContractV0 {
+        is_active: false,
+        is_disputed: true,
+        dispute_reason: "work never delivered".to_string(),
+        escrow_balance: 0,
+        ..legacy_contract(&fixture)
+    };
+    env.set_account_data(
+        &fixture.address(),
+        &freelance_escrow::ID,
+        legacy_data(FreelanceContract::DISCRIMINATOR, &legacy),
+    )
+    .await;
+
+    env.send(&[fixture.migrate_contract_ix(fixture.client.pubkey())], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(contract.is_disputed);
+    let dispute: Dispute = env.account(&fixture.dispute()).await;
+    assert_eq!(dispute.contract, fixture.address());
+    assert_eq!(dispute.reason, "work never delivered");
+    // The evidence window opens with the migration
+    assert!(dispute.opened_at > 0);
+    assert!(dispute.opened_at <= env.now().await);
+
+    let hash = solana_sdk::hash::hashv(&[b"chat log"]).to_bytes();
+    env.send(&[fixture.submit_evidence_ix(&fixture.client, 0, hash, "ipfs://chat")], &[&fixture.client])
+        .await
+        .unwrap();
+    let dispute: Dispute = env.account(&fixture.dispute()).await;
+    assert_eq!(dispute.client_evidence_count, 1);
+}
+
+#[tokio::test]
+async fn legacy_ids_shorter_than_32_bytes_migrate_to_a_hashed_id() {
+    let mut env = TestEnv::start().await;
+    let mut fixture = env.parties("legacy-short-id", 0).await;
+    fixture.legacy_seeds = true;
+    fixture.legacy_id = Some("legacy-contract".to_string());
+    fixture.id = ContractId::from_legacy("legacy-contract").0;
+
+    let legacy = FreelanceContractV0 {
+        contract_id: "legacy-contract".to_string(),
+        ..legacy_contract(&fixture)
+    };
+    env.set_account_data(
+        &fixture.address(),
+        &freelance_escrow::ID,
+        legacy_data(FreelanceContract::DISCRIMINATOR, &legacy),
+    )
+    .await;
+    // The escrow the legacy program funded, owned by the authority seeded with the short id
+    env.associated_token_account(&fixture.escrow_authority(), 500).await;
+
+    env.send(&[fixture.migrate_contract_ix(fixture.client.pubkey())], &[&fixture.client])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.contract_id, fixture.id);
+    assert!(contract.contract_id.iter().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')));
+    assert_eq!(contract.escrow_seed_bytes(), b"legacy-contract");
+
+    // Releases still come out of the escrow seeded with the legacy id
+    env.send(&[fixture.submit_ix(1, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.approve_ix(1)], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 0);
+
+    // Longer ids could never have been a seed
+    let legacy = FreelanceContractV0 {
+        contract_id: "a".repeat(33),
+        ..legacy_contract(&fixture)
+    };
+    env.set_account_data(
+        &fixture.address(),
+        &freelance_escrow::ID,
+        legacy_data(FreelanceContract::DISCRIMINATOR, &legacy),
+    )
+    .await;
+    let result = env.send(&[fixture.migrate_contract_ix(fixture.client.pubkey())], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::InvalidContractId);
+}
+
+#[tokio::test]
+async fn unrecognized_layouts_are_rejected() {
+    let mut env = TestEnv::start().await;
+    let mut fixture = env.parties("legacy-truncated", 0).await;
+    fixture.legacy_seeds = true;
+
+    let mut data = legacy_data(FreelanceContract::DISCRIMINATOR, &legacy_contract(&fixture));
+    data.truncate(data.len() - 100);
+    env.set_account_data(&fixture.address(), &freelance_escrow::ID, data).await;
+
+    let result = env.send(&[fixture.migrate_contract_ix(fixture.client.pubkey())], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::UnknownAccountLayout);
+
+    // Legacy contract data under another account type's discriminator
+    let data = legacy_data(Milestone::DISCRIMINATOR, &legacy_contract(&fixture));
+    env.set_account_data(&fixture.address(), &freelance_escrow::ID, data).await;
+    let result = env.send(&[fixture.migrate_contract_ix(fixture.client.pubkey())], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::UnknownAccountLayout);
+}
+
+#[tokio::test]
+async fn legacy_authorization_is_upgraded_in_place() {
+    let mut env = TestEnv::start().await;
+    let auth = env.authorization("legacy-auth", 1_000, 400, 600).await;
+    let current: PaymentAuthorization = env.account(&auth.address()).await;
+
+    let result = env.send(&[auth.migrate_ix(auth.client.pubkey())], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::AccountAlreadyMigrated);
+
+    let legacy = PaymentAuthorizationV0 {
+        client: current.client,
+        contract_id: current.contract_id_str().to_string(),
+        freelancer: current.freelancer,
+        max_per_milestone: 400,
+        total_authorized: 600,
+        total_spent: 100,
+        is_active: true,
+        authorized_at: current.authorized_at,
+        bump: current.bump,
+    };
+    env.set_account_data(
+        &auth.address(),
+        &payment_auth::ID,
+        legacy_data(PaymentAuthorization::DISCRIMINATOR, &legacy),
+    )
+    .await;
+
+    env.send(&[auth.migrate_ix(auth.client.pubkey())], &[&auth.client])
+        .await
+        .unwrap();
+
+    let migrated: PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(migrated.version, PaymentAuthorization::VERSION);
+    assert_eq!(migrated.total_spent, 100);
+    assert_eq!(migrated.bump, current.bump);
+
+    // Payments resume against the migrated limits
+    env.send(&[auth.process_payment_ix("m-1", 400)], &[&auth.client])
+        .await
+        .unwrap();
+    let result = env.send(&[auth.process_payment_ix("m-2", 200)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::ExceedsTotal);
+}
+
+#[tokio::test]
+async fn legacy_authorization_with_a_short_id_migrates_to_a_hashed_id() {
+    let mut env = TestEnv::start().await;
+    let mut auth = env.authorization("legacy-short-auth", 1_000, 400, 600).await;
+    let current: PaymentAuthorization = env.account(&auth.address()).await;
+
+    auth.legacy_id = Some("legacy-auth".to_string());
+    auth.contract_id = ContractId::from_legacy("legacy-auth").0;
+    let legacy = PaymentAuthorizationV0 {
+        client: current.client,
+        contract_id: "legacy-auth".to_string(),
+        freelancer: current.freelancer,
+        max_per_milestone: 400,
+        total_authorized: 600,
+        total_spent: 0,
+        is_active: true,
+        authorized_at: current.authorized_at,
+        bump: pda::legacy_payment_authorization(&auth.client.pubkey(), b"legacy-auth").1,
+    };
+    env.set_account_data(
+        &auth.address(),
+        &payment_auth::ID,
+        legacy_data(PaymentAuthorization::DISCRIMINATOR, &legacy),
+    )
+    .await;
+
+    env.send(&[auth.migrate_ix(auth.client.pubkey())], &[&auth.client])
+        .await
+        .unwrap();
+
+    let migrated: PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(migrated.contract_id, auth.contract_id);
+    assert_eq!(migrated.id_seed_bytes(), b"legacy-auth");
+    assert_eq!(migrated.id_seed_len, 11);
+
+    // Payments still find the authorization at the address seeded with the legacy id
+    env.send(&[auth.process_payment_ix("m-1", 400)], &[&auth.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&auth.freelancer_token).await, 400);
+
+    // Longer ids could never have been a seed
+    let legacy = PaymentAuthorizationV0 {
+        contract_id: "a".repeat(33),
+        ..legacy
+    };
+    env.set_account_data(
+        &auth.address(),
+        &payment_auth::ID,
+        legacy_data(PaymentAuthorization::DISCRIMINATOR, &legacy),
+    )
+    .await;
+    let result = env.send(&[auth.migrate_ix(auth.client.pubkey())], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::InvalidContractId);
+}
@@ -0,0 +1,104 @@
+//! Payment authorization limits and lifecycle, exercised alongside the escrow program.
+
+mod fixtures;
+
+use fixtures::*;
+use payment_auth::{ErrorCode, PaymentAuthorization};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn milestone_payments_are_capped_per_milestone_and_in_total() {
+    let mut env = TestEnv::start().await;
+    let auth = env.authorization("limits", 1_000, 400, 600).await;
+
+    env.send(&[auth.process_payment_ix("m-1", 400)], &[&auth.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&auth.freelancer_token).await, 400);
+
+    let result = env.send(&[auth.process_payment_ix("m-2", 401)], &[&auth.client]).await;
+    assert_auth_error(result, ErrorCode::ExceedsPerMilestone);
+
+    let result = env.send(&[auth.process_payment_ix("m-2", 300)], &[&auth.client]).await;
+    assert_auth_error(result, ErrorCode::ExceedsTotal);
+
+    env.send(&[auth.process_payment_ix("m-2", 200)], &[&auth.client])
+        .await
+        .unwrap();
+
+    let authorization: PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(authorization.total_spent, 600);
+    assert_eq!(env.token_balance(&auth.client_token).await, 400);
+}
+
+#[tokio::test]
+async fn update_raises_the_limits_of_an_active_authorization() {
+    let mut env = TestEnv::start().await;
+    let auth = env.authorization("update", 1_000, 100, 200).await;
+    env.send(&[auth.process_payment_ix("m-1", 100)], &[&auth.client])
+        .await
+        .unwrap();
+
+    // The per-milestone cap cannot exceed what is left of the total
+    let result = env.send(&[auth.update_ix(Some(150), None)], &[&auth.client]).await;
+    assert_auth_error(result, ErrorCode::ExceedsTotal);
+
+    env.send(&[auth.update_ix(None, Some(300))], &[&auth.client])
+        .await
+        .unwrap();
+    env.send(&[auth.update_ix(Some(250), None)], &[&auth.client])
+        .await
+        .unwrap();
+    env.send(&[auth.process_payment_ix("m-2", 250)], &[&auth.client])
+        .await
+        .unwrap();
+
+    let authorization: PaymentAuthorization = env.account(&auth.address()).await;
+    assert_eq!(authorization.max_per_milestone, 250);
+    assert_eq!(authorization.total_authorized, 500);
+    assert_eq!(authorization.total_spent, 350);
+}
+
+#[tokio::test]
+async fn payments_go_only_to_the_authorized_freelancer() {
+    let mut env = TestEnv::start().await;
+    let auth = env.authorization("freelancer", 1_000, 500, 1_000).await;
+    let stranger = Keypair::new();
+    env.associated_token_account(&stranger.pubkey(), 0).await;
+
+    let result = env
+        .send(&[auth.process_payment_to_ix(stranger.pubkey(), "m-1", 100)], &[&auth.client])
+        .await;
+    assert_auth_error(result, ErrorCode::UnauthorizedFreelancer);
+}
+
+#[tokio::test]
+async fn revoked_and_frozen_authorizations_stop_paying() {
+    let mut env = TestEnv::start().await;
+    let revoked = env.authorization("revoked", 1_000, 500, 1_000).await;
+    let frozen = env.authorization("frozen", 1_000, 500, 1_000).await;
+    let stranger = env.funded_wallet().await;
+
+    env.send(&[revoked.revoke_ix()], &[&revoked.client])
+        .await
+        .unwrap();
+    // Only the config's freeze admins can freeze
+    let result = env.send(&[frozen.freeze_ix(stranger.pubkey())], &[&stranger]).await;
+    assert_auth_error(result, ErrorCode::UnauthorizedAdmin);
+    let admin = Keypair::from_bytes(&env.freeze_admin.to_bytes()).unwrap();
+    env.send(&[frozen.freeze_ix(admin.pubkey())], &[&admin])
+        .await
+        .unwrap();
+
+    for auth in [&revoked, &frozen] {
+        let authorization: PaymentAuthorization = env.account(&auth.address()).await;
+        assert!(!authorization.is_active);
+
+        let result = env.send(&[auth.process_payment_ix("m-1", 100)], &[&auth.client]).await;
+        assert_auth_error(result, ErrorCode::AuthorizationInactive);
+
+        let result = env.send(&[auth.update_ix(None, Some(100))], &[&auth.client]).await;
+        assert_auth_error(result, ErrorCode::AuthorizationInactive);
+    }
+}
@@ -0,0 +1,204 @@
+//! Wallets on the payment_auth sanctions blocklist can neither fund nor receive payments
+//! in either program, and lifting the entry restores them.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{ErrorCode, FreelanceContract, Payee};
+use payment_auth::SanctionEntry;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn sanctioned_freelancer_is_not_paid_until_removed() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let fixture = env.contract("sanctioned-freelancer", 1_000, 2).await;
+    env.send(&[fixture.deposit_ix(500)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+
+    let freelancer = fixture.freelancer.pubkey();
+    env.send(&[authorization::add_sanctioned_wallet(authority, authority, freelancer)], &[&compliance])
+        .await
+        .unwrap();
+    let entry: SanctionEntry = env.account(&pda::sanction_entry(&freelancer).0).await;
+    assert_eq!(entry.wallet, freelancer);
+    assert_eq!(entry.added_by, compliance.pubkey());
+
+    let result = env.send(&[fixture.approve_ix(0)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+    let result = env.send(&[fixture.deposit_ix(500)], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+
+    let compliance_lamports = env.lamports(&compliance.pubkey()).await;
+    env.send(&[authorization::remove_sanctioned_wallet(authority, freelancer, authority)], &[&compliance])
+        .await
+        .unwrap();
+    assert!(env
+        .context
+        .banks_client
+        .get_account(pda::sanction_entry(&freelancer).0)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(env.lamports(&compliance.pubkey()).await > compliance_lamports);
+
+    env.send(&[fixture.approve_ix(0)], &[&fixture.client])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn sanctioned_client_refund_stays_in_escrow() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let fixture = env.funded_contract("sanctioned-client", 1_000, 1).await;
+
+    env.send(&[authorization::add_sanctioned_wallet(authority, authority, fixture.client.pubkey())], &[&compliance])
+        .await
+        .unwrap();
+    let result = env.send(&[fixture.dispute_ix("work never delivered")], &[&fixture.client]).await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert!(!contract.is_disputed);
+    assert_eq!(contract.escrow_balance, 1_000);
+    assert_eq!(env.token_balance(&fixture.escrow_token()).await, 1_000);
+}
+
+#[tokio::test]
+async fn stream_timesheet_and_bonus_payouts_check_the_blocklist() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+
+    let stream = env.parties("sanctioned-stream", 1_000).await;
+    let start = env.now().await;
+    env.send(&[stream.create_streaming_ix(1_000, start, start + 1_000)], &[&stream.freelancer])
+        .await
+        .unwrap();
+    env.send(&[stream.deposit_ix(1_000)], &[&stream.client])
+        .await
+        .unwrap();
+    let hourly = env.parties("sanctioned-hourly", 1_000).await;
+    env.send(&[hourly.create_hourly_ix(1_000, 25, 40)], &[&hourly.freelancer])
+        .await
+        .unwrap();
+    env.send(&[hourly.deposit_ix(1_000)], &[&hourly.client])
+        .await
+        .unwrap();
+    env.send(&[hourly.submit_timesheet_ix(0, 10)], &[&hourly.freelancer])
+        .await
+        .unwrap();
+    let milestone = env.funded_contract("sanctioned-bonus", 1_000, 1).await;
+
+    for wallet in [stream.freelancer.pubkey(), hourly.freelancer.pubkey(), milestone.client.pubkey()] {
+        env.send(&[authorization::add_sanctioned_wallet(authority, authority, wallet)], &[&compliance])
+            .await
+            .unwrap();
+    }
+
+    env.set_time(start + 500);
+    let result = env.send(&[stream.withdraw_vested_ix()], &[&stream.freelancer]).await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+    let result = env.send(&[hourly.approve_timesheet_ix(0)], &[&hourly.client]).await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+    for from_escrow in [false, true] {
+        let result = env
+            .send(&[milestone.pay_bonus_ix(100, from_escrow, &ReleaseOptions::default())], &[&milestone.client])
+            .await;
+        assert_escrow_error(result, ErrorCode::SanctionedParty);
+    }
+    assert_eq!(env.token_balance(&stream.freelancer_token).await, 0);
+    assert_eq!(env.token_balance(&hourly.freelancer_token).await, 0);
+    assert_eq!(env.token_balance(&milestone.freelancer_token).await, 0);
+}
+
+#[tokio::test]
+async fn split_releases_check_every_payee() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let fixture = env.contract("sanctioned-payee", 1_000, 1).await;
+    let partner = Keypair::new();
+    let partner_share = env.token_account(&partner.pubkey(), 0).await;
+    let payees = vec![
+        Payee {
+            wallet: fixture.freelancer.pubkey(),
+            share_bps: 5_000,
+        },
+        Payee {
+            wallet: partner.pubkey(),
+            share_bps: 5_000,
+        },
+    ];
+    env.send(&[fixture.set_payee_split_ix(payees)], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    env.send(&[authorization::add_sanctioned_wallet(authority, authority, partner.pubkey())], &[&compliance])
+        .await
+        .unwrap();
+
+    let release = ReleaseOptions {
+        payee_token_accounts: vec![fixture.freelancer_token, partner_share],
+        payee_wallets: vec![fixture.freelancer.pubkey(), partner.pubkey()],
+        ..ReleaseOptions::default()
+    };
+    let result = env
+        .send(
+            &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &release)],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::SanctionedParty);
+
+    // Blocklist entries must be passed in split order
+    let swapped = ReleaseOptions {
+        payee_wallets: vec![partner.pubkey(), fixture.freelancer.pubkey()],
+        ..release
+    };
+    let result = env
+        .send(
+            &[fixture.approve_with_ix(fixture.client.pubkey(), 0, None, &swapped)],
+            &[&fixture.client],
+        )
+        .await;
+    assert_escrow_error(result, ErrorCode::PayeeAccountsMismatch);
+}
+
+#[tokio::test]
+async fn authorized_payments_refuse_sanctioned_parties() {
+    let mut env = TestEnv::start().await;
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let auth = env.authorization("sanctioned-auth", 1_000, 500, 1_000).await;
+
+    env.send(&[authorization::add_sanctioned_wallet(authority, authority, auth.freelancer)], &[&compliance])
+        .await
+        .unwrap();
+    let result = env.send(&[auth.process_payment_ix("m-1", 100)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::SanctionedParty);
+
+    env.send(&[authorization::remove_sanctioned_wallet(authority, auth.freelancer, authority)], &[&compliance])
+        .await
+        .unwrap();
+    env.send(&[authorization::add_sanctioned_wallet(authority, authority, auth.client.pubkey())], &[&compliance])
+        .await
+        .unwrap();
+    let result = env.send(&[auth.process_payment_ix("m-1", 100)], &[&auth.client]).await;
+    assert_auth_error(result, payment_auth::ErrorCode::SanctionedParty);
+    assert_eq!(env.token_balance(&auth.freelancer_token).await, 0);
+}
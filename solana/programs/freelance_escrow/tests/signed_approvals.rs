@@ -0,0 +1,113 @@
+//! Clients without SOL approve milestones by signing a message off-chain; a relayer submits it
+//! behind an Ed25519 program check, and the contract's approval nonce stops replays. The relayer
+//! picks neither the milestone nor where the release goes.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{ErrorCode, FreelanceContract, Milestone};
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn relayer_submits_a_client_signed_approval_once() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("signed-approval", 1_000, 2).await;
+    let relayer = env.funded_wallet().await;
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let expires_at = env.now().await + 3_600;
+
+    // 20% of the 500 milestone; the client never signs the transaction
+    let approval = fixture.approve_signed_ixs(relayer.pubkey(), 0, 2_000, 100, 0, expires_at);
+    env.send(&approval, &[&relayer]).await.unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 100);
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.approval_nonce, 1);
+
+    let result = env.send(&approval, &[&relayer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidApprovalNonce);
+
+    env.send(
+        &fixture.approve_signed_ixs(relayer.pubkey(), 0, 10_000, 400, 1, expires_at),
+        &[&relayer],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+}
+
+#[tokio::test]
+async fn signed_approvals_bind_the_client_amount_and_expiry() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("signed-approval-checks", 1_000, 1).await;
+    let relayer = env.funded_wallet().await;
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer])
+        .await
+        .unwrap();
+    let now = env.now().await;
+    env.set_time(now);
+
+    // Signed for 100 but submitted as a full release
+    let result = env
+        .send(&fixture.approve_signed_ixs(relayer.pubkey(), 0, 10_000, 100, 0, now + 60), &[&relayer])
+        .await;
+    assert_escrow_error(result, ErrorCode::InvalidApprovalSignature);
+
+    // Signed by someone other than the client
+    let mut forged = fixture.approve_signed_ixs(relayer.pubkey(), 0, 10_000, 1_000, 0, now + 60);
+    let message = freelance_escrow::signed_approval_message(&fixture.address(), 0, 1_000, 0, now + 60);
+    forged[0] = ed25519_ix(&relayer, &message);
+    let result = env.send(&forged, &[&relayer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidApprovalSignature);
+
+    // Without the Ed25519 instruction in front
+    let approval = fixture.approve_signed_ixs(relayer.pubkey(), 0, 10_000, 1_000, 0, now + 60);
+    let result = env.send(&approval[1..], &[&relayer]).await;
+    assert_escrow_error(result, ErrorCode::InvalidApprovalSignature);
+
+    env.set_time(now + 61);
+    let result = env.send(&approval, &[&relayer]).await;
+    assert_escrow_error(result, ErrorCode::ApprovalSignatureExpired);
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.approval_nonce, 0);
+    assert_eq!(contract.escrow_balance, 1_000);
+}
+
+#[tokio::test]
+async fn relayers_cannot_redirect_a_signed_approval() {
+    let mut env = TestEnv::start().await;
+    let fixture = env.funded_contract("signed-approval-accounts", 1_000, 2).await;
+    let relayer = env.funded_wallet().await;
+    for index in 0..2 {
+        env.send(&[fixture.submit_ix(index, "ipfs://proof")], &[&fixture.freelancer])
+            .await
+            .unwrap();
+    }
+    let expires_at = env.now().await + 3_600;
+    let approval = fixture.approve_signed_ixs(relayer.pubkey(), 0, 10_000, 500, 0, expires_at);
+
+    // The release goes to the freelancer, not to an account the relayer picks
+    let relayer_token = env.token_account(&relayer.pubkey(), 0).await;
+    let redirected = vec![
+        approval[0].clone(),
+        replace_account(approval[1].clone(), fixture.freelancer_token, relayer_token),
+    ];
+    let result = env.send(&redirected, &[&relayer]).await;
+    assert!(result.is_err());
+
+    // The signature covers milestone 0, so it cannot pay out another milestone of the contract
+    let mismatched = vec![
+        approval[0].clone(),
+        replace_account(approval[1].clone(), fixture.milestone(0), fixture.milestone(1)),
+    ];
+    let result = env.send(&mismatched, &[&relayer]).await;
+    assert!(result.is_err());
+
+    env.send(&approval, &[&relayer]).await.unwrap();
+    assert_eq!(env.token_balance(&fixture.freelancer_token).await, 500);
+    assert_eq!(env.token_balance(&relayer_token).await, 0);
+    let milestone: Milestone = env.account(&fixture.milestone(1)).await;
+    assert!(!milestone.is_approved);
+}
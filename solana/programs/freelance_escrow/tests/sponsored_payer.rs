@@ -0,0 +1,101 @@
+//! A sponsoring wallet can pay the rent of the accounts participants create, and rent of
+//! closable accounts goes back to whoever paid it rather than to the signing authority.
+
+mod fixtures;
+
+use fixtures::*;
+use freelance_escrow::{FreelanceContract, PendingConfigChange};
+use payment_auth::SanctionEntry;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+#[tokio::test]
+async fn sponsor_pays_the_rent_of_participant_accounts() {
+    let mut env = TestEnv::start().await;
+    let sponsor = env.funded_wallet().await;
+    let mut fixture = env.parties("sponsored", 1_000).await;
+    fixture.sponsor = Some(sponsor.pubkey());
+    let freelancer_lamports = env.lamports(&fixture.freelancer.pubkey()).await;
+    let client_lamports = env.lamports(&fixture.client.pubkey()).await;
+    let sponsor_lamports = env.lamports(&sponsor.pubkey()).await;
+
+    env.send(&[fixture.create_ix(1_000, 2)], &[&fixture.freelancer, &sponsor])
+        .await
+        .unwrap();
+    env.send(&[fixture.deposit_ix(1_000)], &[&fixture.client, &sponsor])
+        .await
+        .unwrap();
+    env.send(&[fixture.submit_ix(0, "ipfs://proof")], &[&fixture.freelancer, &sponsor])
+        .await
+        .unwrap();
+
+    let contract: FreelanceContract = env.account(&fixture.address()).await;
+    assert_eq!(contract.escrow_balance, 1_000);
+    assert_eq!(env.lamports(&fixture.freelancer.pubkey()).await, freelancer_lamports);
+    assert_eq!(env.lamports(&fixture.client.pubkey()).await, client_lamports);
+    let rent = env.lamports(&fixture.address()).await
+        + env.lamports(&fixture.escrow_token()).await
+        + env.lamports(&fixture.milestone(0)).await;
+    assert_eq!(env.lamports(&sponsor.pubkey()).await, sponsor_lamports - rent);
+
+    // The sponsor has to sign for its lamports to be spent
+    let result = env.send(&[fixture.submit_ix(1, "ipfs://proof")], &[&fixture.freelancer]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn closed_accounts_refund_the_wallet_that_paid_their_rent() {
+    let mut env = TestEnv::start().await;
+    let admin = Keypair::from_bytes(&env.admin.to_bytes()).unwrap();
+    let compliance = Keypair::from_bytes(&env.compliance.to_bytes()).unwrap();
+    let authority = compliance.pubkey();
+    let sponsor = env.funded_wallet().await;
+    let wallet = Keypair::new().pubkey();
+
+    env.send(
+        &[authorization::add_sanctioned_wallet(authority, sponsor.pubkey(), wallet)],
+        &[&compliance, &sponsor],
+    )
+    .await
+    .unwrap();
+    let entry: SanctionEntry = env.account(&pda::sanction_entry(&wallet).0).await;
+    assert_eq!(entry.added_by, compliance.pubkey());
+    assert_eq!(entry.rent_payer, sponsor.pubkey());
+
+    let result = env
+        .send(&[authorization::remove_sanctioned_wallet(authority, wallet, authority)], &[&compliance])
+        .await;
+    assert!(result.is_err());
+    let sponsor_lamports = env.lamports(&sponsor.pubkey()).await;
+    let entry_lamports = env.lamports(&pda::sanction_entry(&wallet).0).await;
+    env.send(
+        &[authorization::remove_sanctioned_wallet(authority, wallet, sponsor.pubkey())],
+        &[&compliance],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.lamports(&sponsor.pubkey()).await, sponsor_lamports + entry_lamports);
+
+    // The queued change records the admin as both proposer and rent payer
+    env.send(
+        &[escrow::queue_config_change(
+            admin.pubkey(),
+            admin.pubkey(),
+            0,
+            freelance_escrow::ConfigChange::TimelockDelay(freelance_escrow::MIN_TIMELOCK_DELAY),
+        )],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+    let pending: PendingConfigChange = env.account(&pda::config_change(0).0).await;
+    assert_eq!(pending.proposer, admin.pubkey());
+    assert_eq!(pending.rent_payer, admin.pubkey());
+    let result = env
+        .send(&[escrow::cancel_config_change(admin.pubkey(), 0, sponsor.pubkey())], &[&admin])
+        .await;
+    assert!(result.is_err());
+    env.send(&[escrow::cancel_config_change(admin.pubkey(), 0, admin.pubkey())], &[&admin])
+        .await
+        .unwrap();
+}
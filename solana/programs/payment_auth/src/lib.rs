@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, TokenAccount, Token, Transfer};
+use anchor_lang::solana_program::{bpf_loader_upgradeable, hash};
+use anchor_lang::{system_program, Discriminator};
+use anchor_spl::token::{self, Approve, TokenAccount, Token, Transfer};
 
-declare_id!("SmartF1oPaymentAuth1111111111111111111111");
+declare_id!("vM3ni7Fyg5D478g48dmVVVNuF1sFQ6RMYGMhg91iJmw");
 
 #[program]
 pub mod payment_auth {
@@ -10,7 +12,7 @@ pub mod payment_auth {
     /// Create a payment authorization for a freelance contract
     pub fn create_payment_authorization(
         ctx: Context<CreateAuth>,
-        contract_id: String,
+        contract_id: [u8; 32],
         max_per_milestone: u64,
         total_authorized: u64,
     ) -> Result<()> {
@@ -21,17 +23,43 @@ pub mod payment_auth {
         require!(max_per_milestone > 0, ErrorCode::InvalidAmount);
         require!(total_authorized > 0, ErrorCode::InvalidAmount);
         require!(max_per_milestone <= total_authorized, ErrorCode::ExceedsTotal);
+        require!(
+            contract_id.iter().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')),
+            ErrorCode::InvalidContractId
+        );
+
+        // Large authorizations need both participants to hold a current KYC attestation
+        ctx.accounts.config.require_kyc(
+            total_authorized,
+            &[&ctx.accounts.client_kyc, &ctx.accounts.freelancer_kyc],
+            clock.unix_timestamp,
+        )?;
 
         // Initialize payment authorization
+        auth.version = PaymentAuthorization::VERSION;
         auth.client = ctx.accounts.client.key();
         auth.contract_id = contract_id;
         auth.freelancer = ctx.accounts.freelancer.key();
         auth.max_per_milestone = max_per_milestone;
         auth.total_authorized = total_authorized;
         auth.total_spent = 0;
+        auth.escrow_token_account = Pubkey::default();
         auth.is_active = true;
         auth.authorized_at = clock.unix_timestamp;
-        auth.bump = *ctx.bumps.get("payment_authorization").unwrap();
+        auth.bump = ctx.bumps.payment_authorization;
+        auth.co_signer = Pubkey::default();
+        auth.co_sign_threshold = 0;
+        auth.id_seed = contract_id;
+        auth.id_seed_len = 0;
+
+        emit!(AuthorizationCreated {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            freelancer: auth.freelancer,
+            max_per_milestone,
+            total_authorized,
+            timestamp: clock.unix_timestamp,
+        });
 
         msg!("Payment authorization created: {} USDC authorized", total_authorized);
         Ok(())
@@ -57,6 +85,17 @@ pub mod payment_auth {
         require!(auth.client == ctx.accounts.client.key(), ErrorCode::UnauthorizedClient);
         require!(auth.freelancer == ctx.accounts.freelancer.key(), ErrorCode::UnauthorizedFreelancer);
 
+        // Once payments would total more than the co-signer threshold they need the second key as well,
+        // so a large payment split into smaller ones still needs it
+        if auth.co_signer != Pubkey::default() && auth.total_spent + amount > auth.co_sign_threshold {
+            let co_signer = ctx.accounts.co_signer.as_ref().ok_or(ErrorCode::CoSignerRequired)?;
+            require_keys_eq!(co_signer.key(), auth.co_signer, ErrorCode::CoSignerRequired);
+        }
+
+        // Neither the paying client nor the receiving wallet may be on the blocklist
+        require!(ctx.accounts.client_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+        require!(ctx.accounts.recipient_sanction.data_is_empty(), ErrorCode::SanctionedParty);
+
         // Transfer USDC from client to freelancer
         let cpi_accounts = Transfer {
             from: ctx.accounts.client_token_account.to_account_info(),
@@ -71,10 +110,109 @@ pub mod payment_auth {
         // Update authorization state
         auth.total_spent += amount;
 
+        emit!(MilestonePaymentProcessed {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            milestone_id: milestone_id.clone(),
+            amount,
+            total_spent: auth.total_spent,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         msg!("Milestone payment processed: {} USDC paid for milestone {}", amount, milestone_id);
         Ok(())
     }
 
+    /// Link the authorization to a freelance escrow so the escrow can pull milestone funding.
+    /// Delegates the remaining authorized amount on the client's token account to this PDA.
+    pub fn link_escrow(ctx: Context<LinkEscrow>) -> Result<()> {
+        let auth = &mut ctx.accounts.payment_authorization;
+
+        require!(auth.client == ctx.accounts.client.key(), ErrorCode::UnauthorizedClient);
+        require!(auth.is_active, ErrorCode::AuthorizationInactive);
+
+        let cpi_accounts = Approve {
+            to: ctx.accounts.client_token_account.to_account_info(),
+            delegate: auth.to_account_info(),
+            authority: ctx.accounts.client.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        let allowance = auth.total_authorized - auth.total_spent;
+        token::approve(cpi_ctx, allowance)?;
+
+        auth.escrow_token_account = ctx.accounts.escrow_token_account.key();
+
+        emit!(EscrowLinked {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            escrow_token_account: auth.escrow_token_account,
+            allowance,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Payment authorization for contract {} linked to escrow {}", auth.contract_id_str(), auth.escrow_token_account);
+        Ok(())
+    }
+
+    /// Fund the linked escrow for a milestone; called via CPI by the escrow program
+    pub fn fund_escrow(
+        ctx: Context<FundEscrow>,
+        milestone_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        let auth = &mut ctx.accounts.payment_authorization;
+
+        // Validate authorization is active and linked to this escrow
+        require!(auth.is_active, ErrorCode::AuthorizationInactive);
+        require!(auth.escrow_token_account == ctx.accounts.escrow_token_account.key(), ErrorCode::EscrowNotLinked);
+        require!(ctx.accounts.escrow_token_account.owner == ctx.accounts.escrow_authority.key(), ErrorCode::EscrowNotLinked);
+
+        // Check payment limits
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount <= auth.max_per_milestone, ErrorCode::ExceedsPerMilestone);
+        require!(auth.total_spent + amount <= auth.total_authorized, ErrorCode::ExceedsTotal);
+
+        // Transfer USDC from client to escrow using the delegated allowance
+        let client = auth.client;
+        let contract_id = auth.contract_id;
+        let id_seed = auth.id_seed;
+        let id_seed_len = auth.id_seed_bytes().len();
+        let seeds = &[
+            b"payment_auth",
+            client.as_ref(),
+            &id_seed[..id_seed_len],
+            &[auth.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.client_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: auth.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        // Update authorization state
+        auth.total_spent += amount;
+
+        emit!(EscrowFunded {
+            client,
+            contract_id,
+            milestone_id: milestone_id.clone(),
+            amount,
+            total_spent: auth.total_spent,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Escrow funded: {} USDC for milestone {}", amount, milestone_id);
+        Ok(())
+    }
+
     /// Revoke payment authorization
     pub fn revoke_authorization(ctx: Context<RevokeAuth>) -> Result<()> {
         let auth = &mut ctx.accounts.payment_authorization;
@@ -84,7 +222,13 @@ pub mod payment_auth {
 
         auth.is_active = false;
 
-        msg!("Payment authorization revoked for contract {}", auth.contract_id);
+        emit!(AuthorizationRevoked {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Payment authorization revoked for contract {}", auth.contract_id_str());
         Ok(())
     }
 
@@ -107,45 +251,401 @@ pub mod payment_auth {
             auth.max_per_milestone = new_max;
         }
 
-        // Add additional authorized amount if provided
+        // Add additional authorized amount if provided; a top-up past the KYC threshold needs the
+        // same attestations as creating that large an authorization
         if let Some(additional) = additional_authorized {
             require!(additional > 0, ErrorCode::InvalidAmount);
-            auth.total_authorized += additional;
+            let total_authorized = auth
+                .total_authorized
+                .checked_add(additional)
+                .ok_or(ErrorCode::InvalidAmount)?;
+            ctx.accounts.config.require_kyc(
+                total_authorized,
+                &[&ctx.accounts.client_kyc, &ctx.accounts.freelancer_kyc],
+                Clock::get()?.unix_timestamp,
+            )?;
+            auth.total_authorized = total_authorized;
         }
 
-        msg!("Payment authorization updated for contract {}", auth.contract_id);
+        emit!(AuthorizationUpdated {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            max_per_milestone: auth.max_per_milestone,
+            total_authorized: auth.total_authorized,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Payment authorization updated for contract {}", auth.contract_id_str());
         Ok(())
     }
 
-    /// Emergency freeze authorization (admin only)
+    /// Emergency freeze authorization (freeze admins only)
     pub fn freeze_authorization(ctx: Context<FreezeAuth>) -> Result<()> {
+        require!(
+            ctx.accounts.config.freeze_admins.contains(&ctx.accounts.admin.key()),
+            ErrorCode::UnauthorizedAdmin
+        );
+
         let auth = &mut ctx.accounts.payment_authorization;
-        
-        // In production, verify admin authority here
         auth.is_active = false;
 
-        msg!("Payment authorization frozen for contract {}", auth.contract_id);
+        emit!(AuthorizationFrozen {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            admin: ctx.accounts.admin.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Payment authorization frozen for contract {}", auth.contract_id_str());
+        Ok(())
+    }
+
+    /// Register a second key that must co-sign payments taking the total spent above `threshold`; signed by the
+    /// client and the co-signer
+    pub fn set_co_signer(ctx: Context<SetCoSigner>, threshold: u64) -> Result<()> {
+        let auth = &mut ctx.accounts.payment_authorization;
+        let co_signer = ctx.accounts.co_signer.key();
+
+        require!(auth.co_signer == Pubkey::default(), ErrorCode::CoSignerAlreadySet);
+        require_keys_neq!(co_signer, auth.client, ErrorCode::InvalidCoSigner);
+
+        auth.co_signer = co_signer;
+        auth.co_sign_threshold = threshold;
+
+        emit!(AuthorizationCoSignerUpdated {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            co_signer,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Co-signer registered for contract {}", auth.contract_id_str());
+        Ok(())
+    }
+
+    /// Replace the co-signer key or threshold; signed by the client and the current co-signer
+    pub fn update_co_signer(
+        ctx: Context<UpdateCoSigner>,
+        new_co_signer: Pubkey,
+        threshold: u64,
+    ) -> Result<()> {
+        let auth = &mut ctx.accounts.payment_authorization;
+
+        require_keys_eq!(ctx.accounts.co_signer.key(), auth.co_signer, ErrorCode::UnauthorizedCoSigner);
+        require_keys_neq!(new_co_signer, auth.client, ErrorCode::InvalidCoSigner);
+
+        auth.co_signer = new_co_signer;
+        auth.co_sign_threshold = threshold;
+
+        emit!(AuthorizationCoSignerUpdated {
+            client: auth.client,
+            contract_id: auth.contract_id,
+            co_signer: new_co_signer,
+            threshold,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Co-signer updated for contract {}", auth.contract_id_str());
+        Ok(())
+    }
+
+    /// Rewrite an authorization created before accounts were versioned in the current layout
+    pub fn migrate_authorization(ctx: Context<MigrateAuth>) -> Result<()> {
+        let account = &ctx.accounts.payment_authorization;
+        let legacy_len = 8 + PaymentAuthorizationV0::INIT_SPACE;
+        let current_len = 8 + PaymentAuthorization::INIT_SPACE;
+
+        let legacy = {
+            let data = account.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == PaymentAuthorization::DISCRIMINATOR,
+                ErrorCode::UnknownAccountLayout
+            );
+            require!(data.len() != current_len, ErrorCode::AccountAlreadyMigrated);
+            require!(data.len() == legacy_len, ErrorCode::UnknownAccountLayout);
+            PaymentAuthorizationV0::deserialize(&mut &data[8..])?
+        };
+        // Legacy ids are the PDA seed, so they can be at most 32 bytes; shorter ones are zero-padded
+        // into id_seed and the authorization gets the hashed id its escrow contract migrates to
+        let legacy_id = legacy.contract_id.as_bytes();
+        require!(!legacy_id.is_empty() && legacy_id.len() <= 32, ErrorCode::InvalidContractId);
+        let mut id_seed = [0; 32];
+        id_seed[..legacy_id.len()].copy_from_slice(legacy_id);
+
+        // The payer covers the rent for the larger layout
+        let rent = Rent::get()?.minimum_balance(current_len);
+        let top_up = rent.saturating_sub(account.lamports());
+        if top_up > 0 {
+            let cpi_accounts = system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: account.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+            system_program::transfer(cpi_ctx, top_up)?;
+        }
+
+        account.realloc(current_len, false)?;
+        let auth = PaymentAuthorization {
+            version: PaymentAuthorization::VERSION,
+            client: legacy.client,
+            contract_id: PaymentAuthorization::migrated_contract_id(&legacy.contract_id),
+            freelancer: legacy.freelancer,
+            max_per_milestone: legacy.max_per_milestone,
+            total_authorized: legacy.total_authorized,
+            total_spent: legacy.total_spent,
+            escrow_token_account: Pubkey::default(),
+            is_active: legacy.is_active,
+            authorized_at: legacy.authorized_at,
+            bump: legacy.bump,
+            co_signer: Pubkey::default(),
+            co_sign_threshold: 0,
+            id_seed,
+            id_seed_len: if legacy_id.len() == 32 { 0 } else { legacy_id.len() as u8 },
+            reserved: [0; 23],
+        };
+        let mut data = account.try_borrow_mut_data()?;
+        auth.try_serialize(&mut &mut data[..])?;
+
+        msg!("Payment authorization migrated for contract {}", auth.contract_id_str());
+        Ok(())
+    }
+
+    /// One-time setup of the program config by the upgrade authority; later changes go through the timelock
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        admin: Pubkey,
+        freeze_admins: Vec<Pubkey>,
+        compliance_authority: Pubkey,
+        timelock_delay: i64,
+    ) -> Result<()> {
+        require!(freeze_admins.len() <= MAX_FREEZE_ADMINS, ErrorCode::TooManyFreezeAdmins);
+        require!(
+            (MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(&timelock_delay),
+            ErrorCode::InvalidTimelockDelay
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.version = AuthConfig::VERSION;
+        config.admin = admin;
+        config.freeze_admins = freeze_admins;
+        config.compliance_authority = compliance_authority;
+        config.timelock_delay = timelock_delay;
+        config.change_count = 0;
+        // KYC gating stays off until a `KycRequirement` change sets a threshold
+        config.kyc_threshold = 0;
+        config.kyc_min_level = 0;
+        config.reserved = [0; 23];
+        Ok(())
+    }
+
+    /// Admin queues a config change that anyone can execute once the timelock delay has passed
+    pub fn queue_config_change(ctx: Context<QueueConfigChange>, change: ConfigChange) -> Result<()> {
+        match &change {
+            ConfigChange::FreezeAdmins(admins) => {
+                require!(admins.len() <= MAX_FREEZE_ADMINS, ErrorCode::TooManyFreezeAdmins)
+            }
+            ConfigChange::TimelockDelay(delay) => {
+                require!((MIN_TIMELOCK_DELAY..=MAX_TIMELOCK_DELAY).contains(delay), ErrorCode::InvalidTimelockDelay)
+            }
+            ConfigChange::Admin(_)
+            | ConfigChange::ComplianceAuthority(_)
+            | ConfigChange::KycRequirement { .. } => {}
+        }
+
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        let pending = &mut ctx.accounts.pending_change;
+        pending.version = PendingConfigChange::VERSION;
+        pending.change_id = config.change_count;
+        pending.change = change.clone();
+        pending.proposer = ctx.accounts.admin.key();
+        pending.rent_payer = ctx.accounts.payer.key();
+        pending.queued_at = now;
+        pending.eta = now + config.timelock_delay;
+        pending.reserved = [0; 32];
+        config.change_count += 1;
+
+        emit!(ConfigChangeQueued {
+            change_id: pending.change_id,
+            change,
+            eta: pending.eta,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Apply a queued change after its delay; permissionless so a change cannot be held back
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        let pending = &ctx.accounts.pending_change;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending.eta, ErrorCode::TimelockNotElapsed);
+
+        let config = &mut ctx.accounts.config;
+        match &pending.change {
+            ConfigChange::Admin(admin) => config.admin = *admin,
+            ConfigChange::FreezeAdmins(admins) => config.freeze_admins = admins.clone(),
+            ConfigChange::ComplianceAuthority(authority) => config.compliance_authority = *authority,
+            ConfigChange::KycRequirement { threshold, min_level } => {
+                config.kyc_threshold = *threshold;
+                config.kyc_min_level = *min_level;
+            }
+            ConfigChange::TimelockDelay(delay) => config.timelock_delay = *delay,
+        }
+
+        emit!(ConfigChangeExecuted {
+            change_id: pending.change_id,
+            change: pending.change.clone(),
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    /// Admin or a freeze admin drops a queued change before it is executed, as the escrow
+    /// program's admin or guardian can
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let canceller = ctx.accounts.canceller.key();
+        require!(
+            canceller == config.admin || config.freeze_admins.contains(&canceller),
+            ErrorCode::UnauthorizedAdmin
+        );
+
+        let pending = &ctx.accounts.pending_change;
+        emit!(ConfigChangeCancelled {
+            change_id: pending.change_id,
+            change: pending.change.clone(),
+            canceller,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Put a wallet on the sanctions blocklist; payments to or from it fail in both programs
+    pub fn add_sanctioned_wallet(ctx: Context<AddSanctionedWallet>, wallet: Pubkey) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let entry = &mut ctx.accounts.sanction_entry;
+        entry.version = SanctionEntry::VERSION;
+        entry.wallet = wallet;
+        entry.added_by = ctx.accounts.compliance_authority.key();
+        entry.added_at = now;
+        entry.rent_payer = ctx.accounts.payer.key();
+        entry.reserved = [0; 32];
+
+        emit!(SanctionAdded {
+            wallet,
+            compliance_authority: entry.added_by,
+            timestamp: now,
+        });
+
+        msg!("Wallet {} added to the sanctions blocklist", wallet);
+        Ok(())
+    }
+
+    /// Take a wallet off the sanctions blocklist; closing the entry refunds its rent
+    pub fn remove_sanctioned_wallet(ctx: Context<RemoveSanctionedWallet>) -> Result<()> {
+        let entry = &ctx.accounts.sanction_entry;
+        emit!(SanctionRemoved {
+            wallet: entry.wallet,
+            compliance_authority: ctx.accounts.compliance_authority.key(),
+            added_at: entry.added_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Wallet {} removed from the sanctions blocklist", entry.wallet);
+        Ok(())
+    }
+
+    /// Record that `wallet` passed identity verification at `level` until `expires_at`; issuing
+    /// again for the same wallet renews the attestation
+    pub fn issue_kyc_attestation(
+        ctx: Context<IssueKycAttestation>,
+        wallet: Pubkey,
+        level: u8,
+        expires_at: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(level > 0 && expires_at > now, ErrorCode::InvalidKycAttestation);
+
+        let attestation = &mut ctx.accounts.kyc_attestation;
+        attestation.version = KycAttestation::VERSION;
+        attestation.wallet = wallet;
+        attestation.level = level;
+        attestation.expires_at = expires_at;
+        attestation.issued_by = ctx.accounts.compliance_authority.key();
+        attestation.issued_at = now;
+        // Renewals keep the rent with whoever paid it first
+        if attestation.rent_payer == Pubkey::default() {
+            attestation.rent_payer = ctx.accounts.payer.key();
+        }
+        attestation.reserved = [0; 32];
+
+        emit!(KycAttested {
+            wallet,
+            level,
+            expires_at,
+            compliance_authority: attestation.issued_by,
+            timestamp: now,
+        });
+
+        msg!("KYC level {} attested for wallet {}", level, wallet);
+        Ok(())
+    }
+
+    /// Withdraw a wallet's attestation before it expires; closing it refunds its rent
+    pub fn revoke_kyc_attestation(ctx: Context<RevokeKycAttestation>) -> Result<()> {
+        let attestation = &ctx.accounts.kyc_attestation;
+        emit!(KycRevoked {
+            wallet: attestation.wallet,
+            level: attestation.level,
+            compliance_authority: ctx.accounts.compliance_authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("KYC attestation revoked for wallet {}", attestation.wallet);
         Ok(())
     }
 }
 
+pub const MAX_FREEZE_ADMINS: usize = 5;
+/// A zero delay would let the admin apply changes before anyone can cancel them
+pub const MIN_TIMELOCK_DELAY: i64 = 24 * 60 * 60;
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
 #[derive(Accounts)]
-#[instruction(contract_id: String)]
+#[instruction(contract_id: [u8; 32])]
 pub struct CreateAuth<'info> {
     #[account(
         init,
-        payer = client,
-        space = PaymentAuthorization::LEN,
-        seeds = [b"payment_auth", client.key().as_ref(), contract_id.as_bytes()],
+        payer = payer,
+        space = 8 + PaymentAuthorization::INIT_SPACE,
+        seeds = [b"payment_auth", client.key().as_ref(), contract_id.as_ref()],
         bump
     )]
     pub payment_authorization: Account<'info, PaymentAuthorization>,
     
-    #[account(mut)]
     pub client: Signer<'info>,
+
+    /// Pays the rent; the client or a sponsoring platform
+    #[account(mut)]
+    pub payer: Signer<'info>,
     
     /// CHECK: Freelancer public key, verified in instruction
     pub freelancer: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, AuthConfig>,
+
+    /// CHECK: KYC attestation of the client; only read above the config's threshold
+    #[account(seeds = [b"kyc", client.key().as_ref()], bump)]
+    pub client_kyc: UncheckedAccount<'info>,
+
+    /// CHECK: KYC attestation of the freelancer; only read above the config's threshold
+    #[account(seeds = [b"kyc", freelancer.key().as_ref()], bump)]
+    pub freelancer_kyc: UncheckedAccount<'info>,
     
     pub system_program: Program<'info, System>,
 }
@@ -154,7 +654,7 @@ pub struct CreateAuth<'info> {
 pub struct ProcessPayment<'info> {
     #[account(
         mut,
-        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.contract_id.as_bytes()],
+        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.id_seed_bytes()],
         bump = payment_authorization.bump
     )]
     pub payment_authorization: Account<'info, PaymentAuthorization>,
@@ -170,6 +670,58 @@ pub struct ProcessPayment<'info> {
     
     #[account(mut)]
     pub freelancer_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: blocklist entry of the client; must not exist
+    #[account(seeds = [b"sanctioned", client.key().as_ref()], bump)]
+    pub client_sanction: UncheckedAccount<'info>,
+
+    /// CHECK: blocklist entry of the wallet that owns the receiving token account; must not exist
+    #[account(seeds = [b"sanctioned", freelancer_token_account.owner.as_ref()], bump)]
+    pub recipient_sanction: UncheckedAccount<'info>,
+
+    /// Registered co-signer; needed only above the authorization's co-sign threshold
+    pub co_signer: Option<Signer<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct LinkEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.id_seed_bytes()],
+        bump = payment_authorization.bump
+    )]
+    pub payment_authorization: Account<'info, PaymentAuthorization>,
+    
+    #[account(mut)]
+    pub client: Signer<'info>,
+    
+    #[account(mut, constraint = client_token_account.owner == client.key() @ ErrorCode::UnauthorizedClient)]
+    pub client_token_account: Account<'info, TokenAccount>,
+    
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"payment_auth", payment_authorization.client.as_ref(), payment_authorization.id_seed_bytes()],
+        bump = payment_authorization.bump
+    )]
+    pub payment_authorization: Account<'info, PaymentAuthorization>,
+    
+    /// Escrow PDA that owns the linked escrow token account; only its program can sign for it
+    pub escrow_authority: Signer<'info>,
+    
+    #[account(mut, constraint = client_token_account.owner == payment_authorization.client @ ErrorCode::UnauthorizedClient)]
+    pub client_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub escrow_token_account: Account<'info, TokenAccount>,
     
     pub token_program: Program<'info, Token>,
 }
@@ -178,7 +730,7 @@ pub struct ProcessPayment<'info> {
 pub struct RevokeAuth<'info> {
     #[account(
         mut,
-        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.contract_id.as_bytes()],
+        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.id_seed_bytes()],
         bump = payment_authorization.bump
     )]
     pub payment_authorization: Account<'info, PaymentAuthorization>,
@@ -191,52 +743,613 @@ pub struct RevokeAuth<'info> {
 pub struct UpdateAuth<'info> {
     #[account(
         mut,
-        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.contract_id.as_bytes()],
+        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.id_seed_bytes()],
         bump = payment_authorization.bump
     )]
     pub payment_authorization: Account<'info, PaymentAuthorization>,
     
     #[account(mut)]
     pub client: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, AuthConfig>,
+
+    /// CHECK: KYC attestation of the client; only read when a top-up crosses the config's threshold
+    #[account(seeds = [b"kyc", client.key().as_ref()], bump)]
+    pub client_kyc: UncheckedAccount<'info>,
+
+    /// CHECK: KYC attestation of the freelancer; only read when a top-up crosses the config's threshold
+    #[account(seeds = [b"kyc", payment_authorization.freelancer.as_ref()], bump)]
+    pub freelancer_kyc: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
 pub struct FreezeAuth<'info> {
     #[account(
         mut,
-        seeds = [b"payment_auth", payment_authorization.client.as_ref(), payment_authorization.contract_id.as_bytes()],
+        seeds = [b"payment_auth", payment_authorization.client.as_ref(), payment_authorization.id_seed_bytes()],
         bump = payment_authorization.bump
     )]
     pub payment_authorization: Account<'info, PaymentAuthorization>,
     
-    /// CHECK: Admin authority, would verify against known admin keys in production
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, AuthConfig>,
+
+    /// Must be one of the config's freeze admins
     pub admin: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct SetCoSigner<'info> {
+    #[account(
+        mut,
+        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.id_seed_bytes()],
+        bump = payment_authorization.bump
+    )]
+    pub payment_authorization: Account<'info, PaymentAuthorization>,
+
+    pub client: Signer<'info>,
+
+    /// Signs so a mistyped key cannot lock payments
+    pub co_signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCoSigner<'info> {
+    #[account(
+        mut,
+        seeds = [b"payment_auth", client.key().as_ref(), payment_authorization.id_seed_bytes()],
+        bump = payment_authorization.bump
+    )]
+    pub payment_authorization: Account<'info, PaymentAuthorization>,
+
+    pub client: Signer<'info>,
+
+    /// Must be the currently registered co-signer
+    pub co_signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuth<'info> {
+    /// CHECK: legacy layout is validated by discriminator and size in the instruction
+    #[account(mut, owner = crate::ID)]
+    pub payment_authorization: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AuthConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Only whoever can upgrade the program may set up its config
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(authority.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueConfigChange<'info> {
+    #[account(mut, seeds = [b"config"], bump, has_one = admin @ ErrorCode::UnauthorizedAdmin)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PendingConfigChange::INIT_SPACE,
+        seeds = [b"config_change", config.change_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+
+    pub admin: Signer<'info>,
+
+    /// Pays the rent and gets it back when the change is executed or cancelled
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"config_change", pending_change.change_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+
+    /// CHECK: receives the pending change's rent; checked by `has_one`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// Permissionless; the caller only pays the transaction fee
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"config_change", pending_change.change_id.to_le_bytes().as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub pending_change: Account<'info, PendingConfigChange>,
+
+    /// CHECK: receives the pending change's rent; checked by `has_one`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    pub canceller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddSanctionedWallet<'info> {
+    #[account(seeds = [b"config"], bump, has_one = compliance_authority @ ErrorCode::NotComplianceAuthority)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + SanctionEntry::INIT_SPACE,
+        seeds = [b"sanctioned", wallet.as_ref()],
+        bump
+    )]
+    pub sanction_entry: Account<'info, SanctionEntry>,
+
+    pub compliance_authority: Signer<'info>,
+
+    /// Pays the rent and gets it back when the account is closed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveSanctionedWallet<'info> {
+    #[account(seeds = [b"config"], bump, has_one = compliance_authority @ ErrorCode::NotComplianceAuthority)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"sanctioned", sanction_entry.wallet.as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub sanction_entry: Account<'info, SanctionEntry>,
+
+    pub compliance_authority: Signer<'info>,
+
+    /// CHECK: receives the entry's rent; checked by `has_one`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct IssueKycAttestation<'info> {
+    #[account(seeds = [b"config"], bump, has_one = compliance_authority @ ErrorCode::NotComplianceAuthority)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + KycAttestation::INIT_SPACE,
+        seeds = [b"kyc", wallet.as_ref()],
+        bump
+    )]
+    pub kyc_attestation: Account<'info, KycAttestation>,
+
+    pub compliance_authority: Signer<'info>,
+
+    /// Pays the rent and gets it back when the account is closed
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeKycAttestation<'info> {
+    #[account(seeds = [b"config"], bump, has_one = compliance_authority @ ErrorCode::NotComplianceAuthority)]
+    pub config: Account<'info, AuthConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"kyc", kyc_attestation.wallet.as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub kyc_attestation: Account<'info, KycAttestation>,
+
+    pub compliance_authority: Signer<'info>,
+
+    /// CHECK: receives the attestation's rent; checked by `has_one`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+/// Program-wide settings, changed only through the timelock
 #[account]
+#[derive(InitSpace)]
+pub struct AuthConfig {
+    pub version: u8,
+    /// Key allowed to queue and cancel config changes
+    pub admin: Pubkey,
+    /// Keys allowed to freeze authorizations and cancel queued config changes
+    #[max_len(MAX_FREEZE_ADMINS)]
+    pub freeze_admins: Vec<Pubkey>,
+    /// Key allowed to add and remove sanctions blocklist entries
+    pub compliance_authority: Pubkey,
+    /// Seconds between queueing a change and when it can be executed
+    pub timelock_delay: i64,
+    /// Id of the next queued change
+    pub change_count: u64,
+    /// Authorizations above this total need KYC attestations; 0 disables the check
+    pub kyc_threshold: u64,
+    /// Minimum attestation level accepted above the threshold
+    pub kyc_min_level: u8,
+    /// Room for new fields without a realloc
+    pub reserved: [u8; 23],
+}
+
+impl AuthConfig {
+    pub const VERSION: u8 = 1;
+
+    /// Authorizations totalling more than the KYC threshold need every participant's `attestations`
+    /// to be current and at least the minimum level
+    pub fn require_kyc(&self, total_authorized: u64, attestations: &[&AccountInfo], now: i64) -> Result<()> {
+        if self.kyc_threshold == 0 || total_authorized <= self.kyc_threshold {
+            return Ok(());
+        }
+        for attestation in attestations {
+            let attested = matches!(
+                KycAttestation::load(attestation),
+                Some(kyc) if kyc.is_valid(self.kyc_min_level, now)
+            );
+            require!(attested, ErrorCode::KycRequired);
+        }
+        Ok(())
+    }
+}
+
+/// A config change waiting out the timelock
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfigChange {
+    pub version: u8,
+    pub change_id: u64,
+    pub change: ConfigChange,
+    /// Admin who queued the change
+    pub proposer: Pubkey,
+    /// Paid the rent; gets it back when the change is executed or cancelled
+    pub rent_payer: Pubkey,
+    pub queued_at: i64,
+    /// Earliest time the change can be executed
+    pub eta: i64,
+    /// Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl PendingConfigChange {
+    pub const VERSION: u8 = 1;
+}
+
+/// Config parameters that can only change through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ConfigChange {
+    Admin(Pubkey),
+    FreezeAdmins(#[max_len(MAX_FREEZE_ADMINS)] Vec<Pubkey>),
+    ComplianceAuthority(Pubkey),
+    TimelockDelay(i64),
+    KycRequirement { threshold: u64, min_level: u8 },
+}
+
+/// Identity verification of a wallet, one account per wallet at `[b"kyc", wallet]`.
+/// Read by both programs when an amount is above their KYC threshold.
+#[account]
+#[derive(InitSpace)]
+pub struct KycAttestation {
+    pub version: u8,
+    pub wallet: Pubkey,
+    /// Verification tier; higher levels cover larger volumes
+    pub level: u8,
+    pub expires_at: i64,
+    /// Compliance authority that issued the attestation
+    pub issued_by: Pubkey,
+    pub issued_at: i64,
+    /// Paid the rent; gets it back when the attestation is revoked
+    pub rent_payer: Pubkey,
+    /// Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl KycAttestation {
+    pub const VERSION: u8 = 1;
+
+    /// Decode the attestation at `info`, or `None` if none has been issued there
+    pub fn load(info: &AccountInfo) -> Option<Self> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return None;
+        }
+        let data = info.try_borrow_data().ok()?;
+        Self::try_deserialize(&mut &data[..]).ok()
+    }
+
+    /// Whether the attestation is at least `min_level` and has not expired at `now`
+    pub fn is_valid(&self, min_level: u8, now: i64) -> bool {
+        self.level >= min_level && now < self.expires_at
+    }
+}
+
+/// A wallet on the sanctions blocklist, one account per wallet at `[b"sanctioned", wallet]`.
+/// Payments check that the entry does not exist, so both programs can read the list.
+#[account]
+#[derive(InitSpace)]
+pub struct SanctionEntry {
+    pub version: u8,
+    pub wallet: Pubkey,
+    /// Compliance authority that flagged the wallet
+    pub added_by: Pubkey,
+    pub added_at: i64,
+    /// Paid the rent; gets it back when the wallet is removed
+    pub rent_payer: Pubkey,
+    /// Room for new fields without a realloc
+    pub reserved: [u8; 32],
+}
+
+impl SanctionEntry {
+    pub const VERSION: u8 = 1;
+}
+
+#[account]
+#[derive(InitSpace)]
 pub struct PaymentAuthorization {
+    pub version: u8,
     pub client: Pubkey,
-    pub contract_id: String,
+    /// Backend UUID as 32 lowercase hex digits, the same id as the escrow contract
+    pub contract_id: [u8; 32],
     pub freelancer: Pubkey,
     pub max_per_milestone: u64,
     pub total_authorized: u64,
     pub total_spent: u64,
+    pub escrow_token_account: Pubkey,
     pub is_active: bool,
     pub authorized_at: i64,
     pub bump: u8,
+    /// Second key required on payments taking `total_spent` above `co_sign_threshold`; default when none is registered
+    pub co_signer: Pubkey,
+    pub co_sign_threshold: u64,
+    /// Seeds the address: the contract id, or a legacy id of another length zero-padded
+    pub id_seed: [u8; 32],
+    /// Length of a legacy id shorter than 32 bytes held in `id_seed`; 0 when all 32 bytes are the seed
+    pub id_seed_len: u8,
+    /// Room for new fields without a realloc
+    pub reserved: [u8; 23],
 }
 
 impl PaymentAuthorization {
-    pub const LEN: usize = 8 + // discriminator
-        32 + // client
-        4 + 64 + // contract_id (string with length prefix)
-        32 + // freelancer
-        8 + // max_per_milestone
-        8 + // total_authorized
-        8 + // total_spent
-        1 + // is_active
-        8 + // authorized_at
-        1; // bump
+    pub const VERSION: u8 = 1;
+
+    /// Contract ids are validated as hex on creation, so they always read back as text
+    pub fn contract_id_str(&self) -> &str {
+        std::str::from_utf8(&self.contract_id).unwrap_or_default()
+    }
+
+    /// Seed of the authorization's address, without the padding of a short legacy id
+    pub fn id_seed_bytes(&self) -> &[u8] {
+        match self.id_seed_len {
+            0 => &self.id_seed,
+            len => &self.id_seed[..len as usize],
+        }
+    }
+
+    /// Id a legacy contract is known by once migrated: its own id when that is 32 bytes long,
+    /// otherwise the first half of the id's SHA-256 as lowercase hex, like the ids of new contracts
+    pub fn migrated_contract_id(legacy_id: &str) -> [u8; 32] {
+        if let Ok(contract_id) = legacy_id.as_bytes().try_into() {
+            return contract_id;
+        }
+        const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let digest = hash::hash(legacy_id.as_bytes()).to_bytes();
+        let mut contract_id = [0; 32];
+        for (i, byte) in digest[..16].iter().enumerate() {
+            contract_id[2 * i] = HEX_DIGITS[(byte >> 4) as usize];
+            contract_id[2 * i + 1] = HEX_DIGITS[(byte & 0x0f) as usize];
+        }
+        contract_id
+    }
+}
+
+/// Layout from before accounts carried a version, read only by `migrate_authorization`
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct PaymentAuthorizationV0 {
+    pub client: Pubkey,
+    #[max_len(64)]
+    pub contract_id: String,
+    pub freelancer: Pubkey,
+    pub max_per_milestone: u64,
+    pub total_authorized: u64,
+    pub total_spent: u64,
+    pub is_active: bool,
+    pub authorized_at: i64,
+    pub bump: u8,
+}
+
+/// Emitted when a client creates a payment authorization
+#[event]
+pub struct AuthorizationCreated {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub freelancer: Pubkey,
+    pub max_per_milestone: u64,
+    pub total_authorized: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a milestone is paid directly to the freelancer
+#[event]
+pub struct MilestonePaymentProcessed {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub milestone_id: String,
+    pub amount: u64,
+    pub total_spent: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when an authorization is linked to a freelance escrow
+#[event]
+pub struct EscrowLinked {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub escrow_token_account: Pubkey,
+    pub allowance: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the linked escrow pulls milestone funding
+#[event]
+pub struct EscrowFunded {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub milestone_id: String,
+    pub amount: u64,
+    pub total_spent: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a client changes the authorization limits
+#[event]
+pub struct AuthorizationUpdated {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub max_per_milestone: u64,
+    pub total_authorized: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a client registers or replaces the co-signer of an authorization
+#[event]
+pub struct AuthorizationCoSignerUpdated {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub co_signer: Pubkey,
+    pub threshold: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a client revokes an authorization
+#[event]
+pub struct AuthorizationRevoked {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Emitted when an admin freezes an authorization
+#[event]
+pub struct AuthorizationFrozen {
+    pub client: Pubkey,
+    pub contract_id: [u8; 32],
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the admin queues a config change
+#[event]
+pub struct ConfigChangeQueued {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a queued config change is applied
+#[event]
+pub struct ConfigChangeExecuted {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub timestamp: i64,
+}
+
+/// Emitted when the admin cancels a queued config change
+#[event]
+pub struct ConfigChangeCancelled {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub canceller: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the compliance authority blocklists a wallet
+#[event]
+pub struct SanctionAdded {
+    pub wallet: Pubkey,
+    pub compliance_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the compliance authority lifts a wallet's blocklist entry
+#[event]
+pub struct SanctionRemoved {
+    pub wallet: Pubkey,
+    pub compliance_authority: Pubkey,
+    pub added_at: i64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the compliance authority issues or renews a KYC attestation
+#[event]
+pub struct KycAttested {
+    pub wallet: Pubkey,
+    pub level: u8,
+    pub expires_at: i64,
+    pub compliance_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when the compliance authority revokes a KYC attestation
+#[event]
+pub struct KycRevoked {
+    pub wallet: Pubkey,
+    pub level: u8,
+    pub compliance_authority: Pubkey,
+    pub timestamp: i64,
 }
 
 #[error_code]
@@ -253,4 +1366,38 @@ pub enum ErrorCode {
     UnauthorizedClient,
     #[msg("Unauthorized freelancer")]
     UnauthorizedFreelancer,
+    #[msg("Escrow is not linked to this authorization")]
+    EscrowNotLinked,
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Account data does not match any known layout")]
+    UnknownAccountLayout,
+    #[msg("Contract id must be 32 lowercase hex digits")]
+    InvalidContractId,
+    #[msg("Signer is not an authorized admin")]
+    UnauthorizedAdmin,
+    #[msg("Signer is not the program upgrade authority")]
+    NotUpgradeAuthority,
+    #[msg("Too many freeze admins")]
+    TooManyFreezeAdmins,
+    #[msg("Timelock delay must be between one and 30 days")]
+    InvalidTimelockDelay,
+    #[msg("Config change is still in its timelock window")]
+    TimelockNotElapsed,
+    #[msg("Signer is not the compliance authority")]
+    NotComplianceAuthority,
+    #[msg("A party to this payment is on the sanctions blocklist")]
+    SanctionedParty,
+    #[msg("Amount is above the KYC threshold and a participant has no valid attestation")]
+    KycRequired,
+    #[msg("KYC attestation needs a non-zero level and a future expiry")]
+    InvalidKycAttestation,
+    #[msg("Co-signer must be a different key than the client")]
+    InvalidCoSigner,
+    #[msg("Authorization already has a co-signer")]
+    CoSignerAlreadySet,
+    #[msg("Signer is not the registered co-signer")]
+    UnauthorizedCoSigner,
+    #[msg("Payment is above the co-signer threshold and the co-signer did not sign")]
+    CoSignerRequired,
 }
\ No newline at end of file